@@ -0,0 +1,791 @@
+//! Generates rust types and request builders from the vm service protocol
+//! specification.
+//!
+//! Usage: cargo run --bin gen_protocol -- protocol/service.md src/protocol.rs
+//!
+//! The output is meant to be formatted with rustfmt afterwards.
+//!
+//! The specification lives in the dart sdk at runtime/vm/service/service.md,
+//! protocol/service.md holds a copy of it.
+//! Every class, enum and method is described there in a fenced code block
+//! using a small dart-like syntax, which is all this generator looks at.
+use std::collections::HashSet;
+use std::env;
+use std::fmt::Write;
+use std::fs;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 3 {
+        eprintln!("usage: gen_protocol <service.md> <output.rs>");
+        std::process::exit(1);
+    }
+    let spec = fs::read_to_string(&args[1]).expect("Failed to read service.md");
+    let protocol = Protocol::parse(&spec);
+    fs::write(&args[2], protocol.generate()).expect("Failed to write output");
+    println!(
+        "Generated {} types, {} enums and {} methods for protocol {}.{}",
+        protocol.classes.len(),
+        protocol.enums.len(),
+        protocol.methods.len(),
+        protocol.major,
+        protocol.minor
+    );
+}
+
+struct Protocol {
+    major: i64,
+    minor: i64,
+    classes: Vec<Class>,
+    enums: Vec<Enum>,
+    methods: Vec<Method>,
+}
+
+struct Class {
+    name: String,
+    parent: Option<String>,
+    docs: Vec<String>,
+    fields: Vec<Field>,
+}
+
+struct Field {
+    name: String,
+    kind: String,
+    optional: bool,
+    docs: Vec<String>,
+}
+
+struct Enum {
+    name: String,
+    docs: Vec<String>,
+    variants: Vec<(String, Vec<String>)>,
+}
+
+/// A definition waiting for the prose which follows its code block.
+#[derive(Copy, Clone)]
+enum Pending {
+    Class(usize),
+    Enum(usize),
+    Method(usize),
+}
+
+struct Method {
+    name: String,
+    returns: String,
+    docs: Vec<String>,
+    params: Vec<Field>,
+}
+
+impl Protocol {
+    fn parse(spec: &str) -> Self {
+        let mut protocol = Protocol {
+            major: 0,
+            minor: 0,
+            classes: Vec::new(),
+            enums: Vec::new(),
+            methods: Vec::new(),
+        };
+        let mut in_block = false;
+        let mut block = String::new();
+        // The prose paragraph directly after a code block describes it.
+        let mut pending: Option<Pending> = None;
+        let mut prose: Vec<String> = Vec::new();
+        for line in spec.lines() {
+            if line.starts_with("# Dart VM Service Protocol ") && protocol.major == 0 {
+                let version = line.split_at("# Dart VM Service Protocol ".len()).1;
+                let mut parts = version.trim().split('.');
+                protocol.major = parts.next().and_then(|x| x.parse().ok()).unwrap_or(0);
+                protocol.minor = parts.next().and_then(|x| x.parse().ok()).unwrap_or(0);
+                continue;
+            }
+            if line.trim_start().starts_with("```") {
+                if in_block {
+                    protocol.flush_docs(pending.take(), &mut prose);
+                    pending = protocol.parse_block(&block);
+                    block.clear();
+                } else {
+                    protocol.flush_docs(pending.take(), &mut prose);
+                }
+                in_block = !in_block;
+                continue;
+            }
+            if in_block {
+                block.push_str(line);
+                block.push('\n');
+            } else if line.starts_with('#') {
+                protocol.flush_docs(pending.take(), &mut prose);
+            } else if pending.is_some() {
+                prose.push(line.to_string());
+            }
+        }
+        protocol.flush_docs(pending, &mut prose);
+        protocol
+    }
+
+    /// Attach the first paragraph of prose to the definition which preceded it.
+    fn flush_docs(&mut self, pending: Option<Pending>, prose: &mut Vec<String>) {
+        let lines: Vec<String> = prose
+            .iter()
+            .map(|x| x.trim().to_string())
+            .skip_while(|x| x.is_empty())
+            .take_while(|x| !x.is_empty())
+            .collect();
+        prose.clear();
+        let docs = match pending {
+            Some(Pending::Class(index)) => &mut self.classes[index].docs,
+            Some(Pending::Enum(index)) => &mut self.enums[index].docs,
+            Some(Pending::Method(index)) => &mut self.methods[index].docs,
+            None => return,
+        };
+        if docs.is_empty() {
+            *docs = lines;
+        }
+    }
+
+    /// Parse a single fenced code block, returning the definition which
+    /// should receive the following prose, if any.
+    fn parse_block(&mut self, block: &str) -> Option<Pending> {
+        let trimmed = block.trim();
+        if trimmed.starts_with("class ") {
+            self.classes.push(parse_class(trimmed));
+            Some(Pending::Class(self.classes.len() - 1))
+        } else if trimmed.starts_with("enum ") {
+            self.enums.push(parse_enum(trimmed));
+            Some(Pending::Enum(self.enums.len() - 1))
+        } else if let Some(method) = parse_method(trimmed) {
+            self.methods.push(method);
+            Some(Pending::Method(self.methods.len() - 1))
+        } else {
+            None
+        }
+    }
+
+    fn generate(&self) -> String {
+        // Classes which others extend, such as `@Object`, stand for any of
+        // their subclasses, and classes without fields are arbitrary maps.
+        // Both are left as json values.
+        let parents: HashSet<&str> = self
+            .classes
+            .iter()
+            .filter_map(|x| x.parent.as_ref().map(|x| x.as_str()))
+            .collect();
+        let typed: Vec<&Class> = self
+            .classes
+            .iter()
+            .filter(|x| !parents.contains(x.name.as_str()))
+            .filter(|x| x.parent.is_some() || !x.fields.is_empty())
+            .collect();
+        let known: HashSet<String> = typed
+            .iter()
+            .map(|x| rust_type_name(&x.name))
+            .chain(self.enums.iter().map(|x| x.name.clone()))
+            .collect();
+        let enums: HashSet<&str> = self.enums.iter().map(|x| x.name.as_str()).collect();
+        let mut out = String::new();
+        out.push_str("// Generated by gen_protocol from the vm service protocol specification.\n");
+        out.push_str("// Do not edit by hand, regenerate with:\n");
+        out.push_str("//     cargo run --bin gen_protocol -- protocol/service.md src/protocol.rs\n\n");
+        out.push_str("/// The major version of the protocol these definitions target.\n");
+        writeln!(out, "pub const PROTOCOL_MAJOR_VERSION: i64 = {};\n", self.major).unwrap();
+        out.push_str("/// The minor version of the protocol these definitions target.\n");
+        writeln!(out, "pub const PROTOCOL_MINOR_VERSION: i64 = {};\n", self.minor).unwrap();
+
+        for item in self.enums.iter() {
+            write_docs(&mut out, "", &item.docs);
+            out.push_str("#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]\n");
+            writeln!(out, "pub enum {} {{", item.name).unwrap();
+            for (variant, docs) in item.variants.iter() {
+                write_docs(&mut out, "    ", docs);
+                writeln!(out, "    {},", variant).unwrap();
+            }
+            // Newer vms may send values added after this version.
+            out.push_str("    /// A value added in a later version of the protocol.\n");
+            out.push_str("    #[serde(other)]\n");
+            out.push_str("    Unknown,\n");
+            out.push_str("}\n\n");
+        }
+
+        for class in typed.iter() {
+            let name = rust_type_name(&class.name);
+            write_docs(&mut out, "", &class.docs);
+            out.push_str("#[derive(Serialize, Deserialize, Debug, Clone)]\n");
+            writeln!(out, "pub struct {} {{", name).unwrap();
+            let mut fields: Vec<&Field> = Vec::new();
+            self.collect_fields(class, &mut fields);
+            for (index, field) in fields.iter().enumerate() {
+                if index > 0 {
+                    out.push('\n');
+                }
+                let mut kind = rust_type(&field.kind, &known);
+                if kind == name {
+                    kind = format!("Box<{}>", kind);
+                }
+                write_docs(&mut out, "    ", &field.docs);
+                let (ident, rename) = rust_field_name(&field.name);
+                if rename {
+                    writeln!(out, "    #[serde(rename = \"{}\")]", field.name).unwrap();
+                }
+                if field.optional {
+                    out.push_str("    #[serde(default, skip_serializing_if = \"Option::is_none\")]\n");
+                    writeln!(out, "    pub {}: Option<{}>,", ident, kind).unwrap();
+                } else {
+                    writeln!(out, "    pub {}: {},", ident, kind).unwrap();
+                }
+            }
+            out.push_str("}\n\n");
+        }
+
+        out.push_str("/// A request ready to be serialized and sent to the vm service.\n");
+        out.push_str("#[derive(Serialize, Debug)]\n");
+        out.push_str("pub struct Request {\n");
+        out.push_str("    pub jsonrpc: &'static str,\n");
+        out.push_str("    pub id: i32,\n");
+        out.push_str("    pub method: &'static str,\n");
+        out.push_str("    pub params: serde_json::Map<String, serde_json::Value>,\n");
+        out.push_str("}\n\n");
+        out.push_str("/// Builds requests for every method in the protocol.\n");
+        out.push_str("///\n");
+        out.push_str("/// Clones share the id counter, so ids stay unique to a connection.\n");
+        out.push_str("#[derive(Clone)]\n");
+        out.push_str("pub struct Requests {\n");
+        out.push_str("    id: std::sync::Arc<std::sync::atomic::AtomicI32>,\n");
+        out.push_str("}\n\n");
+        out.push_str("impl Default for Requests {\n");
+        out.push_str("    fn default() -> Self {\n");
+        out.push_str("        Requests {\n");
+        out.push_str("            id: std::sync::Arc::new(std::sync::atomic::AtomicI32::new(0)),\n");
+        out.push_str("        }\n    }\n}\n\n");
+        out.push_str("impl Requests {\n");
+        out.push_str("    pub fn new() -> Self {\n        Requests::default()\n    }\n\n");
+        for method in self.methods.iter() {
+            write_docs(&mut out, "    ", &method.docs);
+            if !method.docs.is_empty() {
+                out.push_str("    ///\n");
+            }
+            writeln!(out, "    /// Responds with `{}`.", method.returns).unwrap();
+            // Methods mirror the protocol's parameters, however many there are.
+            if method.params.len() >= 7 {
+                out.push_str("    #[allow(clippy::too_many_arguments)]\n");
+            }
+            let mut signature = String::new();
+            for param in method.params.iter() {
+                let (ident, _) = rust_field_name(&param.name);
+                let kind = rust_param_type(&param.kind, &enums);
+                if param.optional {
+                    write!(signature, ", {}: Option<{}>", ident, kind).unwrap();
+                } else {
+                    write!(signature, ", {}: {}", ident, kind).unwrap();
+                }
+            }
+            writeln!(
+                out,
+                "    pub fn {}(&self{}) -> Request {{",
+                snake_case(&method.name),
+                signature
+            )
+            .unwrap();
+            let mutable = if method.params.is_empty() { "" } else { "mut " };
+            writeln!(out, "        let {}params = serde_json::Map::new();", mutable).unwrap();
+            for param in method.params.iter() {
+                let (ident, _) = rust_field_name(&param.name);
+                if param.optional {
+                    writeln!(out, "        if let Some({}) = {} {{", ident, ident).unwrap();
+                    writeln!(
+                        out,
+                        "            params.insert(\"{}\".to_string(), serde_json::to_value({}).unwrap());",
+                        param.name, ident
+                    )
+                    .unwrap();
+                    out.push_str("        }\n");
+                } else {
+                    writeln!(
+                        out,
+                        "        params.insert(\"{}\".to_string(), serde_json::to_value({}).unwrap());",
+                        param.name, ident
+                    )
+                    .unwrap();
+                }
+            }
+            out.push_str("        Request {\n");
+            out.push_str("            jsonrpc: \"2.0\",\n");
+            out.push_str("            id: self.next_id(),\n");
+            writeln!(out, "            method: \"{}\",", method.name).unwrap();
+            out.push_str("            params,\n");
+            out.push_str("        }\n    }\n\n");
+        }
+        out.push_str("    pub fn next_id(&self) -> i32 {\n");
+        out.push_str("        self.id.fetch_add(1, std::sync::atomic::Ordering::SeqCst)\n");
+        out.push_str("    }\n}\n");
+        out
+    }
+
+    /// Collect the fields of `class` including those inherited from its
+    /// parents. A field declared again replaces the inherited one in place.
+    fn collect_fields<'a>(&'a self, class: &'a Class, fields: &mut Vec<&'a Field>) {
+        if let Some(parent) = class.parent.as_ref() {
+            if let Some(parent) = self.classes.iter().find(|x| &x.name == parent) {
+                self.collect_fields(parent, fields);
+            }
+        }
+        for field in class.fields.iter() {
+            match fields.iter().position(|x| x.name == field.name) {
+                Some(index) => fields[index] = field,
+                None => fields.push(field),
+            }
+        }
+    }
+}
+
+fn parse_class(block: &str) -> Class {
+    let mut lines = block.lines();
+    let header: Vec<&str> = lines
+        .next()
+        .unwrap()
+        .trim_end_matches('{')
+        .split_whitespace()
+        .collect();
+    let parent = match header.iter().position(|x| *x == "extends") {
+        Some(index) => header.get(index + 1).map(|x| x.to_string()),
+        None => None,
+    };
+    let mut class = Class {
+        name: header[1].to_string(),
+        parent: parent,
+        docs: Vec::new(),
+        fields: Vec::new(),
+    };
+    let mut docs = Vec::new();
+    let mut statement = String::new();
+    for line in lines {
+        let line = line.trim();
+        if line.starts_with("//") {
+            docs.push(comment_text(line));
+            continue;
+        }
+        if line.is_empty() || line == "}" {
+            continue;
+        }
+        statement.push_str(line);
+        statement.push(' ');
+        if !line.ends_with(';') {
+            continue;
+        }
+        if let Some(mut field) = parse_declaration(statement.trim().trim_end_matches(';')) {
+            field.docs = docs.clone();
+            class.fields.push(field);
+        }
+        statement.clear();
+        docs.clear();
+    }
+    class
+}
+
+fn parse_enum(block: &str) -> Enum {
+    let mut lines = block.lines();
+    let header: Vec<&str> = lines.next().unwrap().split_whitespace().collect();
+    let mut item = Enum {
+        name: header[1].trim_end_matches('{').to_string(),
+        docs: Vec::new(),
+        variants: Vec::new(),
+    };
+    let mut docs = Vec::new();
+    for line in lines {
+        let line = line.trim();
+        if line.starts_with("//") {
+            docs.push(comment_text(line));
+            continue;
+        }
+        let variant = line.trim_end_matches(',').trim();
+        if variant.is_empty() || variant == "}" {
+            continue;
+        }
+        item.variants.push((variant.to_string(), docs.clone()));
+        docs.clear();
+    }
+    item
+}
+
+/// Parse a method signature of the form `ReturnType name(type param, ...)`.
+fn parse_method(block: &str) -> Option<Method> {
+    let signature: String = block
+        .lines()
+        .map(|x| x.trim())
+        .filter(|x| !x.starts_with("//"))
+        .collect::<Vec<&str>>()
+        .join(" ");
+    let open = signature.find('(')?;
+    if !signature.ends_with(')') {
+        return None;
+    }
+    let head: Vec<&str> = signature[..open].split_whitespace().collect();
+    if head.len() != 2 || !head[1].chars().all(|x| x.is_alphanumeric() || x == '_') {
+        return None;
+    }
+    let mut params = Vec::new();
+    for param in split_params(&signature[open + 1..signature.len() - 1]) {
+        params.push(parse_declaration(&param)?);
+    }
+    Some(Method {
+        name: head[1].to_string(),
+        returns: head[0].to_string(),
+        docs: Vec::new(),
+        params: params,
+    })
+}
+
+/// Parse `type name [optional]`.
+fn parse_declaration(text: &str) -> Option<Field> {
+    let optional = text.contains("[optional]");
+    let text = text.replace("[optional]", "");
+    let mut parts = text.split_whitespace().collect::<Vec<&str>>();
+    if parts.len() < 2 {
+        return None;
+    }
+    let name = parts.pop().unwrap().to_string();
+    Some(Field {
+        name: name,
+        kind: parts.join(""),
+        optional: optional,
+        docs: Vec::new(),
+    })
+}
+
+/// The text of a `//` comment, keeping the indentation of lists within it.
+fn comment_text(line: &str) -> String {
+    let text = line.trim_start_matches('/');
+    text.strip_prefix(' ')
+        .unwrap_or(text)
+        .trim_end()
+        .to_string()
+}
+
+/// Split a parameter list on commas that are not nested in type arguments.
+fn split_params(text: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut depth = 0;
+    let mut current = String::new();
+    for c in text.chars() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            ',' if depth == 0 => {
+                result.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.trim().is_empty() {
+        result.push(current.trim().to_string());
+    }
+    result
+}
+
+/// `@Isolate` is the reference form of `Isolate`.
+fn rust_type_name(name: &str) -> String {
+    match name.strip_prefix('@') {
+        Some(name) => format!("{}Ref", name),
+        None => name.to_string(),
+    }
+}
+
+fn rust_type(kind: &str, known: &HashSet<String>) -> String {
+    if kind.contains('|') {
+        return String::from("serde_json::Value");
+    }
+    if let Some(element) = kind.strip_suffix("[]") {
+        return format!("Vec<{}>", rust_type(element, known));
+    }
+    match kind {
+        "string" => String::from("String"),
+        "int" => String::from("i64"),
+        "double" => String::from("f64"),
+        "bool" => String::from("bool"),
+        _ => {
+            let name = rust_type_name(kind);
+            if known.contains(&name) {
+                name
+            } else {
+                String::from("serde_json::Value")
+            }
+        }
+    }
+}
+
+fn rust_param_type(kind: &str, enums: &HashSet<&str>) -> String {
+    if let Some(element) = kind.strip_suffix("[]") {
+        return format!("&[{}]", rust_param_type(element, enums));
+    }
+    match kind {
+        "string" => String::from("&str"),
+        "int" => String::from("i64"),
+        "double" => String::from("f64"),
+        "bool" => String::from("bool"),
+        _ if enums.contains(kind) => kind.to_string(),
+        _ => String::from("serde_json::Value"),
+    }
+}
+
+/// Returns the rust identifier for a protocol field and whether it needs a
+/// serde rename.
+fn rust_field_name(name: &str) -> (String, bool) {
+    const KEYWORDS: &[&str] = &[
+        "as", "break", "const", "continue", "crate", "else", "enum", "extern", "false", "fn",
+        "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+        "return", "self", "static", "struct", "super", "trait", "true", "type", "unsafe",
+        "use", "where", "while", "async", "await", "dyn", "abstract", "become", "box", "do",
+        "final", "macro", "override", "priv", "try", "typeof", "unsized", "virtual", "yield",
+    ];
+    let snake = snake_case(name.trim_start_matches('_'));
+    if KEYWORDS.contains(&snake.as_str()) {
+        (format!("{}_", snake), true)
+    } else {
+        let rename = snake != name;
+        (snake, rename)
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    let mut result = String::new();
+    let chars: Vec<char> = name.chars().collect();
+    for (index, c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            // Keep acronyms such as `VM` or `CPU` together.
+            let previous_lower = index > 0 && chars[index - 1].is_lowercase();
+            // A trailing `s` makes an acronym plural, as in `extensionRPCs`.
+            let plural = chars.get(index + 1) == Some(&'s') && index + 2 == chars.len();
+            let next_lower = !plural && chars.get(index + 1).map_or(false, |x| x.is_lowercase());
+            let previous_upper = index > 0 && chars[index - 1].is_uppercase();
+            if index > 0 && (previous_lower || (previous_upper && next_lower)) {
+                result.push('_');
+            }
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(*c);
+        }
+    }
+    result
+}
+
+fn write_docs(out: &mut String, indent: &str, docs: &[String]) {
+    for line in docs {
+        if line.is_empty() {
+            writeln!(out, "{}///", indent).unwrap();
+        } else {
+            writeln!(out, "{}/// {}", indent, line).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXCERPT: &str = "# Dart VM Service Protocol 3.14
+
+## Public RPCs
+
+### addBreakpoint
+
+```
+Breakpoint|Sentinel addBreakpoint(string isolateId,
+                                  string scriptId,
+                                  int line,
+                                  int column [optional])
+```
+
+The _addBreakpoint_ RPC is used to add a breakpoint at a specific line
+of some script.
+
+More prose.
+
+### getSourceReport
+
+```
+SourceReport getSourceReport(string isolateId,
+                             SourceReportKind[] reports,
+                             map<string,string> scope [optional])
+```
+
+## Public Types
+
+### Response
+
+```
+class Response {
+  // Every response returned by the VM Service has the
+  // type property.
+  string type;
+}
+```
+
+Every non-error response returned by the Service Protocol extends _Response_.
+
+### Isolate
+
+```
+class @Isolate extends Response {
+  // The id which is passed to the getIsolate RPC to load this isolate.
+  string id;
+
+  string name;
+  int number [optional];
+}
+```
+
+### SourceReportKind
+
+```
+enum SourceReportKind {
+  // Used to request a code coverage information.
+  Coverage,
+
+  PossibleBreakpoints
+}
+```
+";
+
+    #[test]
+    fn parses_version() {
+        let protocol = Protocol::parse(EXCERPT);
+        assert_eq!((protocol.major, protocol.minor), (3, 14));
+    }
+
+    #[test]
+    fn parses_methods() {
+        let protocol = Protocol::parse(EXCERPT);
+        let names: Vec<&str> = protocol.methods.iter().map(|x| x.name.as_str()).collect();
+        assert_eq!(names, vec!["addBreakpoint", "getSourceReport"]);
+
+        let add = &protocol.methods[0];
+        assert_eq!(add.returns, "Breakpoint|Sentinel");
+        assert_eq!(
+            add.docs,
+            vec![
+                "The _addBreakpoint_ RPC is used to add a breakpoint at a specific line",
+                "of some script."
+            ]
+        );
+        let params: Vec<(&str, &str, bool)> = add
+            .params
+            .iter()
+            .map(|x| (x.name.as_str(), x.kind.as_str(), x.optional))
+            .collect();
+        assert_eq!(
+            params,
+            vec![
+                ("isolateId", "string", false),
+                ("scriptId", "string", false),
+                ("line", "int", false),
+                ("column", "int", true),
+            ]
+        );
+
+        let report = &protocol.methods[1];
+        assert!(report.docs.is_empty());
+        assert_eq!(report.params[1].kind, "SourceReportKind[]");
+        assert_eq!(report.params[2].kind, "map<string,string>");
+    }
+
+    #[test]
+    fn parses_classes_and_enums() {
+        let protocol = Protocol::parse(EXCERPT);
+        let isolate = &protocol.classes[1];
+        assert_eq!(isolate.name, "@Isolate");
+        assert_eq!(isolate.parent, Some(String::from("Response")));
+        assert_eq!(
+            isolate.fields[0].docs,
+            vec!["The id which is passed to the getIsolate RPC to load this isolate."]
+        );
+        assert!(isolate.fields[2].optional);
+        assert_eq!(
+            protocol.classes[0].docs,
+            vec!["Every non-error response returned by the Service Protocol extends _Response_."]
+        );
+
+        let kind = &protocol.enums[0];
+        assert_eq!(kind.name, "SourceReportKind");
+        assert_eq!(kind.variants[0].0, "Coverage");
+        assert_eq!(kind.variants[0].1, vec!["Used to request a code coverage information."]);
+        assert_eq!(kind.variants[1], (String::from("PossibleBreakpoints"), Vec::new()));
+    }
+
+    #[test]
+    fn generates_types_and_builders() {
+        let generated = Protocol::parse(EXCERPT).generate();
+        assert!(generated.contains("pub const PROTOCOL_MINOR_VERSION: i64 = 14;"));
+        let start = generated.find("pub struct IsolateRef {").unwrap();
+        let isolate = &generated[start..start + generated[start..].find("\n}").unwrap()];
+        // Fields are inherited from the parent class.
+        assert!(isolate.contains("    #[serde(rename = \"type\")]\n    pub type_: String,"));
+        assert!(isolate.contains("    pub id: String,"));
+        assert!(isolate.contains("    pub number: Option<i64>,"));
+        assert!(generated.contains(
+            "    pub fn add_breakpoint(&self, isolate_id: &str, script_id: &str, line: i64, column: Option<i64>) -> Request {"
+        ));
+        assert!(generated.contains(
+            "    pub fn get_source_report(&self, isolate_id: &str, reports: &[SourceReportKind], scope: Option<serde_json::Value>) -> Request {"
+        ));
+        assert!(generated.contains("impl Default for Requests {"));
+    }
+
+    #[test]
+    fn generates_overrides_and_values() {
+        let spec = "
+```
+class Object extends Response {
+  string id;
+}
+```
+
+```
+class Instance extends Object {
+  string id [optional];
+  map<string,string> properties;
+  Instance parent [optional];
+  @Object|Sentinel value;
+}
+```
+
+```
+class Null {
+}
+```
+";
+        let generated = Protocol::parse(spec).generate();
+        // Parents and classes without fields are not generated.
+        assert!(!generated.contains("pub struct Object {"));
+        assert!(!generated.contains("pub struct Null {"));
+        let start = generated.find("pub struct Instance {").unwrap();
+        let instance = &generated[start..start + generated[start..].find("\n}").unwrap()];
+        // A redeclared field replaces the inherited one.
+        assert!(instance.contains("    pub id: Option<String>,"));
+        assert!(!instance.contains("    pub id: String,"));
+        assert!(instance.contains("    pub properties: serde_json::Value,"));
+        assert!(instance.contains("    pub parent: Option<Box<Instance>>,"));
+        assert!(instance.contains("    pub value: serde_json::Value,"));
+    }
+
+    #[test]
+    fn enums_accept_unknown_values() {
+        let generated = Protocol::parse(EXCERPT).generate();
+        let start = generated.find("pub enum SourceReportKind {").unwrap();
+        let kind = &generated[start..start + generated[start..].find("\n}").unwrap()];
+        assert!(kind.contains("    #[serde(other)]\n    Unknown,"));
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(rust_type_name("@Isolate"), "IsolateRef");
+        assert_eq!(rust_type_name("Isolate"), "Isolate");
+        assert_eq!(snake_case("getVMTimelineMicros"), "get_vm_timeline_micros");
+        assert_eq!(snake_case("isolateId"), "isolate_id");
+        assert_eq!(snake_case("extensionRPCs"), "extension_rpcs");
+        assert_eq!(snake_case("hostCPU"), "host_cpu");
+        assert_eq!(rust_field_name("type"), (String::from("type_"), true));
+        assert_eq!(rust_field_name("_vmName"), (String::from("vm_name"), true));
+        assert_eq!(rust_field_name("name"), (String::from("name"), false));
+    }
+}
@@ -0,0 +1,223 @@
+//! A vm service which answers with canned responses, for trying out the
+//! tools without a device.
+//!
+//! Usage: cargo run --bin mock_vm_service -- [port]
+//!
+//! It reports a single runnable isolate with one flutter view, whose
+//! screenshots are a 1x1 PNG. Methods it doesn't know answer with the
+//! JSON-RPC method not found error. Only the websocket is served, so devfs
+//! uploads fail.
+use serde_json::{json, Value};
+use std::env;
+use websocket::sync::Server;
+use websocket::OwnedMessage;
+
+const DEFAULT_PORT: u16 = 8181;
+
+/// A 1x1 PNG, base64 encoded.
+const CANNED_SCREENSHOT: &'static str =
+    "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAIAAACQd1PeAAAADElEQVR4nGNgCrsEAAGIASsXWuyZAAAAAElFTkSuQmCC";
+
+const ISOLATE_ID: &'static str = "isolates/1";
+
+const ISOLATE_GROUP_ID: &'static str = "isolateGroups/1";
+
+/// JSON-RPC error code for a method the server doesn't have.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Methods which succeed without returning anything.
+const SUCCESS_METHODS: &'static [&'static str] = &[
+    "streamListen",
+    "streamCancel",
+    "registerService",
+    "_registerService",
+    "setExceptionPauseMode",
+    "setIsolatePauseMode",
+    "pause",
+    "resume",
+    "setVMTimelineFlags",
+    "clearVMTimeline",
+    "clearCpuSamples",
+    "_deleteDevFS",
+    "ext.flutter.reassemble",
+];
+
+fn main() {
+    let port = match env::args().nth(1) {
+        Some(port) => port.parse().expect("port must be a number"),
+        None => DEFAULT_PORT,
+    };
+    let server = Server::bind(("127.0.0.1", port)).expect("Failed to bind");
+    println!("Mock vm service listening on ws://127.0.0.1:{}/ws", port);
+    for request in server.filter_map(Result::ok) {
+        std::thread::spawn(move || {
+            let client = match request.accept() {
+                Ok(client) => client,
+                Err(_) => return,
+            };
+            let (mut receiver, mut sender) = client.split().unwrap();
+            for message in receiver.incoming_messages() {
+                let text = match message {
+                    Ok(OwnedMessage::Text(text)) => text,
+                    Ok(OwnedMessage::Ping(data)) => {
+                        let _ = sender.send_message(&OwnedMessage::Pong(data));
+                        continue;
+                    }
+                    Ok(OwnedMessage::Close(_)) | Err(_) => return,
+                    Ok(_) => continue,
+                };
+                let request: Value = match serde_json::from_str(&text) {
+                    Ok(request) => request,
+                    Err(_) => continue,
+                };
+                // Requests without an id are notifications.
+                if request["id"].is_null() {
+                    continue;
+                }
+                let method = request["method"].as_str().unwrap_or("");
+                let response = match respond(method, &request["params"]) {
+                    Some(result) => json!({
+                        "jsonrpc": "2.0",
+                        "id": request["id"],
+                        "result": result,
+                    }),
+                    None => json!({
+                        "jsonrpc": "2.0",
+                        "id": request["id"],
+                        "error": {
+                            "code": METHOD_NOT_FOUND,
+                            "message": "Method not found",
+                            "data": {"method": method},
+                        },
+                    }),
+                };
+                if sender
+                    .send_message(&OwnedMessage::Text(response.to_string()))
+                    .is_err()
+                {
+                    return;
+                }
+            }
+        });
+    }
+}
+
+/// The canned result of `method`, or None if the mock doesn't have it.
+fn respond(method: &str, params: &Value) -> Option<Value> {
+    let isolate_ref = json!({
+        "type": "@Isolate",
+        "id": ISOLATE_ID,
+        "number": "1",
+        "name": "main",
+        "isSystemIsolate": false,
+        "isolateGroupId": ISOLATE_GROUP_ID,
+    });
+    let isolate_group_ref = json!({
+        "type": "@IsolateGroup",
+        "id": ISOLATE_GROUP_ID,
+        "number": "1",
+        "name": "main",
+        "isSystemIsolateGroup": false,
+    });
+    let result = match method {
+        "getVersion" => json!({"type": "Version", "major": 3, "minor": 27}),
+        "getVM" => json!({
+            "type": "VM",
+            "name": "vm",
+            "architectureBits": 64,
+            "targetCPU": "x64",
+            "hostCPU": "mock",
+            "operatingSystem": "linux",
+            "version": "mock",
+            "pid": std::process::id(),
+            "startTime": 0,
+            "isolates": [isolate_ref],
+            "isolateGroups": [isolate_group_ref],
+            "systemIsolates": [],
+            "systemIsolateGroups": [],
+        }),
+        "getIsolate" => json!({
+            "type": "Isolate",
+            "id": ISOLATE_ID,
+            "number": "1",
+            "name": "main",
+            "isSystemIsolate": false,
+            "isolateGroupId": ISOLATE_GROUP_ID,
+            "isolateFlags": [],
+            "startTime": 0,
+            "runnable": true,
+            "livePorts": 1,
+            "pauseOnExit": false,
+            "pauseEvent": {"type": "Event", "kind": "Resume", "timestamp": 0},
+            "libraries": [],
+            "breakpoints": [],
+            "exceptionPauseMode": "Unhandled",
+            "extensionRPCs": ["ext.flutter.reassemble", "ext.flutter.screenshot"],
+        }),
+        "_flutter.listViews" => json!({
+            "type": "FlutterViewList",
+            "views": [{
+                "type": "FlutterView",
+                "id": "_flutterView/0x1",
+                "isolate": {"type": "@Isolate", "id": ISOLATE_ID, "number": 1, "name": "main"},
+            }],
+        }),
+        "_createDevFS" => json!({
+            "type": "FileSystem",
+            "name": params["fsName"],
+            "uri": format!("file:///tmp/{}/", params["fsName"].as_str().unwrap_or("devfs")),
+        }),
+        "_flutter.screenshot" => json!({"type": "Screenshot", "screenshot": CANNED_SCREENSHOT}),
+        "ext.flutter.screenshot" => json!({
+            "type": "_extensionType",
+            "method": method,
+            "screenshot": CANNED_SCREENSHOT,
+        }),
+        "reloadSources" | "_reloadSources" => json!({"type": "ReloadReport", "success": true}),
+        _ if SUCCESS_METHODS.contains(&method) => json!({"type": "Success"}),
+        _ => return None,
+    };
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn isolate_and_view_agree() {
+        let vm = respond("getVM", &Value::Null).unwrap();
+        let views = respond("_flutter.listViews", &Value::Null).unwrap();
+        let isolate = respond("getIsolate", &json!({"isolateId": ISOLATE_ID})).unwrap();
+        assert_eq!(vm["isolates"][0]["id"], ISOLATE_ID);
+        assert_eq!(views["views"][0]["isolate"]["id"], ISOLATE_ID);
+        assert_eq!(isolate["id"], ISOLATE_ID);
+        assert_eq!(isolate["runnable"], true);
+    }
+
+    #[test]
+    fn screenshots_are_pngs() {
+        for method in ["_flutter.screenshot", "ext.flutter.screenshot"].iter() {
+            let response = respond(method, &Value::Null).unwrap();
+            let png = base64::decode(response["screenshot"].as_str().unwrap()).unwrap();
+            assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        }
+    }
+
+    #[test]
+    fn devfs_is_named_after_the_request() {
+        let response = respond("_createDevFS", &json!({"fsName": "app"})).unwrap();
+        assert_eq!(response["name"], "app");
+        assert_eq!(response["uri"], "file:///tmp/app/");
+        assert_eq!(
+            respond("streamListen", &Value::Null).unwrap()["type"],
+            "Success"
+        );
+    }
+
+    #[test]
+    fn unknown_methods_are_not_found() {
+        assert_eq!(respond("getAllocationProfile", &Value::Null), None);
+        assert_eq!(respond("ext.flutter.unknown", &Value::Null), None);
+    }
+}
@@ -1,115 +1,1213 @@
 use getopts::Options;
+use std::collections::HashMap;
 use std::env;
-use std::time::SystemTime;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
 
 use super::compile::*;
+use super::coverage::*;
+use super::debugger::*;
 use super::devfs::*;
+use super::explorer::*;
+use super::frames::*;
+use super::inspector::*;
+use super::logs::*;
+use super::memory::*;
+use super::profile::*;
+use super::proxy::*;
+use super::retainers::*;
+use super::screenshot::*;
+use super::session::*;
+use super::timeline::*;
 use super::vmservice::*;
 
 const DEBUG_PORT_ARG: &'static str = "debug-port";
+const LOG_LEVEL_ARG: &'static str = "log-level";
+const NO_COLOR_ARG: &'static str = "no-color";
+const GC_ARG: &'static str = "gc";
+const RESET_ARG: &'static str = "reset";
+const SAVE_ARG: &'static str = "save";
+const DIFF_ARG: &'static str = "diff";
+const DURATION_ARG: &'static str = "duration";
+const OUTPUT_ARG: &'static str = "output";
+const VM_SERVICE_URI_ARG: &'static str = "vm-service-uri";
+const CA_CERT_ARG: &'static str = "ca-cert";
+const INSECURE_ARG: &'static str = "insecure";
+const AUTH_TOKEN_ARG: &'static str = "auth-token";
+const HEADER_ARG: &'static str = "header";
+const SCREENSHOT_ON_RELOAD_ARG: &'static str = "screenshot-on-reload";
+const BREAKPOINT_ARG: &'static str = "breakpoint";
+const PAUSE_ON_EXCEPTIONS_ARG: &'static str = "pause-on-exceptions";
+const STOP_ON_EXCEPTIONS_ARG: &'static str = "stop-on-exceptions";
+const PROXY_PORT_ARG: &'static str = "proxy-port";
+
+/// Default location of the coverage tracefile.
+const DEFAULT_LCOV_PATH: &'static str = "coverage/lcov.info";
+
+/// Default directory screenshots are written to.
+const DEFAULT_SCREENSHOT_DIR: &'static str = "build";
+
+/// Default length of a cpu profile in seconds.
+const DEFAULT_PROFILE_SECONDS: u64 = 5;
+
+/// Default file `timeline dump` writes to.
+const DEFAULT_TIMELINE_PATH: &'static str = "timeline.json";
+
+/// Leaves the tool's own spans out of `timeline dump`.
+const NO_TOOL_SPANS_FLAG: &'static str = "--no-tool-spans";
+
+/// Framework dumps by interactive key, with their extension and the name of
+/// the file they are saved to. The keys match `flutter run`.
+const FRAMEWORK_DUMPS: &'static [(&'static str, &'static str, &'static str)] = &[
+    ("w", "ext.flutter.debugDumpApp", "app"),
+    ("t", "ext.flutter.debugDumpRenderTree", "render-tree"),
+    ("L", "ext.flutter.debugDumpLayerTree", "layer-tree"),
+    (
+        "S",
+        "ext.flutter.debugDumpSemanticsTreeInTraversalOrder",
+        "semantics-traversal-order",
+    ),
+    (
+        "U",
+        "ext.flutter.debugDumpSemanticsTreeInInverseHitTestOrder",
+        "semantics-hit-test-order",
+    ),
+    ("F", "ext.flutter.debugDumpFocusTree", "focus-tree"),
+];
+
+/// Error code the vm service expects when an expression fails to compile.
+const EXPRESSION_COMPILATION_ERROR: i64 = 113;
 
 pub fn run() -> Result<(), ()> {
     // Configure and collect command line arguments.
-    // Only debug-port is supported to make the example smaller.
     let mut opts = Options::new();
-    opts.optopt(
+    opts.optmulti(
         "p",
         DEBUG_PORT_ARG,
-        "the observatory port on the device.",
+        "the observatory port on a device. Repeat to reload several apps.",
         "12345",
     );
+    opts.optopt(
+        "",
+        LOG_LEVEL_ARG,
+        "the minimum level of application logs to print.",
+        "info",
+    );
+    opts.optflag("", NO_COLOR_ARG, "do not colour application logs.");
+    opts.optflag("", GC_ARG, "memory: collect garbage before profiling.");
+    opts.optflag("", RESET_ARG, "memory: reset accumulated allocation counters.");
+    opts.optopt("", SAVE_ARG, "memory: write the snapshot to a file.", "FILE");
+    opts.optflag("", DIFF_ARG, "memory: diff two saved snapshots.");
+    opts.optopt("", DURATION_ARG, "profile: seconds to sample for.", "5");
+    opts.optopt(
+        "",
+        OUTPUT_ARG,
+        "profile: prefix of the .speedscope.json and .folded files. coverage: the lcov file. screenshot: the directory. retainers: a JSON file.",
+        "FILE",
+    );
+    opts.optmulti(
+        "",
+        VM_SERVICE_URI_ARG,
+        "a vm service websocket address, as well as or instead of --debug-port.",
+        "wss://host/ws",
+    );
+    opts.optmulti("", CA_CERT_ARG, "a PEM certificate to trust for wss/https.", "FILE");
+    opts.optflag("", INSECURE_ARG, "accept self-signed certificates.");
+    opts.optopt("", AUTH_TOKEN_ARG, "a bearer token for the vm service.", "TOKEN");
+    opts.optmulti("", HEADER_ARG, "a header sent to the vm service.", "NAME: VALUE");
+    opts.optflag("", SCREENSHOT_ON_RELOAD_ARG, "capture a screenshot after each reload.");
+    opts.optmulti(
+        "",
+        BREAKPOINT_ARG,
+        "a breakpoint added before resuming isolates paused at start.",
+        "FILE:LINE",
+    );
+    opts.optopt(
+        "",
+        PAUSE_ON_EXCEPTIONS_ARG,
+        "which exceptions isolates pause on: none, unhandled or all.",
+        "MODE",
+    );
+    opts.optflag(
+        "",
+        STOP_ON_EXCEPTIONS_ARG,
+        "run: debug isolates which pause on exceptions instead of resuming them.",
+    );
+    opts.optopt(
+        "",
+        PROXY_PORT_ARG,
+        "serve the vm service to DevTools and other clients from this port, 0 for any. Each further target uses the next port.",
+        "8182",
+    );
     let args: Vec<String> = env::args().collect();
     let matches = opts.parse(&args[2..]).map_err(handle_error)?;
-    let port: u16 = match matches.opt_str(DEBUG_PORT_ARG) {
-        Some(port) => port.parse::<u16>().unwrap(),
-        None => panic!("--debug-port must be provided"),
+    match args[1].as_str() {
+        "debug" => return debug(&matches),
+        "memory" => return memory(&matches),
+        "profile" => return profile(&matches),
+        "coverage" => return coverage(&matches),
+        "devfs" => return devfs(&matches),
+        "screenshot" => return screenshot(&matches),
+        "proxy" => return proxy(&matches),
+        "retainers" => return retainers(&matches),
+        _ => {}
+    }
+    // Connect to every vm service, and share one compiler between them.
+    let breakpoints = matches.opt_strs(BREAKPOINT_ARG);
+    let (console, input) = Console::start();
+    // A target which can't be reached is skipped rather than ending the
+    // session for the others.
+    let mut targets = Vec::new();
+    for address in addresses(&matches)? {
+        match connect_target(&matches, &address, &breakpoints, &console) {
+            Ok(target) => targets.push(target),
+            Err(()) => println!("Skipping {}, it could not be connected to.", address),
+        }
+    }
+    if targets.is_empty() {
+        println!("No targets could be connected to.");
+        return Err(());
+    }
+    let resident_compiler = start_compiler()?;
+    for target in targets.iter_mut() {
+        if register_compiler(&mut target.vm_service, &mut target.methods, &resident_compiler).is_err() {
+            println!("Expression evaluation is unavailable on {}.", target.address);
+        }
+    }
+    if let Some(port) = proxy_port(&matches)? {
+        for (index, target) in targets.iter().enumerate() {
+            let port = if port == 0 { 0 } else { port + index as u16 };
+            match Proxy::start(&target.vm_service, &target.methods, port) {
+                Ok(address) => println!("Serving {} at {}", target.address, address),
+                Err(()) => println!("Could not serve {} from port {}.", target.address, port),
+            }
+        }
+    }
+    let screenshot_on_reload = matches.opt_present(SCREENSHOT_ON_RELOAD_ARG);
+    // Commands other than reload act on the selected target.
+    let mut selected = 0;
+
+    // Read stdin
+    loop {
+        println!("🔥  To hot reload changes while running, press \"r\".");
+        let line = match input.recv() {
+            Ok(Input::Command(line)) => line,
+            Ok(Input::Event(event)) => {
+                debug_exception(&mut targets, &console, &event);
+                continue;
+            }
+            Err(_) => return Ok(()),
+        };
+        let trimmed = line.trim();
+        let start = SystemTime::now();
+        if trimmed == "r" {
+            let mut resident_compiler = resident_compiler.lock().unwrap();
+            // Each target's devfs tracks the same files, and each must see
+            // the change so it isn't reported again next time.
+            let mut invalidated: Vec<std::path::PathBuf> = Vec::new();
+            for target in targets.iter_mut() {
+                for path in target.devfs.updated_entries() {
+                    if !invalidated.iter().any(|x| x == path) {
+                        invalidated.push(path.to_path_buf());
+                    }
+                }
+            }
+            // Send recompilation request to frontend server.
+            let phase = Instant::now();
+            let output = resident_compiler.recompile(
+                    &std::path::Path::new("/Users/jonahwilliams/Documents/flutter/examples/flutter_gallery/lib/main.dart"),
+                    invalidated.iter().map(|x| x.as_path()));
+             println!("Recompile Elapsed: {:?}", start.elapsed());
+            for target in targets.iter_mut() {
+                target.timeline.record("compile", phase);
+            }
+            for message in output.messages.iter() {
+                println!("{}", message);
+            }
+            let output = match output.output {
+                Some(output) => output,
+                None => {
+                    println!("Compilation failed.");
+                    // Drop the failed compile so the next one starts from
+                    // the last accepted code.
+                    resident_compiler.reject();
+                    continue;
+                }
+            };
+            let (reloaded, results) =
+                reload_all(std::mem::replace(&mut targets, Vec::new()), &output, &breakpoints);
+            targets = reloaded;
+            print_results(&results);
+            if targets.is_empty() {
+                println!("No targets are left.");
+                return Err(());
+            }
+            if selected >= targets.len() {
+                selected = 0;
+            }
+            // There is only one compiler, so the new code is only kept if
+            // every target took it.
+            if results.iter().all(|x| x.error.is_none() && x.success) {
+                resident_compiler.accept();
+            } else {
+                resident_compiler.reject();
+            }
+            for target in targets.iter_mut() {
+                // Report on the code which was just replaced, then start over
+                // so the next report only covers the reloaded code.
+                println!("Frames on {} before this reload:", target.address);
+                target.frame_monitor.print();
+                target.frame_monitor.reset();
+                if screenshot_on_reload {
+                    let _ = current_views(&mut target.vm_service, &mut target.methods).and_then(
+                        |flutter_views| {
+                            write_screenshots(
+                                &mut target.vm_service,
+                                &mut target.methods,
+                                &flutter_views,
+                                std::path::Path::new(DEFAULT_SCREENSHOT_DIR),
+                            )
+                        },
+                    );
+                }
+            }
+            println!("Total Elapsed: {:?}", start.elapsed());
+            continue;
+        }
+        if trimmed == "targets" {
+            for (index, target) in targets.iter().enumerate() {
+                let marker = if index == selected { "*" } else { " " };
+                println!("{} {}  {}", marker, index, target.address);
+            }
+            continue;
+        }
+        if trimmed.starts_with("target ") {
+            match trimmed["target ".len()..].trim().parse::<usize>() {
+                Ok(index) if index < targets.len() => {
+                    selected = index;
+                    println!("Selected {}", targets[index].address);
+                }
+                _ => println!("Usage: target <number>, from 0 to {}", targets.len() - 1),
+            }
+            continue;
+        }
+        let Target {
+            vm_service,
+            methods,
+            frame_monitor,
+            timeline,
+            inspector,
+            explorer,
+            memory_snapshots,
+            ..
+        } = &mut targets[selected];
+        // Failed commands are reported, and shouldn't end the session.
+        let flutter_views = match current_views(vm_service, methods) {
+            Ok(flutter_views) => flutter_views,
+            Err(()) => {
+                println!("Failed to list the flutter views.");
+                continue;
+            }
+        };
+        match trimmed {
+            _ if trimmed == "m" || trimmed.starts_with("memory") => {
+                // Each snapshot is compared with the previous one for the isolate.
+                let words: Vec<&str> = trimmed.split_whitespace().collect();
+                let gc = words.contains(&"gc");
+                let reset = words.contains(&"reset");
+                for view in flutter_views.views.iter() {
+                    let snapshot = match MemorySnapshot::capture(
+                        vm_service,
+                        methods,
+                        &view.isolate.name,
+                        &view.isolate.id,
+                        gc,
+                        reset,
+                    ) {
+                        Ok(snapshot) => snapshot,
+                        Err(()) => {
+                            println!("Failed to capture the memory of {}.", view.isolate.name);
+                            continue;
+                        }
+                    };
+                    snapshot.print();
+                    if let Some(previous) = memory_snapshots.get(&view.isolate.id) {
+                        snapshot.print_diff(previous);
+                    }
+                    memory_snapshots.insert(view.isolate.id.clone(), snapshot);
+                }
+            }
+            _ if trimmed == "profile" || trimmed.starts_with("profile ") => {
+                let seconds = match trimmed["profile".len()..].trim() {
+                    "" => DEFAULT_PROFILE_SECONDS,
+                    seconds => match seconds.parse::<u64>() {
+                        Ok(seconds) => seconds,
+                        Err(_) => {
+                            println!("Usage: profile [seconds]");
+                            continue;
+                        }
+                    },
+                };
+                if write_profiles(vm_service, methods, &flutter_views, seconds, "profile").is_err() {
+                    println!("Profiling failed.");
+                }
+            }
+            _ if trimmed == "coverage" || trimmed.starts_with("coverage ") => {
+                let path = match trimmed["coverage".len()..].trim() {
+                    "" => DEFAULT_LCOV_PATH,
+                    path => path,
+                };
+                if write_coverage(vm_service, methods, &flutter_views, path).is_err() {
+                    println!("Collecting coverage failed.");
+                }
+            }
+            _ if trimmed.starts_with("timeline") => {
+                let words: Vec<&str> = trimmed.split_whitespace().collect();
+                match words.get(1).cloned() {
+                    Some("start") => {
+                        // Streams are given as a comma separated list, e.g. Dart,GC.
+                        let streams: Vec<&str> = match words.get(2) {
+                            Some(streams) => streams.split(',').collect(),
+                            None => DEFAULT_STREAMS.to_vec(),
+                        };
+                        match timeline.start(vm_service, methods, &streams) {
+                            Ok(()) => println!("Recording timeline streams {}.", streams.join(", ")),
+                            Err(()) => println!("Failed to start recording the timeline."),
+                        }
+                    }
+                    Some("stop") => match timeline.stop(vm_service, methods) {
+                        Ok(()) => println!("Stopped recording the timeline."),
+                        Err(()) => println!("Failed to stop recording the timeline."),
+                    },
+                    Some("dump") => {
+                        let (path, tool_spans) = timeline_dump_args(&words[2..]);
+                        let file = std::path::Path::new(path);
+                        match timeline.dump(vm_service, methods, file, tool_spans) {
+                            Ok(count) => println!("Wrote {} events to {}", count, path),
+                            Err(()) => println!("Failed to write the timeline to {}.", path),
+                        }
+                    }
+                    _ => println!(
+                        "Usage: timeline start [streams]|stop|dump [file] [{}]",
+                        NO_TOOL_SPANS_FLAG
+                    ),
+                }
+            }
+            _ if FRAMEWORK_DUMPS
+                .iter()
+                .any(|(key, _, _)| trimmed.split_whitespace().next() == Some(*key)) =>
+            {
+                // "<key>" prints the dump, "<key> save" writes it under build/.
+                let words: Vec<&str> = trimmed.split_whitespace().collect();
+                let (_, extension, name) = FRAMEWORK_DUMPS
+                    .iter()
+                    .find(|(key, _, _)| *key == words[0])
+                    .unwrap();
+                let save = match words[1..] {
+                    [] => false,
+                    ["save"] => true,
+                    _ => {
+                        println!("Usage: {} [save]", words[0]);
+                        continue;
+                    }
+                };
+                let _ = dump_framework(
+                    vm_service,
+                    methods,
+                    &flutter_views,
+                    extension,
+                    name,
+                    save,
+                );
+            }
+            _ if trimmed == "s" || trimmed == "screenshot" || trimmed.starts_with("screenshot ") => {
+                let dir = match trimmed {
+                    "s" | "screenshot" => DEFAULT_SCREENSHOT_DIR,
+                    _ => trimmed["screenshot ".len()..].trim(),
+                };
+                let _ = write_screenshots(
+                    vm_service,
+                    methods,
+                    &flutter_views,
+                    std::path::Path::new(dir),
+                );
+            }
+            "frames" => frame_monitor.print(),
+            _ if trimmed == "inspect" || trimmed.starts_with("inspect ") => {
+                let isolate_id = match flutter_views.views.first() {
+                    Some(view) => view.isolate.id.clone(),
+                    None => continue,
+                };
+                // Failures are printed, and shouldn't end the session.
+                let words: Vec<&str> = trimmed.split_whitespace().collect();
+                let _ = match words[1..] {
+                    [] => inspector.print_tree(vm_service, methods, &isolate_id),
+                    ["select", index] if index.parse::<usize>().is_ok() => {
+                        let index = index.parse().unwrap();
+                        inspector
+                            .select(vm_service, methods, &isolate_id, index)
+                            .and_then(|_| inspector.selected(vm_service, methods, &isolate_id))
+                            .map(|selected| match selected.and_then(|x| x.creation_location) {
+                                Some(location) => println!("Selected {}", location),
+                                None => println!("The selected widget has no creation location."),
+                            })
+                    }
+                    ["open"] => inspector
+                        .selected(vm_service, methods, &isolate_id)
+                        .and_then(|selected| match selected.and_then(|x| x.creation_location) {
+                            Some(location) => open_location(&location),
+                            None => {
+                                println!("No widget with a creation location is selected.");
+                                Ok(())
+                            }
+                        }),
+                    [index] if index.parse::<usize>().is_ok() => inspector.expand(
+                        vm_service,
+                        methods,
+                        &isolate_id,
+                        index.parse().unwrap(),
+                    ),
+                    _ => {
+                        println!("Usage: inspect [number] | select <number> | open");
+                        Ok(())
+                    }
+                };
+            }
+            _ if trimmed == "explore" || trimmed.starts_with("explore ") => {
+                let isolate_id = match flutter_views.views.first() {
+                    Some(view) => view.isolate.id.clone(),
+                    None => continue,
+                };
+                // Failures are printed, and shouldn't end the session.
+                let argument = trimmed["explore".len()..].trim();
+                let _ = match argument {
+                    "" => {
+                        println!("Usage: explore <object id | expression> | <number> | more | back | tostring | refs");
+                        Ok(())
+                    }
+                    "more" => explorer.more(vm_service, methods, &isolate_id),
+                    "back" => explorer.back(vm_service, methods, &isolate_id),
+                    "tostring" => explorer.to_string(vm_service, methods, &isolate_id),
+                    "refs" => explorer.references(vm_service, methods, &isolate_id),
+                    _ => match argument.parse::<usize>() {
+                        Ok(index) => explorer.open_child(vm_service, methods, &isolate_id, index),
+                        Err(_) => explorer.open(vm_service, methods, &isolate_id, argument),
+                    },
+                };
+            }
+            _ if trimmed == "retainers" || trimmed.starts_with("retainers ") => {
+                let isolate_id = match flutter_views.views.first() {
+                    Some(view) => view.isolate.id.clone(),
+                    None => continue,
+                };
+                // "retainers <target> json <file>" also writes the paths out.
+                let words: Vec<&str> = trimmed.split_whitespace().collect();
+                let (target, output) = match words[1..] {
+                    [target] => (target, None),
+                    [target, "json", path] => (target, Some(path)),
+                    _ => {
+                        println!("Usage: retainers <object id | class name> [json <file>]");
+                        continue;
+                    }
+                };
+                if let Ok(retained) = find_retainers(vm_service, methods, &isolate_id, target) {
+                    print_retainers(&retained);
+                    if let Some(path) = output {
+                        let _ = write_retainers(&retained, path);
+                    }
+                }
+            }
+            _ if trimmed.starts_with("eval ") => {
+                let expression = trimmed["eval ".len()..].trim();
+                for view in flutter_views.views.iter() {
+                    let isolate: Isolate =
+                        match vm_service.try_call_method(&methods.get_isolate(&view.isolate.id)) {
+                            Ok(isolate) => isolate,
+                            Err(error) => {
+                                println!("{}: {}", view.isolate.name, describe_error(&error));
+                                continue;
+                            }
+                        };
+                    let root_lib = match isolate.root_lib {
+                        Some(root_lib) => root_lib,
+                        None => continue,
+                    };
+                    let result = vm_service.try_call_method(&methods.evaluate(
+                        &view.isolate.id,
+                        &root_lib.id,
+                        expression,
+                    ));
+                    match result {
+                        Ok(value) => println!("{}", describe(&value)),
+                        Err(error) => println!("{}", describe_error(&error)),
+                    }
+                }
+            }
+            _ => {}
+        };
+    }
+}
+
+/// Print, save or diff memory snapshots of every flutter view's isolate.
+///
+/// With --diff the two free arguments are snapshot files written by --save,
+/// and no connection is made.
+fn memory(matches: &getopts::Matches) -> Result<(), ()> {
+    if matches.opt_present(DIFF_ARG) {
+        if matches.free.len() != 2 {
+            println!("--diff expects two snapshot files.");
+            return Err(());
+        }
+        let before = read_snapshots(&matches.free[0])?;
+        let after = read_snapshots(&matches.free[1])?;
+        for snapshot in after.iter() {
+            match before.iter().find(|x| x.isolate == snapshot.isolate) {
+                Some(previous) => snapshot.print_diff(previous),
+                None => println!("{}: not in {}", snapshot.isolate, matches.free[0]),
+            }
+        }
+        return Ok(());
+    }
+    let mut methods = VMServiceMethods::new();
+    let mut vm_service = connect(matches, &mut methods)?;
+    let flutter_views: FlutterViewList = vm_service
+        .call_method(&methods.list_views())
+        .map_err(handle_error)?;
+    let mut snapshots = Vec::new();
+    for view in flutter_views.views.iter() {
+        let snapshot = MemorySnapshot::capture(
+            &mut vm_service,
+            &mut methods,
+            &view.isolate.name,
+            &view.isolate.id,
+            matches.opt_present(GC_ARG),
+            matches.opt_present(RESET_ARG),
+        )?;
+        snapshot.print();
+        snapshots.push(snapshot);
+    }
+    if let Some(path) = matches.opt_str(SAVE_ARG) {
+        let file = std::fs::File::create(&path).map_err(handle_error)?;
+        serde_json::to_writer_pretty(file, &snapshots).map_err(handle_error)?;
+        println!("Wrote {}", path);
+    }
+    Ok(())
+}
+
+/// Sample the cpu of every flutter view's isolate and write the profiles.
+fn profile(matches: &getopts::Matches) -> Result<(), ()> {
+    let seconds = match matches.opt_str(DURATION_ARG) {
+        Some(seconds) => match seconds.parse::<u64>() {
+            Ok(seconds) => seconds,
+            Err(_) => {
+                println!("--duration must be a number of seconds.");
+                return Err(());
+            }
+        },
+        None => DEFAULT_PROFILE_SECONDS,
+    };
+    let prefix = matches.opt_str(OUTPUT_ARG).unwrap_or("profile".to_string());
+    let mut methods = VMServiceMethods::new();
+    let mut vm_service = connect(matches, &mut methods)?;
+    let flutter_views: FlutterViewList = vm_service
+        .call_method(&methods.list_views())
+        .map_err(handle_error)?;
+    write_profiles(&mut vm_service, &mut methods, &flutter_views, seconds, &prefix)
+}
+
+/// Capture a cpu profile of each view's isolate and write it as speedscope
+/// json and folded stacks. Files are numbered when there are several views.
+fn write_profiles(
+    vm_service: &mut VMService,
+    methods: &mut VMServiceMethods,
+    flutter_views: &FlutterViewList,
+    seconds: u64,
+    prefix: &str,
+) -> Result<(), ()> {
+    println!("Profiling for {}s...", seconds);
+    for (index, view) in flutter_views.views.iter().enumerate() {
+        let profile = CpuProfile::capture(
+            vm_service,
+            methods,
+            &view.isolate.name,
+            &view.isolate.id,
+            std::time::Duration::from_secs(seconds),
+        )?;
+        profile.print();
+        let name = if flutter_views.views.len() > 1 {
+            format!("{}-{}", prefix, index)
+        } else {
+            prefix.to_string()
+        };
+        let speedscope = format!("{}.speedscope.json", name);
+        let folded = format!("{}.folded", name);
+        profile.write_speedscope(std::path::Path::new(&speedscope))?;
+        profile.write_folded(std::path::Path::new(&folded))?;
+        println!("Wrote {} and {}", speedscope, folded);
+    }
+    Ok(())
+}
+
+/// Collect line coverage of the project's package from every flutter view's
+/// isolate and write it as LCOV.
+fn coverage(matches: &getopts::Matches) -> Result<(), ()> {
+    let path = matches.opt_str(OUTPUT_ARG).unwrap_or(DEFAULT_LCOV_PATH.to_string());
+    let mut methods = VMServiceMethods::new();
+    let mut vm_service = connect(matches, &mut methods)?;
+    let flutter_views: FlutterViewList = vm_service
+        .call_method(&methods.list_views())
+        .map_err(handle_error)?;
+    write_coverage(&mut vm_service, &mut methods, &flutter_views, &path)
+}
+
+fn write_coverage(
+    vm_service: &mut VMService,
+    methods: &mut VMServiceMethods,
+    flutter_views: &FlutterViewList,
+    path: &str,
+) -> Result<(), ()> {
+    let package = match read_package_name(std::path::Path::new("pubspec.yaml")) {
+        Ok(package) => package,
+        Err(()) => {
+            println!("Could not read the package name from pubspec.yaml.");
+            return Err(());
+        }
+    };
+    let mut coverage = Coverage::new(&package);
+    for view in flutter_views.views.iter() {
+        coverage.collect(vm_service, methods, &view.isolate.id)?;
+    }
+    coverage.write_lcov(std::path::Path::new(path))?;
+    let (found, hit) = coverage.totals();
+    println!(
+        "Covered {} of {} lines ({:.1}%) in package:{}. Wrote {}",
+        hit,
+        found,
+        hit as f64 * 100.0 / found.max(1) as f64,
+        package,
+        path
+    );
+    Ok(())
+}
+
+/// Capture a screenshot of each view into --output, or build/.
+fn screenshot(matches: &getopts::Matches) -> Result<(), ()> {
+    let dir = matches.opt_str(OUTPUT_ARG).unwrap_or(DEFAULT_SCREENSHOT_DIR.to_string());
+    let mut methods = VMServiceMethods::new();
+    let mut vm_service = connect(matches, &mut methods)?;
+    vm_service.track_isolates(&mut methods)?;
+    let flutter_views = current_views(&mut vm_service, &mut methods)?;
+    write_screenshots(
+        &mut vm_service,
+        &mut methods,
+        &flutter_views,
+        std::path::Path::new(&dir),
+    )
+}
+
+/// Print what keeps an object, or the live instances of a class, alive in
+/// the first flutter view's isolate, and write it to --output as JSON.
+fn retainers(matches: &getopts::Matches) -> Result<(), ()> {
+    let target = match matches.free.as_slice() {
+        [target] => target,
+        _ => {
+            println!("Usage: retainers <object id | class name>");
+            return Err(());
+        }
+    };
+    let mut methods = VMServiceMethods::new();
+    let mut vm_service = connect(matches, &mut methods)?;
+    vm_service.track_isolates(&mut methods)?;
+    let flutter_views = current_views(&mut vm_service, &mut methods)?;
+    let isolate_id = match flutter_views.views.first() {
+        Some(view) => view.isolate.id.clone(),
+        None => {
+            println!("No flutter views to inspect.");
+            return Err(());
+        }
+    };
+    let retained = find_retainers(&mut vm_service, &mut methods, &isolate_id, target)?;
+    print_retainers(&retained);
+    match matches.opt_str(OUTPUT_ARG) {
+        Some(path) => write_retainers(&retained, &path),
+        None => Ok(()),
+    }
+}
+
+/// Serve the vm service to other clients until interrupted.
+fn proxy(matches: &getopts::Matches) -> Result<(), ()> {
+    let port = proxy_port(matches)?.unwrap_or(0);
+    let mut methods = VMServiceMethods::new();
+    let vm_service = connect(matches, &mut methods)?;
+    let address = Proxy::start(&vm_service, &methods, port)?;
+    println!("Serving the vm service at {}", address);
+    loop {
+        std::thread::park();
+    }
+}
+
+/// The port given by --proxy-port, if any.
+fn proxy_port(matches: &getopts::Matches) -> Result<Option<u16>, ()> {
+    match matches.opt_str(PROXY_PORT_ARG) {
+        Some(port) => match port.parse::<u16>() {
+            Ok(port) => Ok(Some(port)),
+            Err(_) => {
+                println!("--proxy-port must be a port number.");
+                Err(())
+            }
+        },
+        None => Ok(None),
+    }
+}
+
+/// The level given by --log-level, or 0 to print every record.
+fn log_level(matches: &getopts::Matches) -> Result<i64, ()> {
+    match matches.opt_str(LOG_LEVEL_ARG) {
+        Some(level) => match parse_level(&level) {
+            Some(level) => Ok(level),
+            None => {
+                println!(
+                    "--log-level must be a number or one of {}.",
+                    level_names().join(", ")
+                );
+                Err(())
+            }
+        },
+        None => Ok(0),
+    }
+}
+
+/// Inspect the devfs on the device: `ls [fs name]` or `cat [fs name] <path>`.
+///
+/// Files cannot be removed one at a time, the vm service only has
+/// `_deleteDevFS` for a whole devfs and no `_deleteDevFSFile`.
+fn devfs(matches: &getopts::Matches) -> Result<(), ()> {
+    let words: Vec<&str> = matches.free.iter().map(|x| x.as_str()).collect();
+    let mut methods = VMServiceMethods::new();
+    let mut vm_service = connect(matches, &mut methods)?;
+    match words.as_slice() {
+        ["ls"] => list_devfs(&mut vm_service, &mut methods, DEVFS_NAME),
+        ["ls", fs_name] => list_devfs(&mut vm_service, &mut methods, fs_name),
+        ["cat", path] => cat_devfs(&mut vm_service, &mut methods, DEVFS_NAME, path),
+        ["cat", fs_name, path] => cat_devfs(&mut vm_service, &mut methods, fs_name, path),
+        _ => {
+            println!("Usage: devfs ls [fs name] | cat [fs name] <path>");
+            Err(())
+        }
+    }
+}
+
+/// Write the contents of a file in a devfs to stdout.
+fn cat_devfs(
+    vm_service: &mut VMService,
+    methods: &mut VMServiceMethods,
+    fs_name: &str,
+    path: &str,
+) -> Result<(), ()> {
+    let file: DevFSFileContents = vm_service
+        .call_method(&methods.read_devfs_file(fs_name, path))
+        .map_err(handle_error)?;
+    let contents = file.decode()?;
+    std::io::Write::write_all(&mut std::io::stdout(), &contents).map_err(|_| ())
+}
+
+/// Print the files in a devfs, marking those older than the local copy.
+fn list_devfs(
+    vm_service: &mut VMService,
+    methods: &mut VMServiceMethods,
+    fs_name: &str,
+) -> Result<(), ()> {
+    let filesystems: DevFSListResult = vm_service
+        .call_method(&methods.list_devfs())
+        .map_err(handle_error)?;
+    if !filesystems.fs_names.iter().any(|x| x == fs_name) {
+        println!(
+            "No devfs named {}. Found: {}",
+            fs_name,
+            filesystems.fs_names.join(", ")
+        );
+        return Err(());
+    }
+    let mut list: DevFSFileList = vm_service
+        .call_method(&methods.list_devfs_files(fs_name))
+        .map_err(handle_error)?;
+    list.files.sort_by(|a, b| a.name.cmp(&b.name));
+    let now = SystemTime::now();
+    for file in list.files.iter() {
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_millis(file.modified.max(0) as u64);
+        let age = now.duration_since(modified).unwrap_or(Duration::from_secs(0));
+        let local = std::fs::metadata(file.name.trim_start_matches('/')).and_then(|x| x.modified());
+        let stale = match local {
+            Ok(local) if local > modified => "  (stale)",
+            _ => "",
+        };
+        println!(
+            "{:>10}  {:>8}s ago  {}{}",
+            format_bytes(file.size),
+            age.as_secs(),
+            file.name,
+            stale
+        );
+    }
+    println!("{} files in {}", list.files.len(), fs_name);
+    Ok(())
+}
+
+/// Run a framework debug dump on each view's isolate, and print it or write
+/// it to `build/<name>-<timestamp>.txt`.
+fn dump_framework(
+    vm_service: &mut VMService,
+    methods: &mut VMServiceMethods,
+    flutter_views: &FlutterViewList,
+    extension: &str,
+    name: &str,
+    save: bool,
+) -> Result<(), ()> {
+    let timestamp = SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|x| x.as_secs())
+        .unwrap_or(0);
+    for (index, view) in flutter_views.views.iter().enumerate() {
+        let response = vm_service.call_extension(methods, extension, &view.isolate.id, &[])?;
+        let data = match response["data"].as_str() {
+            Some(data) => data.to_string(),
+            None => {
+                println!("Unexpected response from {}: {}", extension, response);
+                return Err(());
+            }
+        };
+        if !save {
+            println!("{}", data);
+            continue;
+        }
+        let path = dump_path(name, timestamp, index, flutter_views.views.len());
+        std::fs::create_dir_all("build")
+            .and_then(|_| std::fs::write(&path, data))
+            .map_err(|err| println!("Failed to write {}: {}", path, err))?;
+        println!("Wrote {}", path);
+    }
+    Ok(())
+}
+
+/// The file `timeline dump` writes to, and whether the tool's spans are
+/// included, from the words after `dump`.
+fn timeline_dump_args<'a>(words: &[&'a str]) -> (&'a str, bool) {
+    let path = words
+        .iter()
+        .find(|x| **x != NO_TOOL_SPANS_FLAG)
+        .cloned()
+        .unwrap_or(DEFAULT_TIMELINE_PATH);
+    (path, !words.contains(&NO_TOOL_SPANS_FLAG))
+}
+
+/// Where the dump of the view numbered `index` of `views` is saved, numbered
+/// only when there are several views.
+fn dump_path(name: &str, timestamp: u64, index: usize, views: usize) -> String {
+    if views > 1 {
+        format!("build/{}-{}-{}.txt", name, timestamp, index)
+    } else {
+        format!("build/{}-{}.txt", name, timestamp)
+    }
+}
+
+fn read_snapshots(path: &str) -> Result<Vec<MemorySnapshot>, ()> {
+    let file = std::fs::File::open(path).map_err(handle_error)?;
+    serde_json::from_reader(file).map_err(handle_error)
+}
+
+/// Connect to the first vm service given by --vm-service-uri or
+/// --debug-port, and negotiate the protocol version.
+fn connect(matches: &getopts::Matches, methods: &mut VMServiceMethods) -> Result<VMService, ()> {
+    connect_to(matches, &addresses(matches)?[0], methods)
+}
+
+fn connect_to(
+    matches: &getopts::Matches,
+    address: &str,
+    methods: &mut VMServiceMethods,
+) -> Result<VMService, ()> {
+    let mut vm_service = VMService::connect_with(address, &connect_options(matches)?)?;
+    vm_service.negotiate_version(methods)?;
+    Ok(vm_service)
+}
+
+/// Attach a debugger to an isolate which paused on an exception, until the
+/// user quits it and returns to the run loop.
+fn debug_exception(targets: &mut [Target], console: &Console, event: &Event) {
+    let isolate = match event.isolate.as_ref() {
+        Some(isolate) => isolate,
+        None => return,
+    };
+    let target = match targets
+        .iter_mut()
+        .find(|x| x.vm_service.isolate(&isolate.id).is_some())
+    {
+        Some(target) => target,
+        None => return,
     };
-    // Connect to the vm service.
+    let attached = Debugger::attach(
+        &mut target.vm_service,
+        &mut target.methods,
+        console,
+        &isolate.id,
+    );
+    let result = attached.and_then(|mut debugger| debugger.run());
+    if result.is_err() {
+        println!("Failed to debug {}.", isolate.name);
+    }
+}
+
+/// Connect to the vm service at `address` and get it ready to reload: print
+/// its logs, watch its frames and exceptions, resume isolates paused at start
+/// and upload the project to a fresh devfs.
+///
+/// With --stop-on-exceptions, isolates paused on an exception are passed to
+/// the reader of `console`.
+fn connect_target(
+    matches: &getopts::Matches,
+    address: &str,
+    breakpoints: &[String],
+    console: &Console,
+) -> Result<Target, ()> {
     let mut methods = VMServiceMethods::new();
-    let mut vm_service = VMService::connect(&format!("ws://127.0.0.1:{}/ws", port))?;
+    let mut vm_service = connect_to(matches, address, &mut methods)?;
     let mut devfs = DevFS::init(std::path::Path::new("lib/"))?;
     vm_service
         .send_notification(&methods.register_service("reloadSources", "flutter tools"))
         .map_err(handle_error)?;
 
+    // Print application output.
+    let min_level = log_level(matches)?;
+    let log_printer = LogPrinter {
+        min_level: min_level,
+        color: !matches.opt_present(NO_COLOR_ARG),
+    };
+    log_printer.start(&mut vm_service, &mut methods)?;
+    let frame_monitor = FrameMonitor::start(&mut vm_service, &mut methods)?;
+    vm_service.track_isolates(&mut methods)?;
+    if let Some(mode) = pause_on_exceptions(matches)? {
+        set_pause_mode(&mut vm_service, &mut methods, mode)?;
+    }
+    let stop = if matches.opt_present(STOP_ON_EXCEPTIONS_ARG) {
+        Some(console.clone())
+    } else {
+        None
+    };
+    report_exceptions(&mut vm_service, &mut methods, stop)?;
+    resume_paused_isolates(&mut vm_service, &mut methods, breakpoints)?;
+
     // Create the devfs, or destroy and then create it.
     let devfs_response: DevFSCreatedResponse =
-        match vm_service.call_method(&methods.create_devfs("flutter_gallery")) {
+        match vm_service.call_method(&methods.create_devfs(DEVFS_NAME)) {
             Ok(res) => res,
             Err(_) => {
                 vm_service
-                    .send_notification(&methods.delete_devfs("flutter_gallery"))
+                    .send_notification(&methods.delete_devfs(DEVFS_NAME))
                     .unwrap();
                 vm_service
-                    .call_method(&methods.create_devfs("flutter_gallery"))
+                    .call_method(&methods.create_devfs(DEVFS_NAME))
                     .unwrap()
             }
         };
 
-    // Setup resident compiler.
+    // Upload the project to the devfs.
+    let _ = devfs.add_file(std::path::Path::new(".packages"));
+    let _ = devfs.add_dir(std::path::Path::new("build/flutter_assets"));
+    devfs
+        .sync(vm_service.uploader(), DEVFS_NAME, SYNC_CONCURRENCY, true)
+        .print();
+
+    Ok(Target {
+        address: address.to_string(),
+        vm_service: vm_service,
+        methods: methods,
+        devfs: devfs,
+        devfs_uri: devfs_response.uri,
+        frame_monitor: frame_monitor,
+        timeline: TimelineRecorder::new(),
+        inspector: Inspector::new(),
+        explorer: Explorer::new(),
+        memory_snapshots: HashMap::new(),
+    })
+}
+
+fn connect_options(matches: &getopts::Matches) -> Result<ConnectOptions, ()> {
+    let mut options = ConnectOptions::default();
+    for path in matches.opt_strs(CA_CERT_ARG) {
+        let pem = std::fs::read(&path).map_err(|err| println!("Failed to read {}: {}", path, err))?;
+        options.ca_certificates.push(pem);
+    }
+    options.accept_invalid_certs = matches.opt_present(INSECURE_ARG);
+    for header in matches.opt_strs(HEADER_ARG) {
+        match header.find(':') {
+            Some(index) => options.headers.push((
+                header[..index].trim().to_string(),
+                header[index + 1..].trim().to_string(),
+            )),
+            None => {
+                println!("--header must be of the form \"Name: value\".");
+                return Err(());
+            }
+        }
+    }
+    if let Some(token) = matches.opt_str(AUTH_TOKEN_ARG) {
+        options
+            .headers
+            .push(("Authorization".to_string(), format!("Bearer {}", token)));
+    }
+    Ok(options)
+}
+
+/// The vm service addresses given by --vm-service-uri, followed by those of
+/// each local --debug-port.
+fn addresses(matches: &getopts::Matches) -> Result<Vec<String>, ()> {
+    let mut addresses = matches.opt_strs(VM_SERVICE_URI_ARG);
+    for port in matches.opt_strs(DEBUG_PORT_ARG) {
+        match port.parse::<u16>() {
+            Ok(port) => addresses.push(format!("ws://127.0.0.1:{}/ws", port)),
+            Err(_) => {
+                println!("--debug-port must be a port number, not {}.", port);
+                return Err(());
+            }
+        }
+    }
+    if addresses.is_empty() {
+        panic!("--debug-port must be provided");
+    }
+    Ok(addresses)
+}
+
+/// Start the frontend server.
+fn start_compiler() -> Result<Arc<Mutex<ResidentCompiler>>, ()> {
     let mut resident_compiler = ResidentCompiler::new();
-    resident_compiler.start();
+    resident_compiler.start()?;
     resident_compiler.accept();
+    Ok(Arc::new(Mutex::new(resident_compiler)))
+}
+
+/// Register the compiler with the vm service to compile expressions for
+/// evaluate and evaluateInFrame.
+fn register_compiler(
+    vm_service: &mut VMService,
+    methods: &mut VMServiceMethods,
+    resident_compiler: &Arc<Mutex<ResidentCompiler>>,
+) -> Result<(), ()> {
+    let compiler = Arc::clone(resident_compiler);
+    vm_service.register_handler(
+        "compileExpression",
+        Arc::new(move |params| {
+            let request: CompileExpressionParams = serde_json::from_value(params.clone())
+                .map_err(|err| serde_json::json!({"code": -32602, "message": err.to_string()}))?;
+            let kernel = compiler.lock().unwrap().compile_expression(
+                &request.expression,
+                &request.definitions,
+                &request.type_definitions,
+                &request.library_uri,
+                request.klass.as_ref().map(|x| x.as_str()),
+                request.is_static,
+            );
+            match kernel {
+                Ok(kernel) => Ok(serde_json::json!({
+                    "type": "Success",
+                    "result": {"kernelBytes": kernel},
+                })),
+                Err(details) => Err(serde_json::json!({
+                    "code": EXPRESSION_COMPILATION_ERROR,
+                    "message": "Expression compilation error",
+                    "data": {"details": details},
+                })),
+            }
+        }),
+    );
+    vm_service
+        .send_notification(&methods.register_service("compileExpression", "flutter tools"))
+        .map_err(handle_error)
+}
+
+/// Attach a command line debugger to the main isolate of the first flutter view.
+fn debug(matches: &getopts::Matches) -> Result<(), ()> {
+    let mut methods = VMServiceMethods::new();
+    let mut vm_service = connect(matches, &mut methods)?;
     let flutter_views: FlutterViewList = vm_service
         .call_method(&methods.list_views())
         .map_err(handle_error)?;
+    let isolate_id = match flutter_views.views.first() {
+        Some(view) => view.isolate.id.clone(),
+        None => {
+            println!("No flutter views to debug.");
+            return Err(());
+        }
+    };
+    // Keep the compiler alive for the session so expressions can be evaluated.
+    let _resident_compiler = match start_compiler()
+        .and_then(|x| register_compiler(&mut vm_service, &mut methods, &x).map(|_| x))
+    {
+        Ok(resident_compiler) => Some(resident_compiler),
+        Err(()) => {
+            println!("Expression evaluation is unavailable.");
+            None
+        }
+    };
+    if let Some(mode) = pause_on_exceptions(matches)? {
+        vm_service
+            .send_notification(&methods.set_exception_pause_mode(&isolate_id, mode))
+            .map_err(handle_error)?;
+    }
+    let (console, _input) = Console::start();
+    let mut debugger = Debugger::attach(&mut vm_service, &mut methods, &console, &isolate_id)?;
+    debugger.run()
+}
 
-    // Read stdin
-    loop {
-        let mut input = String::new();
-        println!("🔥  To hot reload changes while running, press \"r\".");
-        std::io::stdin().read_line(&mut input).unwrap();
-        let trimmed = input.trim();
-        let start = SystemTime::now();
-        match trimmed {
-            "r" => {
-                // Send recompilation request to frontend server.
-                let output = resident_compiler.recompile(
-                        &std::path::Path::new("/Users/jonahwilliams/Documents/flutter/examples/flutter_gallery/lib/main.dart"),
-                        devfs.updated_entries());
-                 println!("Recompile Elapsed: {:?}", start.elapsed());
-                // Write incremental dill file to devfs.
-                vm_service
-                    .write_file(
-                        &mut std::fs::File::open(&output.output).unwrap(),
-                        "lib/main.dart.incremental.dill",
-                        "flutter_gallery",
-                    )
-                    .unwrap();
-                 println!("Write File: {:?}", start.elapsed());
-
-                // Call reload sources in each flutter view (generally only 1).
-                // These should be sent concurrently if there are multiple.
-                for view in flutter_views.views.iter() {
-                    let base = &devfs_response.uri;
-                    let report: ReloadReport = vm_service
-                        .call_method(&methods.reload_sources(
-                            &view.isolate.id,
-                            false,
-                            &format!("{}lib/main.dart.incremental.dill", base),
-                            &format!("{}.packages", base),
-                        ))
-                        .unwrap();
-                    println!("Reload sources: {:?}", start.elapsed());
-
-                    // Inform compiler whether sources were accepted.
-                    if report.success {
-                        resident_compiler.accept();
-                    } else {
-                        resident_compiler.reject();
-                    }
-                    vm_service
-                        .send_notification(&methods.reassemble(&view.isolate.id))
-                        .unwrap();
-                    println!("Reassemble: {:?}", start.elapsed());
-                }
-                println!("Total Elapsed: {:?}", start.elapsed());
+/// The mode given by --pause-on-exceptions, if any.
+fn pause_on_exceptions(matches: &getopts::Matches) -> Result<Option<ExceptionPauseMode>, ()> {
+    match matches.opt_str(PAUSE_ON_EXCEPTIONS_ARG) {
+        Some(mode) => match parse_pause_mode(&mode) {
+            Some(mode) => Ok(Some(mode)),
+            None => {
+                println!("--pause-on-exceptions must be none, unhandled or all.");
+                Err(())
             }
-            _ => {}
-        };
+        },
+        None => Ok(None),
     }
 }
 
 fn handle_error<T>(_e: T) -> () {
     println!("Error");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dumps_have_their_own_keys_and_files() {
+        for (index, (key, extension, name)) in FRAMEWORK_DUMPS.iter().enumerate() {
+            assert!(extension.starts_with("ext.flutter.debugDump"));
+            for (other_key, _, other_name) in FRAMEWORK_DUMPS[index + 1..].iter() {
+                assert_ne!(key, other_key);
+                assert_ne!(name, other_name);
+            }
+        }
+    }
+
+    #[test]
+    fn dump_paths_are_numbered_for_several_views() {
+        assert_eq!(dump_path("render-tree", 1700, 0, 1), "build/render-tree-1700.txt");
+        assert_eq!(dump_path("render-tree", 1700, 1, 2), "build/render-tree-1700-1.txt");
+    }
+
+    #[test]
+    fn timeline_dumps_can_leave_out_tool_spans() {
+        assert_eq!(timeline_dump_args(&[]), ("timeline.json", true));
+        assert_eq!(timeline_dump_args(&["reload.json"]), ("reload.json", true));
+        assert_eq!(
+            timeline_dump_args(&["--no-tool-spans"]),
+            ("timeline.json", false)
+        );
+        assert_eq!(
+            timeline_dump_args(&["--no-tool-spans", "reload.json"]),
+            ("reload.json", false)
+        );
+    }
+}
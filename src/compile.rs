@@ -9,8 +9,12 @@ use uuid::*;
 
 #[derive(Debug)]
 pub struct CompileOutput {
-    pub output: std::path::PathBuf,
+    /// The compiled kernel, or `None` if compilation failed.
+    pub output: Option<std::path::PathBuf>,
     pub errors: i32,
+
+    /// The diagnostics the compiler printed, one per line.
+    pub messages: Vec<String>,
 }
 
 pub struct ResidentCompiler {
@@ -29,7 +33,7 @@ impl ResidentCompiler {
     /// Start the frontend server.
     ///
     /// All of these paths are hardcoded here, but should be known ahead of time anyway.
-    pub fn start(&mut self) {
+    pub fn start(&mut self) -> Result<(), ()> {
         let (sender, receiver) = mpsc::channel();
         let frontend_server = Command::new("/Users/jonahwilliams/Documents/flutter/bin/cache/dart-sdk/bin/dart")
             .arg("/Users/jonahwilliams/Documents/flutter/bin/cache/artifacts/engine/darwin-x64/frontend_server.dart.snapshot")
@@ -43,12 +47,13 @@ impl ResidentCompiler {
             .stdout(Stdio::piped())
             .stdin(Stdio::piped())
             .spawn()
-            .expect("Failed to spawn frontend server");
+            .map_err(|err| println!("Failed to spawn frontend server: {}", err))?;
 
         let reader = frontend_server.stdout.unwrap();
         let mut writer = io::BufWriter::new(frontend_server.stdin.unwrap());
         let mut output_handler = CompilerOutputHandler {
             boundary_key: String::new(),
+            messages: Vec::new(),
             compilation_request: sender,
         };
         std::thread::spawn(move || {
@@ -64,6 +69,7 @@ impl ResidentCompiler {
         receiver.recv().unwrap();
         self.writer = Some(writer);
         self.incremental_output = Some(receiver);
+        Ok(())
     }
 
     pub fn recompile<'b, T>(&mut self, main: &std::path::Path, invalidated: T) -> CompileOutput
@@ -82,6 +88,51 @@ impl ResidentCompiler {
         self.incremental_output.as_mut().unwrap().recv().unwrap()
     }
 
+    /// Compile `expression` in the scope of `library_uri` (and `klass`, if any)
+    /// for the vm service's compileExpression request.
+    ///
+    /// Returns the compiled kernel encoded as base64, or the compiler's
+    /// errors.
+    pub fn compile_expression(
+        &mut self,
+        expression: &str,
+        definitions: &[String],
+        type_definitions: &[String],
+        library_uri: &str,
+        klass: Option<&str>,
+        is_static: bool,
+    ) -> Result<String, String> {
+        let writer = self.writer.as_mut().unwrap();
+        let input_key = Uuid::new_v4();
+        write!(writer, "compile-expression {}\n", input_key).unwrap();
+        write!(writer, "{}\n", expression).unwrap();
+        for definition in definitions {
+            write!(writer, "{}\n", definition).unwrap();
+        }
+        write!(writer, "{}\n", input_key).unwrap();
+        for type_definition in type_definitions {
+            write!(writer, "{}\n", type_definition).unwrap();
+        }
+        write!(writer, "{}\n", input_key).unwrap();
+        write!(writer, "{}\n", library_uri).unwrap();
+        write!(writer, "{}\n", klass.unwrap_or("")).unwrap();
+        write!(writer, "{}\n", is_static).unwrap();
+        writer.flush().unwrap();
+        let output = self.incremental_output.as_mut().unwrap().recv().unwrap();
+        let path = match output.output {
+            Some(path) if output.errors == 0 => path,
+            _ if output.messages.is_empty() => {
+                return Err(format!("Failed to compile {}", expression));
+            }
+            _ => return Err(output.messages.join("\n")),
+        };
+        let mut kernel = Vec::new();
+        std::fs::File::open(&path)
+            .and_then(|mut file| io::Read::read_to_end(&mut file, &mut kernel))
+            .map_err(|err| format!("Failed to read {}: {}", path.display(), err))?;
+        Ok(base64::encode(&kernel))
+    }
+
     pub fn accept(&mut self) {
         let writer = self.writer.as_mut().unwrap();
         writer.write(b"accept\n").unwrap();
@@ -116,6 +167,10 @@ impl ResidentCompiler {
 
 struct CompilerOutputHandler {
     boundary_key: String,
+
+    /// The lines printed since the result line of the current request.
+    messages: Vec<String>,
+
     compilation_request: mpsc::Sender<CompileOutput>,
 }
 
@@ -124,17 +179,72 @@ impl CompilerOutputHandler {
         if self.boundary_key.is_empty() && line.starts_with("result ") {
             self.boundary_key.push_str(line.split_at("result ".len()).1);
             return;
-        } else if line.starts_with(&self.boundary_key) {
+        } else if !self.boundary_key.is_empty() && line.starts_with(&self.boundary_key) {
+            // A bare boundary key means compilation failed without output.
             if line.len() <= self.boundary_key.len() {
-                return; // ERROR
+                self.boundary_key.clear();
+                let output = CompileOutput {
+                    output: None,
+                    errors: 1,
+                    messages: std::mem::replace(&mut self.messages, Vec::new()),
+                };
+                self.compilation_request.send(output).unwrap();
+                return;
             }
             let mut sections = line.split(" ").skip(1);
             let output = CompileOutput {
-                output: std::path::Path::new(sections.next().unwrap()).to_path_buf(),
+                output: Some(std::path::Path::new(sections.next().unwrap()).to_path_buf()),
                 errors: sections.next().unwrap().parse::<i32>().unwrap(),
+                messages: std::mem::replace(&mut self.messages, Vec::new()),
             };
             self.boundary_key.clear();
             self.compilation_request.send(output).unwrap();
+        } else if !self.boundary_key.is_empty() {
+            self.messages.push(line.to_string());
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handler() -> (CompilerOutputHandler, mpsc::Receiver<CompileOutput>) {
+        let (sender, receiver) = mpsc::channel();
+        let handler = CompilerOutputHandler {
+            boundary_key: String::new(),
+            messages: Vec::new(),
+            compilation_request: sender,
+        };
+        (handler, receiver)
+    }
+
+    #[test]
+    fn collects_errors_between_boundaries() {
+        let (mut handler, receiver) = handler();
+        handler.on_line("Starting compiler");
+        handler.on_line("result abc");
+        handler.on_line("lib/main.dart:3:5: Error: Expected ';' after this.");
+        handler.on_line("abc build/app.dill 1");
+        let output = receiver.try_recv().unwrap();
+        assert_eq!(output.output, Some(std::path::PathBuf::from("build/app.dill")));
+        assert_eq!(output.errors, 1);
+        assert_eq!(output.messages, vec!["lib/main.dart:3:5: Error: Expected ';' after this."]);
+
+        // The next request starts with no messages.
+        handler.on_line("result def");
+        handler.on_line("def build/app.dill 0");
+        assert!(receiver.try_recv().unwrap().messages.is_empty());
+    }
+
+    #[test]
+    fn bare_boundary_is_a_failure() {
+        let (mut handler, receiver) = handler();
+        handler.on_line("result abc");
+        handler.on_line("Error: unknown expression");
+        handler.on_line("abc");
+        let output = receiver.try_recv().unwrap();
+        assert_eq!(output.output, None);
+        assert_eq!(output.messages, vec!["Error: unknown expression"]);
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, Write};
+
+use super::vmservice::*;
+
+const COVERAGE_REPORT: &[SourceReportKind] = &[SourceReportKind::Coverage];
+
+/// Read the package name from the `name:` field of a pubspec.
+pub fn read_package_name(pubspec: &std::path::Path) -> Result<String, ()> {
+    let file = std::fs::File::open(pubspec).map_err(|_| ())?;
+    for line in std::io::BufReader::new(file).lines() {
+        let line = line.map_err(|_| ())?;
+        if line.starts_with("name:") {
+            let name = line["name:".len()..].trim().trim_matches(|c| c == '"' || c == '\'');
+            return Ok(name.to_string());
+        }
+    }
+    Err(())
+}
+
+/// Line coverage of the libraries in one package, merged across isolates.
+pub struct Coverage {
+    package: String,
+
+    /// Hit counts by line, keyed by the path of each file relative to the
+    /// project root.
+    files: BTreeMap<String, BTreeMap<i64, i64>>,
+}
+
+impl Coverage {
+    pub fn new(package: &str) -> Self {
+        Coverage {
+            package: package.to_string(),
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Collect coverage of every script of the package's libraries in an
+    /// isolate.
+    pub fn collect(
+        &mut self,
+        vm_service: &mut VMService,
+        methods: &mut VMServiceMethods,
+        isolate_id: &str,
+    ) -> Result<(), ()> {
+        let prefix = format!("package:{}/", self.package);
+        let isolate: Isolate = vm_service.call_method(&methods.get_isolate(isolate_id))?;
+        for library_ref in isolate.libraries.iter().filter(|x| x.uri.starts_with(&prefix)) {
+            let library: Library =
+                vm_service.call_method(&methods.get_object(isolate_id, &library_ref.id))?;
+            for script_ref in library.scripts.iter() {
+                let path = match script_ref.uri.get(prefix.len()..) {
+                    Some(path) if script_ref.uri.starts_with(&prefix) => format!("lib/{}", path),
+                    _ => continue,
+                };
+                let report: SourceReport = vm_service.call_method(&methods.get_source_report(
+                    isolate_id,
+                    COVERAGE_REPORT,
+                    &script_ref.id,
+                    true,
+                ))?;
+                let script: Script =
+                    vm_service.call_method(&methods.get_object(isolate_id, &script_ref.id))?;
+                let lines = line_table(&script);
+                let counts = self.files.entry(path).or_insert_with(BTreeMap::new);
+                for range in report.ranges.iter() {
+                    let coverage = match range.coverage.as_ref() {
+                        Some(coverage) => coverage,
+                        None => continue,
+                    };
+                    // The vm reports whether a position ran, not how often.
+                    for (positions, hit) in [(&coverage.hits, 1), (&coverage.misses, 0)].iter() {
+                        for line in positions.iter().filter_map(|x| lines.get(x)) {
+                            let count = counts.entry(*line).or_insert(0);
+                            *count = (*count).max(*hit);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The number of lines found and the number of lines hit.
+    pub fn totals(&self) -> (usize, usize) {
+        self.files.values().fold((0, 0), |(found, hit), lines| {
+            (found + lines.len(), hit + lines.values().filter(|x| **x > 0).count())
+        })
+    }
+
+    /// Write the coverage in the LCOV tracefile format read by genhtml.
+    pub fn write_lcov(&self, path: &std::path::Path) -> Result<(), ()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|_| ())?;
+        }
+        let mut file = std::fs::File::create(path).map_err(|_| ())?;
+        for (source, lines) in self.files.iter() {
+            writeln!(file, "SF:{}", source).map_err(|_| ())?;
+            for (line, count) in lines.iter() {
+                writeln!(file, "DA:{},{}", line, count).map_err(|_| ())?;
+            }
+            writeln!(file, "LF:{}", lines.len()).map_err(|_| ())?;
+            writeln!(file, "LH:{}", lines.values().filter(|x| **x > 0).count()).map_err(|_| ())?;
+            writeln!(file, "end_of_record").map_err(|_| ())?;
+        }
+        Ok(())
+    }
+}
+
+/// Map every token position of a script to its line.
+fn line_table(script: &Script) -> HashMap<i64, i64> {
+    let mut lines = HashMap::new();
+    for row in script.token_pos_table.iter().flatten() {
+        if let Some((line, pairs)) = row.split_first() {
+            for pair in pairs.chunks(2) {
+                lines.insert(pair[0], *line);
+            }
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    #[test]
+    fn maps_token_positions_to_lines() {
+        let script: Script = serde_json::from_value(serde_json::json!({
+            "type": "Script",
+            "id": "scripts/1",
+            "uri": "package:app/main.dart",
+            "library": {"type": "@Library", "id": "libraries/1", "name": "", "uri": "package:app/main.dart"},
+            "tokenPosTable": [[1, 0, 1, 4, 8], [], [2, 9, 1]],
+        }))
+        .unwrap();
+        let lines = line_table(&script);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[&4], 1);
+        assert_eq!(lines[&9], 2);
+    }
+
+    #[test]
+    fn writes_lcov() {
+        let mut coverage = Coverage::new("app");
+        let mut main = BTreeMap::new();
+        main.insert(3, 1);
+        main.insert(4, 0);
+        coverage.files.insert(String::from("lib/main.dart"), main);
+        let mut home = BTreeMap::new();
+        home.insert(10, 1);
+        coverage.files.insert(String::from("lib/src/home.dart"), home);
+        assert_eq!(coverage.totals(), (3, 2));
+
+        let dir = TempDir::new();
+        let path = dir.join("lcov.info");
+        coverage.write_lcov(&path).unwrap();
+        let lcov = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            lcov,
+            "SF:lib/main.dart\nDA:3,1\nDA:4,0\nLF:2\nLH:1\nend_of_record\n\
+             SF:lib/src/home.dart\nDA:10,1\nLF:1\nLH:1\nend_of_record\n"
+        );
+    }
+
+    #[test]
+    fn reads_package_name() {
+        let dir = TempDir::new();
+        let path = dir.join("pubspec.yaml");
+        std::fs::write(&path, "# An app.\nname: 'gallery'\ndependencies:\n  name: other\n").unwrap();
+        assert_eq!(read_package_name(&path), Ok(String::from("gallery")));
+        assert_eq!(read_package_name(&dir.join("missing.yaml")), Err(()));
+    }
+}
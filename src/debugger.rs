@@ -0,0 +1,848 @@
+use std::collections::HashMap;
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+use std::sync::{mpsc, Arc, Mutex};
+
+use super::coverage::read_package_name;
+use super::vmservice::*;
+
+const HELP: &'static str = "Commands:
+  b, break <file>:<line>    set a breakpoint
+  delete <number>           remove a breakpoint
+  info                      list breakpoints
+  bt, backtrace             print the stack
+  f, frame <index>          select a stack frame
+  l, list                   show the source of the selected frame
+  v, vars                   show the variables of the selected frame
+  c, continue               resume the isolate
+  s, step                   step into the next call
+  n, next                   step over the next call
+  finish                    step out of the current function
+  rewind <index>            rewind to the start of a frame
+  pause                     interrupt the running isolate
+  catch none|unhandled|all  set the exception pause mode
+  p, eval <expression>      evaluate an expression in the selected frame
+  q, quit                   detach from the isolate";
+
+/// Lines of context shown around the current line.
+const CONTEXT_LINES: i64 = 3;
+
+/// Input to the debugger, either a command typed by the user or an event
+/// from the Debug stream.
+pub enum Input {
+    Command(String),
+    Event(Event),
+}
+
+/// Reads stdin on a background thread and hands each line to whoever is
+/// reading commands, so a debugger can take over from the run loop and give
+/// the console back when it detaches.
+#[derive(Clone)]
+pub struct Console {
+    sink: Arc<Mutex<mpsc::Sender<Input>>>,
+}
+
+impl Console {
+    /// Start reading stdin, delivering lines to the returned receiver.
+    pub fn start() -> (Console, mpsc::Receiver<Input>) {
+        let (sender, receiver) = mpsc::channel();
+        let console = Console {
+            sink: Arc::new(Mutex::new(sender)),
+        };
+        let sink = Arc::clone(&console.sink);
+        std::thread::spawn(move || {
+            let stdin = io::stdin();
+            for line in stdin.lock().lines() {
+                match line {
+                    Ok(line) => {
+                        let _ = sink.lock().unwrap().send(Input::Command(line));
+                    }
+                    Err(_) => return,
+                }
+            }
+        });
+        (console, receiver)
+    }
+
+    /// Pass `input` to the current reader as though it had been typed.
+    pub fn send(&self, input: Input) {
+        let _ = self.sink.lock().unwrap().send(input);
+    }
+
+    /// Deliver input to `sender` from now on, returning the previous reader.
+    fn redirect(&self, sender: mpsc::Sender<Input>) -> mpsc::Sender<Input> {
+        std::mem::replace(&mut *self.sink.lock().unwrap(), sender)
+    }
+}
+
+/// A command line debugger attached to a single isolate.
+pub struct Debugger<'a> {
+    vm_service: &'a mut VMService,
+    methods: &'a mut VMServiceMethods,
+    isolate_id: String,
+    input: mpsc::Receiver<Input>,
+    console: Console,
+
+    /// The reader the console goes back to when the debugger detaches.
+    previous: Option<mpsc::Sender<Input>>,
+    scripts: HashMap<String, Script>,
+    stack: Vec<Frame>,
+    frame: usize,
+    paused: bool,
+    at_async_suspension: bool,
+}
+
+impl<'a> Debugger<'a> {
+    /// Attach to the isolate `isolate_id` and take over reading commands
+    /// from `console` until dropped.
+    pub fn attach(
+        vm_service: &'a mut VMService,
+        methods: &'a mut VMServiceMethods,
+        console: &Console,
+        isolate_id: &str,
+    ) -> Result<Self, ()> {
+        let (sender, receiver) = mpsc::channel();
+        let (event_sender, event_receiver) = mpsc::channel();
+        vm_service.listen(methods, "Debug", event_sender)?;
+        let events = sender.clone();
+        std::thread::spawn(move || {
+            for notification in event_receiver {
+                if events.send(Input::Event(notification.event)).is_err() {
+                    return;
+                }
+            }
+        });
+        let isolate: Isolate = vm_service.call_method(&methods.get_isolate(isolate_id))?;
+        vm_service.set_debugged(isolate_id, true);
+        let mut debugger = Debugger {
+            vm_service: vm_service,
+            methods: methods,
+            isolate_id: isolate_id.to_string(),
+            input: receiver,
+            console: console.clone(),
+            previous: Some(console.redirect(sender)),
+            scripts: HashMap::new(),
+            stack: Vec::new(),
+            frame: 0,
+            paused: false,
+            at_async_suspension: false,
+        };
+        println!("Attached to {} ({}).", isolate.name, isolate.id);
+        if let Some(method) = debugger
+            .methods
+            .get_isolate_group(&isolate.isolate_group_id)
+        {
+            let group: IsolateGroup = debugger.vm_service.call_method(&method)?;
+            println!(
+                "It shares isolate group {} with {} other isolates.",
+                group.name,
+                group.isolates.len().saturating_sub(1)
+            );
+        }
+        if is_pause(isolate.pause_event.kind) {
+            debugger.report_pause(&isolate.pause_event);
+        }
+        Ok(debugger)
+    }
+
+    /// Process commands and events until the user quits.
+    pub fn run(&mut self) -> Result<(), ()> {
+        self.prompt();
+        while let Ok(input) = self.input.recv() {
+            match input {
+                Input::Event(event) => {
+                    let isolate_id = event.isolate.as_ref().map(|x| x.id.as_str());
+                    if isolate_id != Some(self.isolate_id.as_str()) {
+                        continue;
+                    }
+                    if is_pause(event.kind) {
+                        self.report_pause(&event);
+                        self.prompt();
+                    } else if event.kind == EventKind::Resume {
+                        self.paused = false;
+                    }
+                }
+                Input::Command(line) => {
+                    let words: Vec<&str> = line.split_whitespace().collect();
+                    if words.first() == Some(&"q") || words.first() == Some(&"quit") {
+                        return Ok(());
+                    }
+                    let was_paused = self.paused;
+                    if self.on_command(&words).is_err() {
+                        println!("Command failed.");
+                    }
+                    // Resuming commands prompt again once the isolate pauses.
+                    if self.paused || !was_paused {
+                        self.prompt();
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Show where the isolate paused. A failure is reported rather than
+    /// ending the session, the user can still list or resume.
+    fn report_pause(&mut self, event: &Event) {
+        if self.on_paused(event).is_err() {
+            println!("Failed to show where the isolate paused.");
+        }
+    }
+
+    fn prompt(&self) {
+        if self.paused {
+            print!("(paused) ");
+        }
+        print!("> ");
+        io::stdout().flush().unwrap();
+    }
+
+    fn on_command(&mut self, words: &[&str]) -> Result<(), ()> {
+        let command = match words.first() {
+            Some(command) => *command,
+            None => return Ok(()),
+        };
+        let argument = words.get(1).map(|x| *x);
+        match (command, argument) {
+            ("help", _) | ("h", _) => println!("{}", HELP),
+            ("b", Some(location)) | ("break", Some(location)) => self.add_breakpoint(location)?,
+            ("delete", Some(number)) => self.remove_breakpoint(number)?,
+            ("info", _) => self.list_breakpoints()?,
+            ("pause", _) => {
+                self.vm_service
+                    .send_notification(&self.methods.pause(&self.isolate_id))
+                    .map_err(|_| ())?;
+            }
+            ("catch", Some(mode)) => {
+                let mode = parse_pause_mode(mode).ok_or(())?;
+                let method = self.methods.set_exception_pause_mode(&self.isolate_id, mode);
+                self.vm_service.send_notification(&method).map_err(|_| ())?;
+            }
+            ("p", Some(_)) | ("eval", Some(_)) => self.evaluate(&words[1..].join(" "))?,
+            _ if !self.paused => {
+                println!("The isolate is running, use \"pause\" first.");
+            }
+            ("bt", _) | ("backtrace", _) => self.print_stack(),
+            ("f", Some(index)) | ("frame", Some(index)) => {
+                let index = index.parse::<usize>().map_err(|_| ())?;
+                if index >= self.stack.len() {
+                    return Err(());
+                }
+                self.frame = index;
+                self.print_source()?;
+            }
+            ("l", _) | ("list", _) => self.print_source()?,
+            ("v", _) | ("vars", _) => self.print_variables(),
+            ("c", _) | ("continue", _) => self.resume(None, None)?,
+            ("s", _) | ("step", _) => self.resume(Some(StepOption::Into), None)?,
+            ("n", _) | ("next", _) => {
+                let step = if self.at_async_suspension {
+                    StepOption::OverAsyncSuspension
+                } else {
+                    StepOption::Over
+                };
+                self.resume(Some(step), None)?
+            }
+            ("finish", _) => self.resume(Some(StepOption::Out), None)?,
+            ("rewind", Some(index)) => {
+                let index = index.parse::<i64>().map_err(|_| ())?;
+                self.resume(Some(StepOption::Rewind), Some(index))?
+            }
+            _ => println!("Unknown command, try \"help\"."),
+        }
+        Ok(())
+    }
+
+    /// Evaluate in the selected frame, or in the root library while running.
+    fn evaluate(&mut self, expression: &str) -> Result<(), ()> {
+        let result = if self.paused {
+            let frame_index = self.frame as i64;
+            self.vm_service.try_call_method(&self.methods.evaluate_in_frame(
+                &self.isolate_id,
+                frame_index,
+                expression,
+            ))
+        } else {
+            let isolate: Isolate = self
+                .vm_service
+                .call_method(&self.methods.get_isolate(&self.isolate_id))?;
+            let root_lib = isolate.root_lib.ok_or(())?;
+            self.vm_service.try_call_method(&self.methods.evaluate(
+                &self.isolate_id,
+                &root_lib.id,
+                expression,
+            ))
+        };
+        match result {
+            Ok(value) => println!("{}", describe(&value)),
+            Err(error) => println!("{}", describe_error(&error)),
+        }
+        Ok(())
+    }
+
+    fn resume(&mut self, step: Option<StepOption>, frame_index: Option<i64>) -> Result<(), ()> {
+        self.vm_service
+            .send_notification(&self.methods.resume(&self.isolate_id, step, frame_index))
+            .map_err(|_| ())?;
+        self.paused = false;
+        self.stack.clear();
+        Ok(())
+    }
+
+    fn on_paused(&mut self, event: &Event) -> Result<(), ()> {
+        self.paused = true;
+        self.at_async_suspension = event.at_async_suspension.unwrap_or(false);
+        let stack: Stack = self
+            .vm_service
+            .call_method(&self.methods.get_stack(&self.isolate_id))?;
+        self.stack = stack.frames;
+        self.frame = 0;
+        match event.kind {
+            EventKind::PauseBreakpoint => {
+                let numbers: Vec<String> = event
+                    .pause_breakpoints
+                    .iter()
+                    .flat_map(|x| x.iter())
+                    .map(|x| x.breakpoint_number.to_string())
+                    .collect();
+                if numbers.is_empty() {
+                    println!("Paused.");
+                } else {
+                    println!("Paused at breakpoint {}.", numbers.join(", "));
+                }
+            }
+            EventKind::PauseException => match (event.isolate.as_ref(), event.exception.as_ref()) {
+                (Some(isolate), Some(exception)) => {
+                    report_exception(self.vm_service, self.methods, isolate, exception)?
+                }
+                _ => println!("Paused on exception."),
+            },
+            kind => println!("Paused ({:?}).", kind),
+        }
+        if !self.stack.is_empty() {
+            self.print_source()?;
+        }
+        Ok(())
+    }
+
+    fn add_breakpoint(&mut self, location: &str) -> Result<(), ()> {
+        let (file, line) = parse_location(location)?;
+        let uri = script_uri(file);
+        let breakpoint: Breakpoint = match self.find_script(&uri)? {
+            Some(script) => self.vm_service.call_method(&self.methods.add_breakpoint(
+                &self.isolate_id,
+                &script.id,
+                line,
+            ))?,
+            // Not loaded yet, the vm resolves the breakpoint once it is.
+            None => self
+                .vm_service
+                .call_method(&self.methods.add_breakpoint_with_script_uri(
+                    &self.isolate_id,
+                    &uri,
+                    line,
+                ))?,
+        };
+        println!(
+            "Breakpoint {} at {}",
+            breakpoint.breakpoint_number,
+            self.describe_location(breakpoint.source_location())
+        );
+        Ok(())
+    }
+
+    fn remove_breakpoint(&mut self, number: &str) -> Result<(), ()> {
+        let number = number.parse::<i64>().map_err(|_| ())?;
+        let isolate: Isolate = self
+            .vm_service
+            .call_method(&self.methods.get_isolate(&self.isolate_id))?;
+        let breakpoint = isolate
+            .breakpoints
+            .iter()
+            .find(|x| x.breakpoint_number == number)
+            .ok_or(())?;
+        self.vm_service
+            .send_notification(&self.methods.remove_breakpoint(&self.isolate_id, &breakpoint.id))
+            .map_err(|_| ())?;
+        println!("Deleted breakpoint {}.", number);
+        Ok(())
+    }
+
+    fn list_breakpoints(&mut self) -> Result<(), ()> {
+        let isolate: Isolate = self
+            .vm_service
+            .call_method(&self.methods.get_isolate(&self.isolate_id))?;
+        if isolate.breakpoints.is_empty() {
+            println!("No breakpoints.");
+        }
+        for breakpoint in isolate.breakpoints.iter() {
+            let resolved = if breakpoint.resolved { "" } else { " (pending)" };
+            println!(
+                "{:>3}  {}{}",
+                breakpoint.breakpoint_number,
+                self.describe_location(breakpoint.source_location()),
+                resolved
+            );
+        }
+        Ok(())
+    }
+
+    fn print_stack(&mut self) {
+        for (index, frame) in self.stack.clone().iter().enumerate() {
+            let marker = if index == self.frame { "*" } else { " " };
+            let name = frame.function.as_ref().map_or("<unknown>", |x| &x.name);
+            println!(
+                "{}#{:<3} {} ({})",
+                marker,
+                index,
+                name,
+                self.describe_location(frame.location.clone().map(From::from))
+            );
+        }
+    }
+
+    fn print_variables(&self) {
+        let frame = match self.stack.get(self.frame) {
+            Some(frame) => frame,
+            None => return,
+        };
+        let vars = frame.vars.as_ref().map_or(&[][..], |x| x.as_slice());
+        if vars.is_empty() {
+            println!("No variables.");
+        }
+        for variable in vars.iter() {
+            println!("  {} = {}", variable.name, describe(&variable.value));
+        }
+    }
+
+    /// Print the source surrounding the selected frame.
+    fn print_source(&mut self) -> Result<(), ()> {
+        let location = match self.stack.get(self.frame).and_then(|x| x.location.clone()) {
+            Some(location) => location,
+            None => return Ok(()),
+        };
+        let script_id = location.script.id.clone();
+        let line = self.resolve_line(&location.into());
+        let script = self.load_script(&script_id)?;
+        println!("{}:{}", script.uri, line.unwrap_or(0));
+        let (source, line) = match (script.source.as_ref(), line) {
+            (Some(source), Some(line)) => (source, line),
+            _ => return Ok(()),
+        };
+        for (index, text) in source.lines().enumerate() {
+            let current = index as i64 + 1;
+            if (current - line).abs() > CONTEXT_LINES {
+                continue;
+            }
+            let marker = if current == line { "=>" } else { "  " };
+            println!("{} {:>4}  {}", marker, current, text);
+        }
+        Ok(())
+    }
+
+    fn describe_location(&mut self, location: Option<UnresolvedSourceLocation>) -> String {
+        let location = match location {
+            Some(location) => location,
+            None => return String::from("<unknown>"),
+        };
+        let uri = match (location.script.as_ref(), location.script_uri.as_ref()) {
+            (Some(script), _) => script.uri.clone(),
+            (None, Some(uri)) => uri.clone(),
+            (None, None) => String::from("<unknown>"),
+        };
+        match self.resolve_line(&location) {
+            Some(line) => format!("{}:{}", uri, line),
+            None => uri,
+        }
+    }
+
+    fn resolve_line(&mut self, location: &UnresolvedSourceLocation) -> Option<i64> {
+        if location.line.is_some() {
+            return location.line;
+        }
+        let script_id = &location.script.as_ref()?.id;
+        let token_pos = location.token_pos?;
+        self.load_script(script_id).ok()?.line_of(token_pos)
+    }
+
+    fn load_script(&mut self, script_id: &str) -> Result<&Script, ()> {
+        if !self.scripts.contains_key(script_id) {
+            let script: Script = self
+                .vm_service
+                .call_method(&self.methods.get_object(&self.isolate_id, script_id))?;
+            self.scripts.insert(script_id.to_string(), script);
+        }
+        Ok(&self.scripts[script_id])
+    }
+
+    /// Find the loaded script with the uri given by [script_uri].
+    fn find_script(&mut self, uri: &str) -> Result<Option<ScriptRef>, ()> {
+        let scripts: ScriptList = self
+            .vm_service
+            .call_method(&self.methods.get_scripts(&self.isolate_id))?;
+        Ok(scripts.scripts.into_iter().find(|x| x.uri == uri))
+    }
+}
+
+/// Parse an exception pause mode given as none, unhandled or all.
+pub fn parse_pause_mode(mode: &str) -> Option<ExceptionPauseMode> {
+    match mode {
+        "none" => Some(ExceptionPauseMode::None),
+        "unhandled" => Some(ExceptionPauseMode::Unhandled),
+        "all" => Some(ExceptionPauseMode::All),
+        _ => None,
+    }
+}
+
+/// Set the exception pause mode of every live isolate.
+pub fn set_pause_mode(
+    vm_service: &mut VMService,
+    methods: &mut VMServiceMethods,
+    mode: ExceptionPauseMode,
+) -> Result<(), ()> {
+    for state in vm_service.isolates() {
+        vm_service
+            .send_notification(&methods.set_exception_pause_mode(&state.isolate.id, mode))
+            .map_err(|_| ())?;
+    }
+    Ok(())
+}
+
+impl<'a> Drop for Debugger<'a> {
+    fn drop(&mut self) {
+        self.vm_service.set_debugged(&self.isolate_id, false);
+        if let Some(previous) = self.previous.take() {
+            self.console.redirect(previous);
+        }
+    }
+}
+
+/// Report isolates which pause on an exception, on a background thread.
+///
+/// Without a `console` the isolates continue. With one they stay paused and
+/// the pause event is passed to the console's reader, which can attach a
+/// debugger. Isolates a debugger is attached to are left to it.
+pub fn report_exceptions(
+    vm_service: &mut VMService,
+    methods: &mut VMServiceMethods,
+    console: Option<Console>,
+) -> Result<(), ()> {
+    let (sender, receiver) = mpsc::channel();
+    vm_service.listen(methods, "Debug", sender)?;
+    let mut vm_service = vm_service.clone();
+    let mut methods = methods.clone();
+    std::thread::spawn(move || {
+        for notification in receiver {
+            let event = notification.event;
+            if event.kind != EventKind::PauseException {
+                continue;
+            }
+            let isolate = match event.isolate.clone() {
+                Some(isolate) => isolate,
+                None => continue,
+            };
+            if vm_service.is_debugged(&isolate.id) {
+                continue;
+            }
+            match console.as_ref() {
+                // The debugger reports the exception when it attaches.
+                Some(console) => {
+                    println!("{} paused on an exception.", isolate.name);
+                    console.send(Input::Event(event));
+                }
+                None => {
+                    if let Some(exception) = event.exception.as_ref() {
+                        let _ =
+                            report_exception(&mut vm_service, &mut methods, &isolate, exception);
+                    }
+                    let _ = vm_service.send_notification(&methods.resume(&isolate.id, None, None));
+                }
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Print an exception an isolate is paused on, what its toString returns,
+/// and the stack with source lines.
+pub fn report_exception(
+    vm_service: &mut VMService,
+    methods: &mut VMServiceMethods,
+    isolate: &IsolateRef,
+    exception: &InstanceRef,
+) -> Result<(), ()> {
+    let value = serde_json::to_value(exception).unwrap_or_default();
+    println!("{} paused on exception {}.", isolate.name, describe(&value));
+    let text = vm_service.try_call_method::<serde_json::Value>(&methods.evaluate(
+        &isolate.id,
+        &exception.id,
+        "toString()",
+    ));
+    match text {
+        Ok(ref text) if text["valueAsString"].is_string() => {
+            println!("{}", text["valueAsString"].as_str().unwrap());
+        }
+        Ok(text) => println!("{}", describe(&text)),
+        Err(error) => println!("toString() failed: {}", describe_error(&error)),
+    }
+    let stack: Stack = vm_service.call_method(&methods.get_stack(&isolate.id))?;
+    let mut scripts: HashMap<String, Script> = HashMap::new();
+    for (index, frame) in stack.frames.iter().enumerate() {
+        let name = frame.function.as_ref().map_or("<unknown>", |x| &x.name);
+        let location = match frame.location.as_ref() {
+            Some(location) => location,
+            None => {
+                println!("#{:<3} {}", index, name);
+                continue;
+            }
+        };
+        let script = &location.script;
+        let line = match location.line {
+            Some(line) => Some(line),
+            None => {
+                if !scripts.contains_key(&script.id) {
+                    let loaded: Script =
+                        vm_service.call_method(&methods.get_object(&isolate.id, &script.id))?;
+                    scripts.insert(script.id.clone(), loaded);
+                }
+                scripts[&script.id].line_of(location.token_pos)
+            }
+        };
+        let uri = &script.uri;
+        match line {
+            Some(line) => println!("#{:<3} {} ({}:{})", index, name, uri, line),
+            None => println!("#{:<3} {} ({})", index, name, uri),
+        }
+    }
+    Ok(())
+}
+
+/// Resume every isolate paused at start, first adding `breakpoints` given as
+/// `<file>:<line>`, and let isolates paused at exit finish exiting.
+///
+/// Hot reload fails while an isolate is paused, so this runs after
+/// connecting and before each reload.
+pub fn resume_paused_isolates(
+    vm_service: &mut VMService,
+    methods: &mut VMServiceMethods,
+    breakpoints: &[String],
+) -> Result<(), ()> {
+    'isolates: for state in vm_service.isolates() {
+        // Isolates may exit at any point, after which calls on them answer
+        // with a Sentinel or, once the exit event has arrived, fail.
+        let method = methods.get_isolate(&state.isolate.id);
+        let isolate: Isolate = match call_isolate(vm_service, &state, &method)? {
+            Some(isolate) => isolate,
+            None => continue,
+        };
+        match isolate.pause_event.kind {
+            EventKind::PauseStart => {
+                for location in breakpoints.iter() {
+                    let (file, line) = match parse_location(location) {
+                        Ok(location) => location,
+                        Err(()) => {
+                            println!(
+                                "Breakpoints must be given as <file>:<line>, not {}",
+                                location
+                            );
+                            return Err(());
+                        }
+                    };
+                    let uri = script_uri(file);
+                    let method = methods.add_breakpoint_with_script_uri(&isolate.id, &uri, line);
+                    let breakpoint: Breakpoint = match call_isolate(vm_service, &state, &method)? {
+                        Some(breakpoint) => breakpoint,
+                        None => continue 'isolates,
+                    };
+                    println!(
+                        "Breakpoint {} at {}:{} in {}",
+                        breakpoint.breakpoint_number, uri, line, isolate.name
+                    );
+                }
+                let method = methods.resume(&isolate.id, None, None);
+                if call_isolate::<serde_json::Value>(vm_service, &state, &method)?.is_some() {
+                    println!("Resumed {}, which was paused at start.", isolate.name);
+                }
+            }
+            EventKind::PauseExit => {
+                // The isolate is gone once resumed, and dropped from the views
+                // when its exit event arrives.
+                println!("{} has exited.", isolate.name);
+                let method = methods.resume(&isolate.id, None, None);
+                call_isolate::<serde_json::Value>(vm_service, &state, &method)?;
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Call a method on a tracked isolate, returning None if the isolate has
+/// exited. Errors fail only while the isolate is still alive.
+fn call_isolate<T>(
+    vm_service: &mut VMService,
+    state: &IsolateState,
+    method: &VMServiceMethod,
+) -> Result<Option<T>, ()>
+where
+    T: serde::de::DeserializeOwned,
+{
+    match vm_service.try_call_method::<serde_json::Value>(method) {
+        Ok(ref response) if response["type"] == "Sentinel" => Ok(None),
+        Ok(response) => serde_json::from_value(response).map(Some).map_err(|err| {
+            println!("Unexpected response from the vm service: {}", err);
+        }),
+        Err(_) if vm_service.isolate(&state.isolate.id).is_none() => Ok(None),
+        Err(error) => {
+            println!(
+                "Call on {} failed: {}",
+                state.isolate.name,
+                describe_error(&error)
+            );
+            Err(())
+        }
+    }
+}
+
+/// Split a location such as `lib/main.dart:12` into the file and the line.
+fn parse_location(location: &str) -> Result<(&str, i64), ()> {
+    let split = location.rfind(':').ok_or(())?;
+    let line = location[split + 1..].parse::<i64>().map_err(|_| ())?;
+    Ok((&location[..split], line))
+}
+
+/// The uri a script is loaded from, which for files under lib/ is a
+/// package: uri. Files outside the project are left as they are.
+fn script_uri(file: &str) -> String {
+    if file.contains("://") || file.starts_with("package:") || file.starts_with("dart:") {
+        return file.to_string();
+    }
+    let package = read_package_name(std::path::Path::new("pubspec.yaml"));
+    match (file.strip_prefix("lib/"), package) {
+        (Some(path), Ok(package)) => format!("package:{}/{}", package, path),
+        _ => match std::fs::canonicalize(file) {
+            Ok(path) => format!("file://{}", path.display()),
+            Err(_) => file.to_string(),
+        },
+    }
+}
+
+fn is_pause(kind: EventKind) -> bool {
+    match kind {
+        EventKind::PauseStart
+        | EventKind::PauseExit
+        | EventKind::PauseBreakpoint
+        | EventKind::PauseInterrupted
+        | EventKind::PauseException
+        | EventKind::PausePostRequest => true,
+        _ => false,
+    }
+}
+
+/// Describe an instance, sentinel or other object reference for display.
+pub fn describe(value: &serde_json::Value) -> String {
+    let class_name = value["class"]["name"].as_str().unwrap_or("Object");
+    match (value["type"].as_str(), value["valueAsString"].as_str()) {
+        // The vm usually brackets sentinels already, as in `<optimized out>`.
+        (Some("Sentinel"), Some(text)) if text.starts_with('<') => text.to_string(),
+        (Some("Sentinel"), Some(text)) => format!("<{}>", text),
+        (Some("@Instance"), Some(text)) | (Some("Instance"), Some(text)) => {
+            let truncated = if value["valueAsStringIsTruncated"] == true { "..." } else { "" };
+            if value["kind"] == "String" {
+                format!("\"{}{}\"", text, truncated)
+            } else {
+                format!("{}{}", text, truncated)
+            }
+        }
+        (Some("@Instance"), None) | (Some("Instance"), None) => match value["length"].as_i64() {
+            Some(length) => format!("{} (length {})", class_name, length),
+            None => format!("instance of {}", class_name),
+        },
+        (Some("@Error"), _) | (Some("Error"), _) => {
+            value["message"].as_str().unwrap_or("error").to_string()
+        }
+        (Some(kind), _) => match value["name"].as_str() {
+            Some(name) => format!("{} {}", kind.trim_start_matches('@'), name),
+            None => kind.trim_start_matches('@').to_string(),
+        },
+        (None, _) => value.to_string(),
+    }
+}
+
+/// Describe an error object returned by the vm service.
+pub fn describe_error(error: &serde_json::Value) -> String {
+    match (error["data"]["details"].as_str(), error["message"].as_str()) {
+        (Some(details), _) => details.to_string(),
+        (None, Some(message)) => message.to_string(),
+        (None, None) => String::from("Unknown error"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_locations() {
+        assert_eq!(parse_location("lib/main.dart:12"), Ok(("lib/main.dart", 12)));
+        assert_eq!(
+            parse_location("package:app/src/home.dart:3"),
+            Ok(("package:app/src/home.dart", 3))
+        );
+        assert_eq!(parse_location("lib/main.dart"), Err(()));
+        assert_eq!(parse_location("lib/main.dart:x"), Err(()));
+    }
+
+    #[test]
+    fn parses_pause_modes() {
+        assert_eq!(parse_pause_mode("none"), Some(ExceptionPauseMode::None));
+        assert_eq!(parse_pause_mode("unhandled"), Some(ExceptionPauseMode::Unhandled));
+        assert_eq!(parse_pause_mode("all"), Some(ExceptionPauseMode::All));
+        assert_eq!(parse_pause_mode("All"), None);
+    }
+
+    #[test]
+    fn uris_are_left_alone() {
+        assert_eq!(script_uri("package:app/main.dart"), "package:app/main.dart");
+        assert_eq!(script_uri("dart:core/object.dart"), "dart:core/object.dart");
+        assert_eq!(script_uri("file:///app/lib/main.dart"), "file:///app/lib/main.dart");
+    }
+
+    #[test]
+    fn describes_values() {
+        let string = serde_json::json!({
+            "type": "@Instance",
+            "kind": "String",
+            "valueAsString": "hello",
+            "valueAsStringIsTruncated": true,
+        });
+        assert_eq!(describe(&string), "\"hello...\"");
+        let sentinel = serde_json::json!({"type": "Sentinel", "valueAsString": "<optimized out>"});
+        assert_eq!(describe(&sentinel), "<optimized out>");
+        let collected = serde_json::json!({"type": "Sentinel", "valueAsString": "collected"});
+        assert_eq!(describe(&collected), "<collected>");
+    }
+
+    #[test]
+    fn console_input_goes_to_the_current_reader() {
+        let (run_loop, run_loop_input) = mpsc::channel();
+        let console = Console {
+            sink: Arc::new(Mutex::new(run_loop)),
+        };
+        let (debugger, debugger_input) = mpsc::channel();
+        let previous = console.redirect(debugger);
+        console.send(Input::Command(String::from("bt")));
+        match debugger_input.try_recv() {
+            Ok(Input::Command(line)) => assert_eq!(line, "bt"),
+            _ => panic!("the debugger did not get the command"),
+        }
+        assert!(run_loop_input.try_recv().is_err());
+
+        // Detaching hands the console back.
+        console.redirect(previous);
+        console.send(Input::Command(String::from("r")));
+        assert!(debugger_input.try_recv().is_err());
+        assert!(run_loop_input.try_recv().is_ok());
+    }
+}
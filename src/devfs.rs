@@ -1,7 +1,17 @@
+use std::collections::{HashSet, VecDeque};
 use std::fs;
+use std::io::Write;
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use super::memory::format_bytes;
+use super::upload::*;
 
 pub struct DevFS {
     entries: Vec<DevFSEntry>,
+
+    /// The paths of `entries`, so files are only tracked once.
+    paths: HashSet<std::path::PathBuf>,
 }
 
 impl DevFS {
@@ -9,16 +19,51 @@ impl DevFS {
         if !root.is_dir() {
             return Err(());
         }
-        let current_time = std::time::SystemTime::now();
-        let mut entries: Vec<DevFSEntry> = Vec::new();
-        DevFS::visit_dir(&mut entries, root, &current_time);
-        Ok(DevFS { entries: entries })
+        let mut devfs = DevFS {
+            entries: Vec::new(),
+            paths: HashSet::new(),
+        };
+        devfs.visit_dir(root, &SystemTime::now(), true);
+        Ok(devfs)
+    }
+
+    /// Track a file which is uploaded but not compiled, such as the package
+    /// config or a dill.
+    pub fn add_file(&mut self, path: &std::path::Path) -> Result<(), ()> {
+        if !path.is_file() {
+            return Err(());
+        }
+        self.track(path.to_path_buf(), SystemTime::now(), false);
+        Ok(())
+    }
+
+    /// Track a file uploaded to `device_uri` rather than its project path,
+    /// such as the compiler's output. Adding it again marks it for upload
+    /// even if its modification time is unchanged.
+    pub fn add_file_as(&mut self, path: &std::path::Path, device_uri: &str) -> Result<(), ()> {
+        if !path.is_file() {
+            return Err(());
+        }
+        self.track(path.to_path_buf(), SystemTime::now(), false);
+        let entry = self.entries.iter_mut().find(|x| x.path == path).unwrap();
+        entry.device_uri = device_uri.to_string();
+        entry.uploaded = None;
+        Ok(())
+    }
+
+    /// Track every file under `root`, such as the assets directory, for upload.
+    pub fn add_dir(&mut self, root: &std::path::Path) -> Result<(), ()> {
+        if !root.is_dir() {
+            return Err(());
+        }
+        self.visit_dir(root, &SystemTime::now(), false);
+        Ok(())
     }
 
     /// Return an iterator over the updated entries.
     pub fn updated_entries(&mut self) -> impl Iterator<Item = &std::path::Path> {
         self.entries.iter_mut().filter_map(|x| {
-            if x.is_dirty() {
+            if x.source && x.is_dirty() {
                 Some(x.path.as_path())
             } else {
                 None
@@ -26,21 +71,163 @@ impl DevFS {
         })
     }
 
+    /// Upload every entry which changed since it was last uploaded, using up
+    /// to `concurrency` connections at once.
+    ///
+    /// With `progress`, it is printed on a single line as uploads complete.
+    /// Entries which fail to upload stay dirty and are retried by the next sync.
+    pub fn sync(
+        &mut self,
+        uploader: &Uploader,
+        fs_name: &str,
+        concurrency: usize,
+        progress: bool,
+    ) -> SyncSummary {
+        let start = Instant::now();
+        let pending: VecDeque<(usize, std::path::PathBuf, String, SystemTime)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| {
+                entry
+                    .needs_upload()
+                    .map(|x| (index, entry.path.clone(), entry.device_uri.clone(), x))
+            })
+            .collect();
+        let total = pending.len();
+        let queue = Arc::new(Mutex::new(pending));
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..concurrency.max(1).min(total) {
+            let queue = Arc::clone(&queue);
+            let sender = sender.clone();
+            let uploader = uploader.clone();
+            let fs_name = fs_name.to_string();
+            std::thread::spawn(move || loop {
+                let next = queue.lock().unwrap().pop_front();
+                let (index, path, device_uri, modified) = match next {
+                    Some(next) => next,
+                    None => return,
+                };
+                let result = uploader.upload(&path, &device_uri, &fs_name);
+                if sender.send((index, path, modified, result)).is_err() {
+                    return;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut summary = SyncSummary {
+            files: 0,
+            failed: Vec::new(),
+            bytes_read: 0,
+            bytes_sent: 0,
+            elapsed: Duration::from_secs(0),
+        };
+        for (index, path, modified, result) in receiver {
+            match result {
+                Ok(report) => {
+                    self.entries[index].uploaded = Some(modified);
+                    summary.files += 1;
+                    summary.bytes_read += report.bytes_read;
+                    summary.bytes_sent += report.bytes_sent;
+                }
+                Err(()) => summary.failed.push(path),
+            }
+            summary.elapsed = start.elapsed();
+            if !progress {
+                continue;
+            }
+            print!(
+                "\rSyncing {}/{} files, {} sent ({}/s)",
+                summary.files + summary.failed.len(),
+                total,
+                format_bytes(summary.bytes_sent as i64),
+                format_bytes(summary.throughput() as i64)
+            );
+            std::io::stdout().flush().unwrap();
+        }
+        if progress && total > 0 {
+            println!();
+        }
+        summary.elapsed = start.elapsed();
+        summary
+    }
+
     fn visit_dir(
-        entries: &mut Vec<DevFSEntry>,
+        &mut self,
         current: &std::path::Path,
         current_time: &std::time::SystemTime,
+        source: bool,
     ) {
         for entry in fs::read_dir(current).unwrap() {
             let path = entry.unwrap().path();
             if path.is_dir() {
-                DevFS::visit_dir(entries, path.as_path(), current_time);
+                self.visit_dir(path.as_path(), current_time, source);
                 continue;
             }
-            entries.push(DevFSEntry {
-                path: path,
-                modified: *current_time,
-            });
+            self.track(path, *current_time, source);
+        }
+    }
+
+    /// Add an entry for `path` unless it is already tracked.
+    fn track(&mut self, path: std::path::PathBuf, modified: SystemTime, source: bool) {
+        if self.paths.insert(path.clone()) {
+            self.entries.push(DevFSEntry::new(path, modified, source));
+        }
+    }
+}
+
+/// The devfs uri of a local path, which mirrors the project layout.
+fn device_uri(path: &std::path::Path) -> String {
+    let components: Vec<String> = path
+        .components()
+        .filter_map(|x| match x {
+            std::path::Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect();
+    components.join("/")
+}
+
+/// The result of a [DevFS::sync].
+#[derive(Debug)]
+pub struct SyncSummary {
+    /// Number of files uploaded.
+    pub files: usize,
+
+    /// Files which could not be uploaded.
+    pub failed: Vec<std::path::PathBuf>,
+
+    /// Size of the uploaded files.
+    pub bytes_read: usize,
+
+    /// Compressed bytes sent.
+    pub bytes_sent: usize,
+
+    pub elapsed: Duration,
+}
+
+impl SyncSummary {
+    /// Compressed bytes sent per second.
+    pub fn throughput(&self) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds > 0.0 {
+            self.bytes_sent as f64 / seconds
+        } else {
+            0.0
+        }
+    }
+
+    pub fn print(&self) {
+        println!(
+            "Synced {} files ({} compressed to {}) in {:.1}s.",
+            self.files,
+            format_bytes(self.bytes_read as i64),
+            format_bytes(self.bytes_sent as i64),
+            self.elapsed.as_secs_f64()
+        );
+        for path in self.failed.iter() {
+            println!("Failed to upload {}", path.display());
         }
     }
 }
@@ -49,9 +236,28 @@ impl DevFS {
 pub struct DevFSEntry {
     path: std::path::PathBuf,
     modified: std::time::SystemTime,
+
+    /// Where the file is uploaded to, relative to the devfs root.
+    device_uri: String,
+
+    /// Whether this is a source file the compiler needs to know about.
+    source: bool,
+
+    /// The modification time of the file when it was last uploaded.
+    uploaded: Option<SystemTime>,
 }
 
 impl DevFSEntry {
+    fn new(path: std::path::PathBuf, modified: SystemTime, source: bool) -> Self {
+        DevFSEntry {
+            device_uri: device_uri(&path),
+            path: path,
+            modified: modified,
+            source: source,
+            uploaded: None,
+        }
+    }
+
     pub fn is_dirty(&mut self) -> bool {
         let metadata = fs::metadata(&self.path).unwrap();
         let modified = metadata.modified().unwrap();
@@ -62,4 +268,102 @@ impl DevFSEntry {
             false
         }
     }
+
+    /// The current modification time if the file changed since it was last
+    /// uploaded, or was never uploaded.
+    ///
+    /// Sources are never uploaded, the compiler's output carries them.
+    fn needs_upload(&self) -> Option<SystemTime> {
+        if self.source {
+            return None;
+        }
+        let modified = fs::metadata(&self.path).and_then(|x| x.modified()).ok()?;
+        match self.uploaded {
+            Some(uploaded) if uploaded >= modified => None,
+            _ => Some(modified),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    #[test]
+    fn tracks_each_file_once() {
+        let dir = TempDir::with_files(&["lib/main.dart", "lib/src/home.dart", "assets/a.png"]);
+        let lib = dir.join("lib");
+        let mut devfs = DevFS::init(&lib).unwrap();
+        assert_eq!(devfs.entries.len(), 2);
+
+        devfs.add_dir(&lib).unwrap();
+        devfs.add_file(&lib.join("main.dart")).unwrap();
+        devfs.add_dir(&dir.join("assets")).unwrap();
+        devfs.add_file(&dir.join("assets/a.png")).unwrap();
+        assert_eq!(devfs.entries.len(), 3);
+        // Files first seen as sources stay sources.
+        let main = devfs
+            .entries
+            .iter()
+            .find(|x| x.path == lib.join("main.dart"))
+            .unwrap();
+        assert!(main.source);
+
+        assert!(devfs.add_file(&dir.join("missing.dart")).is_err());
+        assert!(DevFS::init(&dir.join("missing")).is_err());
+    }
+
+    #[test]
+    fn sources_are_not_uploaded() {
+        let dir = TempDir::with_files(&["lib/main.dart", "assets/a.png"]);
+        let mut devfs = DevFS::init(&dir.join("lib")).unwrap();
+        devfs.add_dir(&dir.join("assets")).unwrap();
+        let pending: Vec<&std::path::Path> = devfs
+            .entries
+            .iter()
+            .filter(|x| x.needs_upload().is_some())
+            .map(|x| x.path.as_path())
+            .collect();
+        assert_eq!(pending, vec![dir.join("assets/a.png").as_path()]);
+
+        // Once uploaded an asset waits until it changes again.
+        let modified = devfs.entries[1].needs_upload().unwrap();
+        devfs.entries[1].uploaded = Some(modified);
+        assert!(devfs.entries[1].needs_upload().is_none());
+    }
+
+    #[test]
+    fn compiler_output_is_uploaded_to_its_device_uri() {
+        let dir = TempDir::with_files(&["lib/main.dart", "build/app.dill"]);
+        let mut devfs = DevFS::init(&dir.join("lib")).unwrap();
+        let dill = dir.join("build/app.dill");
+        devfs
+            .add_file_as(&dill, "lib/main.dart.incremental.dill")
+            .unwrap();
+        let entry = devfs.entries.iter().find(|x| x.path == dill).unwrap();
+        assert_eq!(entry.device_uri, "lib/main.dart.incremental.dill");
+        let modified = entry.needs_upload().unwrap();
+
+        // A new compile is uploaded even within the same modification time.
+        devfs.entries[1].uploaded = Some(modified);
+        assert!(devfs.entries[1].needs_upload().is_none());
+        devfs
+            .add_file_as(&dill, "lib/main.dart.incremental.dill")
+            .unwrap();
+        assert_eq!(devfs.entries.len(), 2);
+        assert!(devfs.entries[1].needs_upload().is_some());
+    }
+
+    #[test]
+    fn device_uris_mirror_the_project() {
+        assert_eq!(
+            device_uri(std::path::Path::new("lib/src/home.dart")),
+            "lib/src/home.dart"
+        );
+        assert_eq!(
+            device_uri(std::path::Path::new("./assets/a.png")),
+            "assets/a.png"
+        );
+    }
 }
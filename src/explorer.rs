@@ -0,0 +1,324 @@
+use super::debugger::{describe, describe_error};
+use super::vmservice::*;
+
+/// Number of elements or associations fetched at once.
+const PAGE_SIZE: i64 = 100;
+
+/// Number of inbound references fetched.
+const REFERENCE_LIMIT: i64 = 20;
+
+/// Walks the objects of an isolate one level at a time.
+///
+/// The fields, elements or captured variables of the current object are
+/// printed numbered, so they can be opened in turn.
+pub struct Explorer {
+    /// The ids of the objects opened so far, the current one last.
+    path: Vec<String>,
+
+    /// The object ids of the printed children, by number.
+    ids: Vec<String>,
+
+    /// Where the next page of the current list or map starts.
+    offset: i64,
+}
+
+impl Explorer {
+    pub fn new() -> Self {
+        Explorer {
+            path: Vec::new(),
+            ids: Vec::new(),
+            offset: 0,
+        }
+    }
+
+    /// Start over from `target`, an object id such as `objects/123` or an
+    /// expression evaluated in the root library.
+    pub fn open(
+        &mut self,
+        vm_service: &mut VMService,
+        methods: &mut VMServiceMethods,
+        isolate_id: &str,
+        target: &str,
+    ) -> Result<(), ()> {
+        let object_id = if is_object_id(target) {
+            target.to_string()
+        } else {
+            let isolate: Isolate = vm_service.call_method(&methods.get_isolate(isolate_id))?;
+            let root_lib = match isolate.root_lib {
+                Some(root_lib) => root_lib,
+                None => {
+                    println!("The isolate has no root library to evaluate in.");
+                    return Err(());
+                }
+            };
+            let value: serde_json::Value = vm_service
+                .try_call_method(&methods.evaluate(isolate_id, &root_lib.id, target))
+                .map_err(|err| println!("{}", describe_error(&err)))?;
+            match value["id"].as_str() {
+                Some(id) => id.to_string(),
+                None => {
+                    println!("{}", describe(&value));
+                    return Err(());
+                }
+            }
+        };
+        self.path.clear();
+        self.visit(vm_service, methods, isolate_id, object_id)
+    }
+
+    /// Open the child numbered `index`.
+    pub fn open_child(
+        &mut self,
+        vm_service: &mut VMService,
+        methods: &mut VMServiceMethods,
+        isolate_id: &str,
+        index: usize,
+    ) -> Result<(), ()> {
+        let id = match self.ids.get(index) {
+            Some(id) => id.clone(),
+            None => {
+                println!("Nothing numbered {}.", index);
+                return Err(());
+            }
+        };
+        self.visit(vm_service, methods, isolate_id, id)
+    }
+
+    /// Go back to the object opened before the current one.
+    pub fn back(
+        &mut self,
+        vm_service: &mut VMService,
+        methods: &mut VMServiceMethods,
+        isolate_id: &str,
+    ) -> Result<(), ()> {
+        if self.path.len() < 2 {
+            println!("There is no previous object.");
+            return Err(());
+        }
+        let opened = self.path.split_off(self.path.len() - 2);
+        let result = self.visit(vm_service, methods, isolate_id, opened[0].clone());
+        if result.is_err() {
+            self.path.extend(opened);
+        }
+        result
+    }
+
+    /// Print the next page of the current list or map.
+    pub fn more(
+        &mut self,
+        vm_service: &mut VMService,
+        methods: &mut VMServiceMethods,
+        isolate_id: &str,
+    ) -> Result<(), ()> {
+        let id = self.current()?;
+        let object: serde_json::Value = vm_service
+            .try_call_method(&methods.get_object_range(isolate_id, &id, self.offset, PAGE_SIZE))
+            .map_err(|err| println!("{}", describe_error(&err)))?;
+        match instance(object)? {
+            Some(ref instance) if instance.elements.is_some() || instance.associations.is_some() => {
+                self.print_page(instance);
+                Ok(())
+            }
+            _ => {
+                println!("Only lists and maps have more.");
+                Err(())
+            }
+        }
+    }
+
+    /// Print what the current object's toString returns.
+    pub fn to_string(
+        &mut self,
+        vm_service: &mut VMService,
+        methods: &mut VMServiceMethods,
+        isolate_id: &str,
+    ) -> Result<(), ()> {
+        let id = self.current()?;
+        let value: serde_json::Value = vm_service
+            .try_call_method(&methods.invoke(isolate_id, &id, "toString", &[]))
+            .map_err(|err| println!("toString() failed: {}", describe_error(&err)))?;
+        println!("{}", describe(&value));
+        Ok(())
+    }
+
+    /// Print the objects which refer to the current one, numbered so they can
+    /// be opened.
+    pub fn references(
+        &mut self,
+        vm_service: &mut VMService,
+        methods: &mut VMServiceMethods,
+        isolate_id: &str,
+    ) -> Result<(), ()> {
+        let id = self.current()?;
+        let inbound: InboundReferences = vm_service
+            .try_call_method(&methods.get_inbound_references(isolate_id, &id, REFERENCE_LIMIT))
+            .map_err(|err| println!("{}", describe_error(&err)))?;
+        self.ids.clear();
+        if inbound.references.is_empty() {
+            println!("Nothing refers to {}.", id);
+        }
+        for reference in inbound.references.iter() {
+            let via = match (reference.parent_field.as_ref(), reference.parent_list_index) {
+                (Some(field), _) => match field["name"].as_str() {
+                    Some(name) => format!(".{}", name),
+                    None => format!(".{}", field),
+                },
+                (None, Some(index)) => format!("[{}]", index),
+                (None, None) => String::from("<reference>"),
+            };
+            self.print_child(&via, &reference.source);
+        }
+        Ok(())
+    }
+
+    fn current(&self) -> Result<String, ()> {
+        match self.path.last() {
+            Some(id) => Ok(id.clone()),
+            None => {
+                println!("Nothing is open. Start with \"explore <object id | expression>\".");
+                Err(())
+            }
+        }
+    }
+
+    /// Print `object_id` and make it the current object.
+    fn visit(
+        &mut self,
+        vm_service: &mut VMService,
+        methods: &mut VMServiceMethods,
+        isolate_id: &str,
+        object_id: String,
+    ) -> Result<(), ()> {
+        let object: serde_json::Value = vm_service
+            .try_call_method(&methods.get_object_range(isolate_id, &object_id, 0, PAGE_SIZE))
+            .map_err(|err| println!("{}", describe_error(&err)))?;
+        self.ids.clear();
+        self.offset = 0;
+        println!("{}  ({})", describe(&object), object_id);
+        match object["type"].as_str() {
+            Some("Instance") => {
+                let instance = instance(object)?
+                    .ok_or_else(|| println!("{} is not an instance.", object_id))?;
+                self.print_instance(&instance);
+            }
+            Some("Context") => {
+                let context: Context = serde_json::from_value(object)
+                    .map_err(|err| println!("Unexpected context: {}", err))?;
+                for (index, variable) in context.variables.iter().enumerate() {
+                    self.print_child(&format!("[{}]", index), &variable.value);
+                }
+                if let Some(ref parent) = context.parent {
+                    self.print_child("parent", &serde_json::to_value(parent).unwrap_or_default());
+                }
+            }
+            _ => {}
+        }
+        self.path.push(object_id);
+        Ok(())
+    }
+
+    fn print_instance(&mut self, instance: &Instance) {
+        for field in instance.fields.iter().flatten() {
+            self.print_child(&field.name(), &field.value);
+        }
+        if let Some(ref function) = instance.closure_function {
+            println!("       function {}", function.name);
+        }
+        if let Some(ref context) = instance.closure_context {
+            self.print_child(
+                "context",
+                &serde_json::to_value(context).unwrap_or_default(),
+            );
+        }
+        self.print_page(instance);
+    }
+
+    /// Print the elements or associations returned with `instance`, and
+    /// note how many are left.
+    fn print_page(&mut self, instance: &Instance) {
+        if instance.elements.is_none() && instance.associations.is_none() {
+            return;
+        }
+        let offset = instance.offset.unwrap_or(0);
+        if let Some(elements) = instance.elements.as_ref().and_then(|x| x.as_array()) {
+            for (index, element) in elements.iter().enumerate() {
+                self.print_child(&format!("[{}]", offset + index as i64), element);
+            }
+        }
+        if let Some(ref associations) = instance.associations {
+            for association in associations.iter() {
+                self.print_child(&format!("[{}]", describe(&association.key)), &association.value);
+            }
+        }
+        let count = instance.count.unwrap_or(0);
+        self.offset = offset + count;
+        if let Some(length) = instance.length {
+            if instance.count.is_some() && self.offset < length {
+                println!(
+                    "       {} of {} shown, \"explore more\" for the next {}.",
+                    self.offset,
+                    length,
+                    PAGE_SIZE.min(length - self.offset)
+                );
+            }
+        }
+    }
+
+    /// Print a named value, numbered when it is an object which can be opened.
+    fn print_child(&mut self, name: &str, value: &serde_json::Value) {
+        let number = match value["id"].as_str() {
+            Some(id) if value["type"] != "Sentinel" => {
+                self.ids.push(id.to_string());
+                format!("{:>4}", self.ids.len() - 1)
+            }
+            _ => "    ".to_string(),
+        };
+        println!("{}   {} = {}", number, name, describe(value));
+    }
+}
+
+/// The kinds of object id the vm service hands out.
+const OBJECT_ID_PREFIXES: &[&str] = &[
+    "objects/",
+    "classes/",
+    "libraries/",
+    "scripts/",
+    "code/",
+    "typearguments/",
+];
+
+/// Whether `target` is an object id like `objects/123` rather than an
+/// expression such as `count/2`.
+pub fn is_object_id(target: &str) -> bool {
+    OBJECT_ID_PREFIXES
+        .iter()
+        .any(|x| target.len() > x.len() && target.starts_with(x))
+        && !target.contains(char::is_whitespace)
+}
+
+/// The instance described by a getObject response, or None for other
+/// objects.
+fn instance(object: serde_json::Value) -> Result<Option<Instance>, ()> {
+    if object["type"] != "Instance" {
+        return Ok(None);
+    }
+    serde_json::from_value(object)
+        .map(Some)
+        .map_err(|err| println!("Unexpected instance: {}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn object_ids_are_told_from_expressions() {
+        assert!(is_object_id("objects/123"));
+        assert!(is_object_id("classes/45/functions/build"));
+        assert!(is_object_id("libraries/@1234/scripts/main.dart/0"));
+        assert!(!is_object_id("count/2"));
+        assert!(!is_object_id("objects/"));
+        assert!(!is_object_id("objects/1 + 1"));
+        assert!(!is_object_id("items.length"));
+    }
+}
@@ -0,0 +1,227 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{mpsc, Arc, Mutex};
+
+use super::vmservice::*;
+
+/// Time available to build or rasterize a frame at 60fps, in microseconds.
+const FRAME_BUDGET_MICROS: i64 = 16_667;
+
+/// Number of recent frames the statistics are computed over.
+const WINDOW: usize = 300;
+
+/// The timings of one frame from a `Flutter.Frame` event, in microseconds.
+#[derive(Deserialize, Debug, Clone)]
+pub struct FrameTiming {
+    pub number: i64,
+    pub build: i64,
+    pub raster: i64,
+
+    /// The view which rendered the frame, sent by engines with multiple
+    /// views.
+    #[serde(rename = "viewId", default)]
+    pub view_id: Option<i64>,
+}
+
+impl FrameTiming {
+    /// Whether either thread went over the frame budget.
+    pub fn is_janky(&self) -> bool {
+        self.build > FRAME_BUDGET_MICROS || self.raster > FRAME_BUDGET_MICROS
+    }
+
+    /// The name the frame's statistics are kept under: its view, or the
+    /// isolate which rendered it when the event has no view.
+    fn source(&self, isolate: &str) -> String {
+        match self.view_id {
+            Some(view_id) => format!("{} view {}", isolate, view_id),
+            None => isolate.to_string(),
+        }
+    }
+}
+
+/// Rolling frame statistics of one view.
+#[derive(Debug, Default)]
+pub struct FrameStats {
+    /// The most recent frames, oldest first.
+    recent: VecDeque<FrameTiming>,
+
+    /// Frames seen since the statistics were last reset.
+    total: usize,
+
+    /// Janky frames seen since the statistics were last reset.
+    janky: usize,
+
+    /// The janky frame with the longest build or raster time.
+    worst: Option<FrameTiming>,
+}
+
+impl FrameStats {
+    fn record(&mut self, frame: FrameTiming) {
+        self.total += 1;
+        if frame.is_janky() {
+            self.janky += 1;
+            let longest = frame.build.max(frame.raster);
+            if self.worst.as_ref().map_or(true, |x| longest > x.build.max(x.raster)) {
+                self.worst = Some(frame.clone());
+            }
+        }
+        if self.recent.len() == WINDOW {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(frame);
+    }
+
+    pub fn print(&self, view: &str) {
+        if self.total == 0 {
+            println!("{}: no frames.", view);
+            return;
+        }
+        println!(
+            "{}: {} of {} frames over the {:.1}ms budget ({:.1}%)",
+            view,
+            self.janky,
+            self.total,
+            FRAME_BUDGET_MICROS as f64 / 1000.0,
+            self.janky as f64 * 100.0 / self.total as f64
+        );
+        let build: Vec<i64> = self.recent.iter().map(|x| x.build).collect();
+        let raster: Vec<i64> = self.recent.iter().map(|x| x.raster).collect();
+        println!("  last {} frames:", self.recent.len());
+        print_times("build", build);
+        print_times("raster", raster);
+        if let Some(ref worst) = self.worst {
+            println!(
+                "  worst: frame {} (build {}, raster {})",
+                worst.number,
+                format_micros(worst.build),
+                format_micros(worst.raster)
+            );
+        }
+    }
+}
+
+/// Print the average, 90th percentile and maximum of `times`.
+fn print_times(name: &str, mut times: Vec<i64>) {
+    times.sort();
+    let average = times.iter().sum::<i64>() / times.len().max(1) as i64;
+    let p90 = times[(times.len() * 9 / 10).min(times.len() - 1)];
+    println!(
+        "    {:<6} avg {}, p90 {}, max {}",
+        name,
+        format_micros(average),
+        format_micros(p90),
+        format_micros(*times.last().unwrap())
+    );
+}
+
+fn format_micros(micros: i64) -> String {
+    format!("{:.1}ms", micros as f64 / 1000.0)
+}
+
+/// Collects frame timings from the `Flutter.Frame` events of every view.
+pub struct FrameMonitor {
+    /// Statistics by view, named after the isolate which survives a restart.
+    stats: Arc<Mutex<HashMap<String, FrameStats>>>,
+}
+
+impl FrameMonitor {
+    /// Subscribe to the Extension stream and record frames on a background
+    /// thread.
+    pub fn start(vm_service: &mut VMService, methods: &mut VMServiceMethods) -> Result<Self, ()> {
+        let stats: Arc<Mutex<HashMap<String, FrameStats>>> = Arc::new(Mutex::new(HashMap::new()));
+        let (sender, receiver) = mpsc::channel();
+        vm_service.listen(methods, "Extension", sender)?;
+        let recorder = Arc::clone(&stats);
+        std::thread::spawn(move || {
+            for notification in receiver {
+                let event = notification.event;
+                if event.extension_kind.as_ref().map(|x| x.as_str()) != Some("Flutter.Frame") {
+                    continue;
+                }
+                let (isolate, data) = match (event.isolate, event.extension_data) {
+                    (Some(isolate), Some(data)) => (isolate, data),
+                    _ => continue,
+                };
+                if let Ok(frame) = serde_json::from_value::<FrameTiming>(data) {
+                    let mut stats = recorder.lock().unwrap();
+                    stats.entry(frame.source(&isolate.name)).or_default().record(frame);
+                }
+            }
+        });
+        Ok(FrameMonitor { stats: stats })
+    }
+
+    /// Print the statistics of every view which has rendered frames.
+    pub fn print(&self) {
+        let stats = self.stats.lock().unwrap();
+        if stats.is_empty() {
+            println!("No frames have been rendered.");
+            return;
+        }
+        let mut names: Vec<&String> = stats.keys().collect();
+        names.sort();
+        for name in names {
+            stats[name].print(name);
+        }
+    }
+
+    /// Forget every frame seen so far, so later statistics only cover
+    /// frames after this point.
+    pub fn reset(&self) {
+        self.stats.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(number: i64, build: i64, raster: i64) -> FrameTiming {
+        FrameTiming {
+            number: number,
+            build: build,
+            raster: raster,
+            view_id: None,
+        }
+    }
+
+    #[test]
+    fn records_janky_frames() {
+        let mut stats = FrameStats::default();
+        stats.record(frame(1, 4_000, 5_000));
+        stats.record(frame(2, 20_000, 5_000));
+        stats.record(frame(3, 4_000, 30_000));
+        stats.record(frame(4, 18_000, 3_000));
+        assert_eq!(stats.total, 4);
+        assert_eq!(stats.janky, 3);
+        assert_eq!(stats.worst.as_ref().unwrap().number, 3);
+    }
+
+    #[test]
+    fn keeps_a_window_of_frames() {
+        let mut stats = FrameStats::default();
+        for number in 0..WINDOW as i64 + 10 {
+            stats.record(frame(number, 1_000, 1_000));
+        }
+        assert_eq!(stats.total, WINDOW + 10);
+        assert_eq!(stats.recent.len(), WINDOW);
+        assert_eq!(stats.recent.front().unwrap().number, 10);
+        assert!(stats.worst.is_none());
+    }
+
+    #[test]
+    fn frames_are_kept_by_view() {
+        let event = serde_json::json!({"number": 1, "build": 10, "raster": 20, "elapsed": 40});
+        let timing: FrameTiming = serde_json::from_value(event).unwrap();
+        assert_eq!(timing.source("main"), "main");
+
+        let event = serde_json::json!({"number": 1, "build": 10, "raster": 20, "viewId": 2});
+        let timing: FrameTiming = serde_json::from_value(event).unwrap();
+        assert_eq!(timing.source("main"), "main view 2");
+    }
+
+    #[test]
+    fn formats_micros() {
+        assert_eq!(format_micros(16_667), "16.7ms");
+        assert_eq!(format_micros(0), "0.0ms");
+    }
+}
@@ -0,0 +1,265 @@
+use super::vmservice::*;
+
+/// The inspector object group holding references to the printed widgets.
+const OBJECT_GROUP: &'static str = "flutter-tools-inspector";
+
+/// Depth of the subtree fetched when a widget is expanded.
+const SUBTREE_DEPTH: &'static str = "2";
+
+/// Prints the widget tree of an isolate using the `ext.flutter.inspector.*`
+/// extensions.
+///
+/// Printed widgets are numbered so they can be expanded or selected later.
+pub struct Inspector {
+    /// The inspector ids of the printed widgets, by number.
+    ids: Vec<String>,
+}
+
+impl Inspector {
+    pub fn new() -> Self {
+        Inspector { ids: Vec::new() }
+    }
+
+    /// Print the summary tree of widgets created by the project.
+    pub fn print_tree(
+        &mut self,
+        vm_service: &mut VMService,
+        methods: &mut VMServiceMethods,
+        isolate_id: &str,
+    ) -> Result<(), ()> {
+        // Release the widgets held for the previous tree.
+        vm_service.call_extension(
+            methods,
+            "ext.flutter.inspector.disposeGroup",
+            isolate_id,
+            &[("objectGroup", OBJECT_GROUP)],
+        )?;
+        self.ids.clear();
+        let root = vm_service.call_extension(
+            methods,
+            "ext.flutter.inspector.getRootWidgetSummaryTree",
+            isolate_id,
+            &[("objectGroup", OBJECT_GROUP)],
+        )?;
+        match node(root)? {
+            Some(root) => self.print_node(&root, 0),
+            None => println!("There is no widget tree yet."),
+        }
+        Ok(())
+    }
+
+    /// Print the full subtree below the widget numbered `index`.
+    pub fn expand(
+        &mut self,
+        vm_service: &mut VMService,
+        methods: &mut VMServiceMethods,
+        isolate_id: &str,
+        index: usize,
+    ) -> Result<(), ()> {
+        let id = self.id(index)?;
+        let subtree = vm_service.call_extension(
+            methods,
+            "ext.flutter.inspector.getDetailsSubtree",
+            isolate_id,
+            &[
+                ("arg", &id),
+                ("objectGroup", OBJECT_GROUP),
+                ("subtreeDepth", SUBTREE_DEPTH),
+            ],
+        )?;
+        if let Some(subtree) = node(subtree)? {
+            self.print_node(&subtree, 0);
+        }
+        Ok(())
+    }
+
+    /// Select the widget numbered `index` on the device.
+    pub fn select(
+        &mut self,
+        vm_service: &mut VMService,
+        methods: &mut VMServiceMethods,
+        isolate_id: &str,
+        index: usize,
+    ) -> Result<(), ()> {
+        let id = self.id(index)?;
+        vm_service.call_extension(
+            methods,
+            "ext.flutter.inspector.setSelectionById",
+            isolate_id,
+            &[("arg", &id), ("objectGroup", OBJECT_GROUP)],
+        )?;
+        Ok(())
+    }
+
+    /// The widget selected on the device, if any.
+    pub fn selected(
+        &mut self,
+        vm_service: &mut VMService,
+        methods: &mut VMServiceMethods,
+        isolate_id: &str,
+    ) -> Result<Option<DiagnosticsNode>, ()> {
+        let selected = vm_service.call_extension(
+            methods,
+            "ext.flutter.inspector.getSelectedSummaryWidget",
+            isolate_id,
+            &[("objectGroup", OBJECT_GROUP)],
+        )?;
+        node(selected)
+    }
+
+    fn id(&self, index: usize) -> Result<String, ()> {
+        match self.ids.get(index) {
+            Some(id) => Ok(id.clone()),
+            None => {
+                println!("No widget numbered {}. Print the tree with \"inspect\".", index);
+                Err(())
+            }
+        }
+    }
+
+    fn print_node(&mut self, node: &DiagnosticsNode, depth: usize) {
+        let number = match node.value_id {
+            Some(ref id) => {
+                self.ids.push(id.clone());
+                format!("{:>4}", self.ids.len() - 1)
+            }
+            None => "    ".to_string(),
+        };
+        let location = match node.creation_location {
+            Some(ref location) if node.created_by_local_project => format!("  {}", location),
+            _ => String::new(),
+        };
+        println!(
+            "{} {}{}{}",
+            number,
+            "  ".repeat(depth),
+            node.description,
+            location
+        );
+        for child in node.children.iter() {
+            self.print_node(child, depth + 1);
+        }
+    }
+}
+
+/// Open `location` in $VISUAL or $EDITOR, or print it if neither is set.
+pub fn open_location(location: &CreationLocation) -> Result<(), ()> {
+    println!("{}", location);
+    let editor = match std::env::var("VISUAL").or_else(|_| std::env::var("EDITOR")) {
+        Ok(editor) => editor,
+        Err(_) => return Ok(()),
+    };
+    std::process::Command::new(&editor)
+        .arg(format!("+{}", location.line))
+        .arg(location.path())
+        .status()
+        .map(|_| ())
+        .map_err(|err| println!("Failed to run {}: {}", editor, err))
+}
+
+/// Extensions wrap their return value in a `result` field.
+fn node(response: serde_json::Value) -> Result<Option<DiagnosticsNode>, ()> {
+    serde_json::from_value(response["result"].clone())
+        .map_err(|err| println!("Unexpected response from the inspector: {}", err))
+}
+
+/// A widget as described by the inspector.
+#[derive(Deserialize, Debug)]
+pub struct DiagnosticsNode {
+    pub description: String,
+
+    /// The inspector id of the widget, used to refer to it in later calls.
+    #[serde(rename = "valueId")]
+    pub value_id: Option<String>,
+
+    #[serde(rename = "creationLocation")]
+    pub creation_location: Option<CreationLocation>,
+
+    /// Whether the widget was created by the project rather than a package.
+    #[serde(rename = "createdByLocalProject", default)]
+    pub created_by_local_project: bool,
+
+    #[serde(default)]
+    pub children: Vec<DiagnosticsNode>,
+}
+
+/// Where a widget was constructed in the source.
+#[derive(Deserialize, Debug)]
+pub struct CreationLocation {
+    /// A file uri.
+    pub file: String,
+    pub line: i64,
+    pub column: i64,
+}
+
+impl CreationLocation {
+    /// The path of the file, relative to the project when inside it.
+    pub fn path(&self) -> String {
+        let path = self.file.trim_start_matches("file://");
+        let current = std::env::current_dir().unwrap_or_default();
+        match std::path::Path::new(path).strip_prefix(&current) {
+            Ok(relative) => relative.display().to_string(),
+            Err(_) => path.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for CreationLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.path(), self.line, self.column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_widgets_with_ids() {
+        let response = serde_json::json!({
+            "type": "_extensionType",
+            "result": {
+                "description": "MyApp",
+                "valueId": "inspector-0",
+                "createdByLocalProject": true,
+                "children": [
+                    {"description": "Padding"},
+                    {"description": "Text", "valueId": "inspector-1"},
+                ],
+            },
+        });
+        let tree = node(response).unwrap().unwrap();
+        assert_eq!(tree.children.len(), 2);
+        assert!(!tree.children[0].created_by_local_project);
+
+        let mut inspector = Inspector::new();
+        inspector.print_node(&tree, 0);
+        assert_eq!(inspector.ids, vec!["inspector-0", "inspector-1"]);
+        assert_eq!(inspector.id(1), Ok(String::from("inspector-1")));
+        assert_eq!(inspector.id(2), Err(()));
+    }
+
+    #[test]
+    fn no_selection_is_none() {
+        let response = serde_json::json!({"type": "_extensionType", "result": null});
+        assert!(node(response).unwrap().is_none());
+        assert!(node(serde_json::json!({"result": {"valueId": 1}})).is_err());
+    }
+
+    #[test]
+    fn locations_are_relative_to_the_project() {
+        let current = std::env::current_dir().unwrap();
+        let location = CreationLocation {
+            file: format!("file://{}", current.join("lib/main.dart").display()),
+            line: 12,
+            column: 5,
+        };
+        assert_eq!(location.to_string(), "lib/main.dart:12:5");
+        let location = CreationLocation {
+            file: String::from("file:///flutter/packages/flutter/lib/src/widgets/text.dart"),
+            line: 1,
+            column: 1,
+        };
+        assert_eq!(location.path(), "/flutter/packages/flutter/lib/src/widgets/text.dart");
+    }
+}
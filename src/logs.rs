@@ -0,0 +1,234 @@
+use std::sync::mpsc;
+
+use super::vmservice::*;
+
+/// Severity levels, matching those of package:logging.
+const LEVELS: &[(&str, i64)] = &[
+    ("all", 0),
+    ("finest", 300),
+    ("finer", 400),
+    ("fine", 500),
+    ("config", 700),
+    ("info", 800),
+    ("warning", 900),
+    ("severe", 1000),
+    ("shout", 1200),
+    ("off", 2000),
+];
+
+const INFO: i64 = 800;
+const WARNING: i64 = 900;
+const SEVERE: i64 = 1000;
+
+const RED: &str = "\x1b[31m";
+const YELLOW: &str = "\x1b[33m";
+const GREY: &str = "\x1b[90m";
+const RESET: &str = "\x1b[0m";
+
+/// Streams which carry output from the application.
+const STREAMS: &[&str] = &["Stdout", "Stderr", "Logging", "Extension"];
+
+/// Parse a level name such as `warning`, or a numeric level.
+pub fn parse_level(level: &str) -> Option<i64> {
+    let lower = level.to_lowercase();
+    LEVELS
+        .iter()
+        .find(|x| x.0 == lower)
+        .map(|x| x.1)
+        .or_else(|| level.parse::<i64>().ok())
+}
+
+/// The names accepted by [parse_level], from the lowest level.
+pub fn level_names() -> Vec<&'static str> {
+    LEVELS.iter().map(|x| x.0).collect()
+}
+
+/// Prints application output received on the vm service streams.
+pub struct LogPrinter {
+    /// Records below this level are not printed.
+    pub min_level: i64,
+
+    /// Whether to colour output with ANSI escapes.
+    pub color: bool,
+}
+
+impl LogPrinter {
+    /// Subscribe to the application output streams and print events as they
+    /// arrive on a background thread.
+    pub fn start(
+        self,
+        vm_service: &mut VMService,
+        methods: &mut VMServiceMethods,
+    ) -> Result<(), ()> {
+        let (sender, receiver) = mpsc::channel();
+        for stream in STREAMS {
+            vm_service.listen(methods, stream, sender.clone())?;
+        }
+        std::thread::spawn(move || {
+            for notification in receiver {
+                self.print(&notification);
+            }
+        });
+        Ok(())
+    }
+
+    fn print(&self, notification: &Notification) {
+        let event = &notification.event;
+        let extension_kind = event.extension_kind.as_ref().map(|x| x.as_str());
+        let (level, message) = match notification.stream_id.as_str() {
+            "Stdout" => (INFO, decode_bytes(event)),
+            "Stderr" => (SEVERE, decode_bytes(event)),
+            "Logging" => match event.log_record.as_ref() {
+                Some(record) => (record.level, format_record(record)),
+                None => return,
+            },
+            "Extension" if extension_kind == Some("Flutter.Error") => {
+                let data = event.extension_data.clone().unwrap_or_default();
+                let text = data["renderedErrorText"]
+                    .as_str()
+                    .or_else(|| data["description"].as_str())
+                    .unwrap_or("Flutter error");
+                (SEVERE, text.to_string())
+            }
+            _ => return,
+        };
+        if level < self.min_level {
+            return;
+        }
+        let isolate = event.isolate.as_ref().map_or("", |x| x.name.as_str());
+        let timestamp = format_timestamp(event.timestamp);
+        let color = if !self.color {
+            ""
+        } else if level >= SEVERE {
+            RED
+        } else if level >= WARNING {
+            YELLOW
+        } else if level < INFO {
+            GREY
+        } else {
+            ""
+        };
+        let reset = if color.is_empty() { "" } else { RESET };
+        // Writes are not line buffered, so one event may hold several lines or
+        // end in a newline.
+        for line in message.trim_end_matches('\n').lines() {
+            if self.color {
+                println!("{}{}{} [{}] {}{}{}", GREY, timestamp, RESET, isolate, color, line, reset);
+            } else {
+                println!("{} [{}] {}", timestamp, isolate, line);
+            }
+        }
+    }
+}
+
+/// Stdout and Stderr events carry the written bytes as base64.
+fn decode_bytes(event: &Event) -> String {
+    let bytes = event
+        .bytes
+        .as_ref()
+        .and_then(|x| base64::decode(x).ok())
+        .unwrap_or_default();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn format_record(record: &LogRecord) -> String {
+    let mut text = String::new();
+    if let Some(name) = record.logger_name.value_as_string.as_ref() {
+        if !name.is_empty() {
+            text.push_str(&format!("[{}] ", name));
+        }
+    }
+    text.push_str(
+        record
+            .message
+            .value_as_string
+            .as_ref()
+            .map_or("", |x| x.as_str()),
+    );
+    for extra in [&record.error, &record.stack_trace].iter() {
+        if extra.kind != InstanceKind::Null {
+            text.push('\n');
+            text.push_str(extra.value_as_string.as_ref().map_or("", |x| x.as_str()));
+        }
+    }
+    text
+}
+
+/// Format milliseconds since the epoch as a UTC `HH:MM:SS.mmm` time of day.
+fn format_timestamp(millis: i64) -> String {
+    let seconds = millis / 1000;
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        (seconds / 3600) % 24,
+        (seconds / 60) % 60,
+        seconds % 60,
+        millis % 1000
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_levels() {
+        assert_eq!(parse_level("warning"), Some(WARNING));
+        assert_eq!(parse_level("SEVERE"), Some(SEVERE));
+        assert_eq!(parse_level("850"), Some(850));
+        assert_eq!(parse_level("loud"), None);
+        for name in level_names() {
+            assert!(parse_level(name).is_some());
+        }
+    }
+
+    fn instance(kind: &str, value: &str) -> serde_json::Value {
+        serde_json::json!({
+            "type": "@Instance",
+            "id": "objects/1",
+            "kind": kind,
+            "identityHashCode": 0,
+            "class": {
+                "type": "@Class",
+                "id": "classes/1",
+                "name": kind,
+                "library": {"type": "@Library", "id": "libraries/1", "name": "", "uri": "dart:core"},
+            },
+            "valueAsString": value,
+        })
+    }
+
+    #[test]
+    fn formats_records() {
+        let record: LogRecord = serde_json::from_value(serde_json::json!({
+            "type": "LogRecord",
+            "message": instance("String", "tapped"),
+            "time": 0,
+            "level": INFO,
+            "sequenceNumber": 0,
+            "loggerName": instance("String", "ui"),
+            "zone": instance("Null", "null"),
+            "error": instance("Null", "null"),
+            "stackTrace": instance("StackTrace", "#0 main"),
+        }))
+        .unwrap();
+        assert_eq!(format_record(&record), "[ui] tapped\n#0 main");
+    }
+
+    #[test]
+    fn decodes_bytes() {
+        let event: Event = serde_json::from_value(serde_json::json!({
+            "type": "Event",
+            "kind": "WriteEvent",
+            "timestamp": 0,
+            "bytes": base64::encode("hello\n"),
+        }))
+        .unwrap();
+        assert_eq!(decode_bytes(&event), "hello\n");
+    }
+
+    #[test]
+    fn formats_timestamps() {
+        assert_eq!(format_timestamp(0), "00:00:00.000");
+        assert_eq!(format_timestamp(((25 * 60 + 1) * 60 + 2) * 1000 + 34), "01:01:02.034");
+    }
+}
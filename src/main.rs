@@ -8,12 +8,32 @@ extern crate http;
 extern crate base64;
 extern crate uuid;
 extern crate reqwest;
-extern crate libflate;
+extern crate flate2;
+extern crate native_tls;
+extern crate tokio;
+extern crate futures;
 
 mod commands;
 mod vmservice;
 mod devfs;
 mod compile;
+mod coverage;
+mod debugger;
+mod explorer;
+mod frames;
+mod inspector;
+mod logs;
+mod memory;
+mod profile;
+mod protocol;
+mod proxy;
+mod retainers;
+mod screenshot;
+mod session;
+#[cfg(test)]
+mod testing;
+mod timeline;
+mod upload;
 
 use commands::*;
 
@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+
+use super::vmservice::*;
+
+/// Number of classes shown in histograms and diffs.
+const TOP_CLASSES: usize = 20;
+
+/// The heap of one isolate at a point in time.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MemorySnapshot {
+    /// The name of the isolate, used to match snapshots across restarts.
+    pub isolate: String,
+
+    /// The time the snapshot was taken in milliseconds since the epoch.
+    pub timestamp: i64,
+
+    #[serde(rename = "memoryUsage")]
+    pub memory_usage: MemoryUsage,
+
+    pub classes: Vec<ClassHeapStats>,
+}
+
+impl MemorySnapshot {
+    /// Capture memory usage and the allocation profile of an isolate.
+    ///
+    /// If `gc` is set a full collection runs first, so the profile only
+    /// contains live objects. If `reset` is set the accumulated allocation
+    /// counters are cleared.
+    pub fn capture(
+        vm_service: &mut VMService,
+        methods: &mut VMServiceMethods,
+        isolate: &str,
+        isolate_id: &str,
+        gc: bool,
+        reset: bool,
+    ) -> Result<Self, ()> {
+        let profile: AllocationProfile =
+            vm_service.call_method(&methods.get_allocation_profile(isolate_id, gc, reset))?;
+        let memory_usage: MemoryUsage =
+            vm_service.call_method(&methods.get_memory_usage(isolate_id))?;
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|x| x.as_millis() as i64)
+            .unwrap_or(0);
+        Ok(MemorySnapshot {
+            isolate: isolate.to_string(),
+            timestamp: timestamp,
+            memory_usage: memory_usage,
+            classes: profile.members,
+        })
+    }
+
+    /// Print heap usage and the classes retaining the most memory.
+    pub fn print(&self) {
+        println!(
+            "{}: heap {} of {}, external {}",
+            self.isolate,
+            format_bytes(self.memory_usage.heap_usage),
+            format_bytes(self.memory_usage.heap_capacity),
+            format_bytes(self.memory_usage.external_usage)
+        );
+        let mut classes: Vec<&ClassHeapStats> = self.classes.iter().collect();
+        classes.sort_by(|a, b| b.bytes_current.cmp(&a.bytes_current));
+        println!("{:>12} {:>12}  {}", "bytes", "instances", "class");
+        for stats in classes.iter().take(TOP_CLASSES) {
+            println!(
+                "{:>12} {:>12}  {}",
+                format_bytes(stats.bytes_current),
+                stats.instances_current,
+                stats.class.name
+            );
+        }
+    }
+
+    /// Print the classes which grew between `previous` and this snapshot.
+    pub fn print_diff(&self, previous: &MemorySnapshot) {
+        println!(
+            "{}: heap {} over {:.1}s",
+            self.isolate,
+            format_bytes_delta(self.memory_usage.heap_usage - previous.memory_usage.heap_usage),
+            (self.timestamp - previous.timestamp) as f64 / 1000.0
+        );
+        let growth = self.growth(previous);
+        if growth.is_empty() {
+            println!("No classes grew.");
+            return;
+        }
+        println!("{:>12} {:>12}  {}", "+bytes", "+instances", "class");
+        for (bytes, instances, class_ref) in growth.iter().take(TOP_CLASSES) {
+            let bytes = format_bytes_delta(*bytes);
+            let instances = format!("{:+}", instances);
+            println!(
+                "{:>12} {:>12}  {}",
+                bytes,
+                instances,
+                self.class_name(class_ref)
+            );
+        }
+    }
+
+    /// The growth in bytes and instances of each class which grew since
+    /// `previous`, largest first.
+    ///
+    /// Classes are matched by id, since several libraries may declare a
+    /// class with the same name.
+    fn growth(&self, previous: &MemorySnapshot) -> Vec<(i64, i64, &ClassRef)> {
+        let before: HashMap<&str, &ClassHeapStats> = previous
+            .classes
+            .iter()
+            .map(|x| (x.class.id.as_str(), x))
+            .collect();
+        let mut growth: Vec<(i64, i64, &ClassRef)> = self
+            .classes
+            .iter()
+            .map(|after| {
+                let (bytes, instances) = before
+                    .get(after.class.id.as_str())
+                    .map_or((0, 0), |x| (x.bytes_current, x.instances_current));
+                (
+                    after.bytes_current - bytes,
+                    after.instances_current - instances,
+                    &after.class,
+                )
+            })
+            .filter(|x| x.0 > 0 || x.1 > 0)
+            .collect();
+        growth.sort_by(|a, b| b.0.cmp(&a.0));
+        growth
+    }
+
+    /// The name of a class, with its id when another class shares the name.
+    fn class_name(&self, class_ref: &ClassRef) -> String {
+        let shared = self
+            .classes
+            .iter()
+            .any(|x| x.class.name == class_ref.name && x.class.id != class_ref.id);
+        if shared {
+            format!("{} ({})", class_ref.name, class_ref.id)
+        } else {
+            class_ref.name.clone()
+        }
+    }
+}
+
+/// Format a byte count with a binary unit suffix.
+pub fn format_bytes(bytes: i64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value.abs() >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{}{}", bytes, UNITS[0])
+    } else {
+        format!("{:.1}{}", value, UNITS[unit])
+    }
+}
+
+/// A change in size, with a sign even when it grew.
+fn format_bytes_delta(bytes: i64) -> String {
+    if bytes > 0 {
+        format!("+{}", format_bytes(bytes))
+    } else {
+        format_bytes(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(id: &str, name: &str, bytes: i64, instances: i64) -> ClassHeapStats {
+        serde_json::from_value(serde_json::json!({
+            "type": "ClassHeapStats",
+            "class": {
+                "type": "@Class",
+                "id": id,
+                "name": name,
+                "library": {"type": "@Library", "id": "libraries/1", "name": "", "uri": "main.dart"},
+            },
+            "accumulatedSize": 0,
+            "bytesCurrent": bytes,
+            "instancesAccumulated": 0,
+            "instancesCurrent": instances,
+        }))
+        .unwrap()
+    }
+
+    fn snapshot(classes: Vec<ClassHeapStats>) -> MemorySnapshot {
+        MemorySnapshot {
+            isolate: String::from("main"),
+            timestamp: 0,
+            memory_usage: MemoryUsage {
+                type_: String::from("MemoryUsage"),
+                external_usage: 0,
+                heap_capacity: 0,
+                heap_usage: 0,
+            },
+            classes: classes,
+        }
+    }
+
+    #[test]
+    fn diff_keeps_same_named_classes_apart() {
+        let before = snapshot(vec![
+            stats("classes/1", "State", 100, 1),
+            stats("classes/2", "State", 500, 5),
+        ]);
+        let after = snapshot(vec![
+            stats("classes/1", "State", 300, 3),
+            stats("classes/2", "State", 400, 4),
+            stats("classes/3", "Widget", 50, 1),
+        ]);
+        let growth: Vec<(i64, i64, &str)> = after
+            .growth(&before)
+            .into_iter()
+            .map(|(bytes, instances, class_ref)| (bytes, instances, class_ref.id.as_str()))
+            .collect();
+        assert_eq!(growth, vec![(200, 2, "classes/1"), (50, 1, "classes/3")]);
+
+        assert_eq!(
+            after.class_name(&after.classes[0].class),
+            "State (classes/1)"
+        );
+        assert_eq!(after.class_name(&after.classes[2].class), "Widget");
+    }
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(512), "512B");
+        assert_eq!(format_bytes(1536), "1.5KB");
+        assert_eq!(format_bytes(-2 * 1024 * 1024), "-2.0MB");
+        assert_eq!(format_bytes(5 * 1024 * 1024 * 1024 * 1024), "5120.0GB");
+        assert_eq!(format_bytes_delta(1536), "+1.5KB");
+        assert_eq!(format_bytes_delta(-512), "-512B");
+        assert_eq!(format_bytes_delta(0), "0B");
+    }
+}
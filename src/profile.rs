@@ -0,0 +1,288 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use super::vmservice::*;
+
+/// Number of functions shown in the summary.
+const TOP_FUNCTIONS: usize = 20;
+
+/// Timeline streams recorded while sampling: dart code, the engine's frame
+/// work and garbage collections.
+const PROFILE_STREAMS: &[&str] = &["Dart", "Embedder", "GC"];
+
+/// Cpu samples of one isolate collected over a window of time.
+pub struct CpuProfile {
+    /// The name of the isolate the samples were collected from.
+    pub isolate: String,
+
+    pub samples: CpuSamples,
+}
+
+impl CpuProfile {
+    /// Clear the sample buffer of an isolate, wait for `duration` and collect
+    /// the samples taken in between.
+    ///
+    /// Sample timestamps use the vm's timeline clock, so the window is measured
+    /// with getVMTimelineMicros rather than the local clock. [PROFILE_STREAMS]
+    /// are recorded while sampling so the window can be lined up with a
+    /// timeline dump, and the streams recorded before are restored after.
+    pub fn capture(
+        vm_service: &mut VMService,
+        methods: &mut VMServiceMethods,
+        isolate: &str,
+        isolate_id: &str,
+        duration: std::time::Duration,
+    ) -> Result<Self, ()> {
+        let flags: TimelineFlags = vm_service.call_method(&methods.get_vm_timeline_flags())?;
+        let previous: Vec<&str> = flags.recorded_streams.iter().map(|x| x.as_str()).collect();
+        let mut streams = previous.clone();
+        for stream in PROFILE_STREAMS.iter() {
+            if !streams.contains(stream) {
+                streams.push(stream);
+            }
+        }
+        let _: serde_json::Value =
+            vm_service.call_method(&methods.set_vm_timeline_flags(&streams))?;
+        let samples = Self::sample(vm_service, methods, isolate_id, duration);
+        let restored: Result<serde_json::Value, ()> =
+            vm_service.call_method(&methods.set_vm_timeline_flags(&previous));
+        if restored.is_err() {
+            println!("Failed to restore the timeline streams {:?}.", previous);
+        }
+        Ok(CpuProfile {
+            isolate: isolate.to_string(),
+            samples: samples?,
+        })
+    }
+
+    fn sample(
+        vm_service: &mut VMService,
+        methods: &mut VMServiceMethods,
+        isolate_id: &str,
+        duration: std::time::Duration,
+    ) -> Result<CpuSamples, ()> {
+        let _: serde_json::Value =
+            vm_service.call_method(&methods.clear_cpu_samples(isolate_id))?;
+        let start: Timestamp = vm_service.call_method(&methods.get_vm_timeline_micros())?;
+        std::thread::sleep(duration);
+        let end: Timestamp = vm_service.call_method(&methods.get_vm_timeline_micros())?;
+        vm_service.call_method(&methods.get_cpu_samples(
+            isolate_id,
+            start.timestamp,
+            end.timestamp - start.timestamp,
+        ))
+    }
+
+    /// The stack of a sample from the root to the leaf.
+    fn root_first(sample: &CpuSample) -> impl Iterator<Item = usize> + '_ {
+        sample.stack.iter().rev().map(|x| *x as usize)
+    }
+
+    /// Print the functions which were most often on top of the stack.
+    pub fn print(&self) {
+        let _ = self.write_summary(&mut std::io::stdout());
+    }
+
+    fn write_summary(&self, out: &mut impl Write) -> std::io::Result<()> {
+        writeln!(
+            out,
+            "{}: {} samples over {:.1}s",
+            self.isolate,
+            self.samples.sample_count,
+            self.samples.time_extent_micros as f64 / 1_000_000.0
+        )?;
+        let total = self.samples.samples.len().max(1) as f64;
+        // Ordered by self time, then total time. Functions never sampled have
+        // no total time, so they sort last and end the list.
+        let mut functions: Vec<&ProfileFunction> = self
+            .samples
+            .functions
+            .iter()
+            .filter(|x| x.inclusive_ticks > 0)
+            .collect();
+        functions.sort_by(|a, b| {
+            (b.exclusive_ticks, b.inclusive_ticks).cmp(&(a.exclusive_ticks, a.inclusive_ticks))
+        });
+        writeln!(out, "{:>7} {:>7}  {}", "self", "total", "function")?;
+        for function in functions.iter().take(TOP_FUNCTIONS) {
+            writeln!(
+                out,
+                "{:>6.1}% {:>6.1}%  {}",
+                function.exclusive_ticks as f64 * 100.0 / total,
+                function.inclusive_ticks as f64 * 100.0 / total,
+                function.display_name()
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Write the samples as a speedscope file, with one profile per thread.
+    ///
+    /// See https://www.speedscope.app/file-format-schema.json.
+    pub fn write_speedscope(&self, path: &std::path::Path) -> Result<(), ()> {
+        let frames: Vec<serde_json::Value> = self
+            .samples
+            .functions
+            .iter()
+            .map(|x| serde_json::json!({"name": x.display_name(), "file": x.resolved_url}))
+            .collect();
+        let mut threads: BTreeMap<i64, Vec<&CpuSample>> = BTreeMap::new();
+        for sample in self.samples.samples.iter() {
+            threads
+                .entry(sample.tid)
+                .or_insert_with(Vec::new)
+                .push(sample);
+        }
+        let period = self.samples.sample_period;
+        let profiles: Vec<serde_json::Value> = threads
+            .iter()
+            .map(|(tid, samples)| {
+                let stacks: Vec<Vec<usize>> = samples
+                    .iter()
+                    .map(|x| Self::root_first(x).collect())
+                    .collect();
+                serde_json::json!({
+                    "type": "sampled",
+                    "name": format!("{} (thread {})", self.isolate, tid),
+                    "unit": "microseconds",
+                    "startValue": 0,
+                    "endValue": period * samples.len() as i64,
+                    "samples": stacks,
+                    "weights": vec![period; samples.len()],
+                })
+            })
+            .collect();
+        let file = std::fs::File::create(path).map_err(|_| ())?;
+        serde_json::to_writer(
+            file,
+            &serde_json::json!({
+                "$schema": "https://www.speedscope.app/file-format-schema.json",
+                "name": self.isolate,
+                "exporter": "flutter_tools",
+                "activeProfileIndex": 0,
+                "shared": {"frames": frames},
+                "profiles": profiles,
+            }),
+        )
+        .map_err(|_| ())
+    }
+
+    /// Write the samples as folded stacks, one `root;...;leaf count` line per
+    /// distinct stack, for flamegraph.pl and similar tools.
+    pub fn write_folded(&self, path: &std::path::Path) -> Result<(), ()> {
+        let names: Vec<String> = self
+            .samples
+            .functions
+            .iter()
+            .map(|x| x.display_name().replace(';', ":"))
+            .collect();
+        let mut stacks: BTreeMap<String, i64> = BTreeMap::new();
+        for sample in self.samples.samples.iter() {
+            let stack: Vec<&str> = Self::root_first(sample)
+                .map(|x| names.get(x).map_or("<unknown>", |x| x.as_str()))
+                .collect();
+            *stacks.entry(stack.join(";")).or_insert(0) += 1;
+        }
+        let mut file = std::fs::File::create(path).map_err(|_| ())?;
+        for (stack, count) in stacks.iter() {
+            writeln!(file, "{} {}", stack, count).map_err(|_| ())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    fn profile() -> CpuProfile {
+        let function = |name: &str, owner: Option<&str>, inclusive: i64, exclusive: i64| {
+            let owner = match owner {
+                Some(owner) => serde_json::json!({"type": "@Class", "name": owner}),
+                None => serde_json::json!({"type": "@Library", "name": "app"}),
+            };
+            serde_json::json!({
+                "kind": "Dart",
+                "inclusiveTicks": inclusive,
+                "exclusiveTicks": exclusive,
+                "resolvedUrl": "package:app/main.dart",
+                "function": {"name": name, "owner": owner},
+            })
+        };
+        let samples = serde_json::json!({
+            "type": "CpuSamples",
+            "samplePeriod": 250,
+            "maxStackDepth": 128,
+            "sampleCount": 4,
+            "timeOriginMicros": 0,
+            "timeExtentMicros": 1000,
+            "pid": 1,
+            "functions": [
+                function("main", None, 4, 1),
+                function("build", Some("Home"), 2, 2),
+                function("a;b", None, 1, 1),
+                function("unused", None, 0, 0),
+            ],
+            // Stacks are leaf first.
+            "samples": [
+                {"tid": 1, "timestamp": 0, "stack": [1, 0]},
+                {"tid": 1, "timestamp": 250, "stack": [1, 0]},
+                {"tid": 2, "timestamp": 500, "stack": [2, 0]},
+                {"tid": 1, "timestamp": 750, "stack": [0]},
+            ],
+        });
+        CpuProfile {
+            isolate: String::from("main"),
+            samples: serde_json::from_value(samples).unwrap(),
+        }
+    }
+
+    #[test]
+    fn writes_folded_stacks() {
+        let dir = TempDir::new();
+        let path = dir.join("profile.folded");
+        profile().write_folded(&path).unwrap();
+        let folded = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(folded, "main 1\nmain;Home.build 2\nmain;a:b 1\n");
+    }
+
+    #[test]
+    fn prints_the_top_functions() {
+        let mut out = Vec::new();
+        profile().write_summary(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        // Functions which were never sampled are left out.
+        assert_eq!(
+            lines,
+            vec![
+                "main: 4 samples over 0.0s",
+                "   self   total  function",
+                "  50.0%   50.0%  Home.build",
+                "  25.0%  100.0%  main",
+                "  25.0%   25.0%  a;b",
+            ]
+        );
+    }
+
+    #[test]
+    fn writes_speedscope() {
+        let dir = TempDir::new();
+        let path = dir.join("profile.speedscope.json");
+        profile().write_speedscope(&path).unwrap();
+        let file: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(file["shared"]["frames"][1]["name"], "Home.build");
+        let profiles = file["profiles"].as_array().unwrap();
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0]["name"], "main (thread 1)");
+        assert_eq!(
+            profiles[0]["samples"],
+            serde_json::json!([[0, 1], [0, 1], [0]])
+        );
+        assert_eq!(profiles[0]["weights"], serde_json::json!([250, 250, 250]));
+        assert_eq!(profiles[0]["endValue"], 750);
+        assert_eq!(profiles[1]["samples"], serde_json::json!([[0, 2]]));
+    }
+}
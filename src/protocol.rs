@@ -0,0 +1,4636 @@
+// Generated by gen_protocol from the vm service protocol specification.
+// Do not edit by hand, regenerate with:
+//     cargo run --bin gen_protocol -- protocol/service.md src/protocol.rs
+
+/// The major version of the protocol these definitions target.
+pub const PROTOCOL_MAJOR_VERSION: i64 = 3;
+
+/// The minor version of the protocol these definitions target.
+pub const PROTOCOL_MINOR_VERSION: i64 = 53;
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum CodeKind {
+    Dart,
+    Native,
+    Stub,
+    Tag,
+    Collected,
+    /// A value added in a later version of the protocol.
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum ErrorKind {
+    /// The isolate has encountered an unhandled Dart exception.
+    UnhandledException,
+    /// The isolate has encountered a Dart language error in the program.
+    LanguageError,
+    /// The isolate has encountered an internal error. These errors should be
+    /// reported as bugs.
+    InternalError,
+    /// The isolate has been terminated by an external source.
+    TerminationError,
+    /// A value added in a later version of the protocol.
+    #[serde(other)]
+    Unknown,
+}
+
+/// Adding new values to _EventKind_ is considered a backwards compatible
+/// change. Clients should ignore unrecognized events.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum EventKind {
+    /// Notification that VM identifying information has changed. Currently used
+    /// to notify of changes to the VM debugging name via setVMName.
+    VMUpdate,
+    /// Notification that a VM flag has been changed via the service protocol.
+    VMFlagUpdate,
+    /// Notification that a new isolate has started.
+    IsolateStart,
+    /// Notification that an isolate is ready to run.
+    IsolateRunnable,
+    /// Notification that an isolate has exited.
+    IsolateExit,
+    /// Notification that isolate identifying information has changed.
+    /// Currently used to notify of changes to the isolate debugging name
+    /// via setName.
+    IsolateUpdate,
+    /// Notification that an isolate has been reloaded.
+    IsolateReload,
+    /// Notification that an extension RPC was registered on an isolate.
+    ServiceExtensionAdded,
+    /// An isolate has paused at start, before executing code.
+    PauseStart,
+    /// An isolate has paused at exit, before terminating.
+    PauseExit,
+    /// An isolate has paused at a breakpoint or due to stepping.
+    PauseBreakpoint,
+    /// An isolate has paused due to interruption via pause.
+    PauseInterrupted,
+    /// An isolate has paused due to an exception.
+    PauseException,
+    /// An isolate has paused after a service request.
+    PausePostRequest,
+    /// An isolate has started or resumed execution.
+    Resume,
+    /// Indicates an isolate is not yet runnable. Only appears in an Isolate's
+    /// pauseEvent. Never sent over a stream.
+    None,
+    /// A breakpoint has been added for an isolate.
+    BreakpointAdded,
+    /// An unresolved breakpoint has been resolved for an isolate.
+    BreakpointResolved,
+    /// A breakpoint has been removed.
+    BreakpointRemoved,
+    /// A breakpoint has been updated.
+    BreakpointUpdated,
+    /// A garbage collection event.
+    GC,
+    /// Notification of bytes written, for example, to stdout/stderr.
+    WriteEvent,
+    /// Notification from dart:developer.inspect.
+    Inspect,
+    /// Event from dart:developer.postEvent.
+    Extension,
+    /// Event from dart:developer.log.
+    Logging,
+    /// A block of timeline events has been completed.
+    ///
+    /// This service event is not sent for individual TimelineEvents. It is
+    /// subject to buffering, so the most recent timeline events may never be
+    /// included in any TimelineEvents event if no timeline events occur later to
+    /// complete the block.
+    TimelineEvents,
+    /// The set of active timeline streams was changed via `setVMTimelineFlags`.
+    TimelineStreamSubscriptionsUpdate,
+    /// Notification that a Service has been registered into the Service Protocol
+    /// from another client.
+    ServiceRegistered,
+    /// Notification that a Service has been removed from the Service Protocol
+    /// from another client.
+    ServiceUnregistered,
+    /// Notification that the UserTag for an isolate has been changed.
+    UserTagChanged,
+    /// A block of recently collected CPU samples.
+    CpuSamples,
+    /// A heap snapshot chunk, sent as a binary event.
+    HeapSnapshot,
+    /// A value added in a later version of the protocol.
+    #[serde(other)]
+    Unknown,
+}
+
+/// A _FrameKind_ is used to distinguish different kinds of _Frame_ objects.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum FrameKind {
+    Regular,
+    AsyncCausal,
+    AsyncSuspensionMarker,
+    AsyncActivation,
+    /// A value added in a later version of the protocol.
+    #[serde(other)]
+    Unknown,
+}
+
+/// Adding new values to _InstanceKind_ is considered a backwards compatible
+/// change. Clients should treat unrecognized instance kinds as _PlainInstance_.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum InstanceKind {
+    /// A general instance of the Dart class Object.
+    PlainInstance,
+    /// null instance.
+    Null,
+    /// true or false.
+    Bool,
+    /// An instance of the Dart class double.
+    Double,
+    /// An instance of the Dart class int.
+    Int,
+    /// An instance of the Dart class String.
+    String,
+    /// An instance of the built-in VM List implementation. User-defined
+    /// Lists will be PlainInstance.
+    List,
+    /// An instance of the built-in VM Map implementation. User-defined
+    /// Maps will be PlainInstance.
+    Map,
+    /// An instance of the built-in VM Set implementation. User-defined
+    /// Sets will be PlainInstance.
+    Set,
+    /// Vector instance kinds.
+    Float32x4,
+    Float64x2,
+    Int32x4,
+    /// An instance of the built-in VM TypedData implementations. User-defined
+    /// TypedDatas will be PlainInstance.
+    Uint8ClampedList,
+    Uint8List,
+    Uint16List,
+    Uint32List,
+    Uint64List,
+    Int8List,
+    Int16List,
+    Int32List,
+    Int64List,
+    Float32List,
+    Float64List,
+    Int32x4List,
+    Float32x4List,
+    Float64x2List,
+    /// An instance of the Dart class Record.
+    Record,
+    /// An instance of the Dart class StackTrace.
+    StackTrace,
+    /// An instance of the built-in VM Closure implementation. User-defined
+    /// Closures will be PlainInstance.
+    Closure,
+    /// An instance of the Dart class MirrorReference.
+    MirrorReference,
+    /// An instance of the Dart class RegExp.
+    RegExp,
+    /// An instance of the Dart class WeakProperty.
+    WeakProperty,
+    /// An instance of the Dart class WeakReference.
+    WeakReference,
+    /// An instance of the Dart class Type.
+    Type,
+    /// An instance of the Dart class TypeParameter.
+    TypeParameter,
+    /// An instance of the Dart class TypeRef.
+    TypeRef,
+    /// An instance of the Dart class FunctionType.
+    FunctionType,
+    /// An instance of the Dart class BoundedType.
+    BoundedType,
+    /// An instance of the Dart class ReceivePort.
+    ReceivePort,
+    /// An instance of the Dart class UserTag.
+    UserTag,
+    /// An instance of the Dart class Finalizer.
+    Finalizer,
+    /// An instance of the Dart class NativeFinalizer.
+    NativeFinalizer,
+    /// An instance of the Dart class FinalizerEntry.
+    FinalizerEntry,
+    /// A value added in a later version of the protocol.
+    #[serde(other)]
+    Unknown,
+}
+
+/// A _SentinelKind_ is used to distinguish different kinds of _Sentinel_
+/// objects.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum SentinelKind {
+    /// Indicates that the object referred to has been collected by the GC.
+    Collected,
+    /// Indicates that an object id has expired.
+    Expired,
+    /// Indicates that a variable or field has not been initialized.
+    NotInitialized,
+    /// Indicates that a variable or field is in the process of being initialized.
+    BeingInitialized,
+    /// Indicates that a variable has been eliminated by the optimizing compiler.
+    OptimizedOut,
+    /// Reserved for future use.
+    Free,
+    /// A value added in a later version of the protocol.
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum SourceReportKind {
+    /// Used to request a code coverage information.
+    Coverage,
+    /// Used to request a list of token positions of possible breakpoints.
+    PossibleBreakpoints,
+    /// A value added in a later version of the protocol.
+    #[serde(other)]
+    Unknown,
+}
+
+/// An _ExceptionPauseMode_ indicates how the isolate pauses when an exception
+/// is thrown.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum ExceptionPauseMode {
+    None,
+    Unhandled,
+    All,
+    /// A value added in a later version of the protocol.
+    #[serde(other)]
+    Unknown,
+}
+
+/// A _StepOption_ indicates which form of stepping is requested in a
+/// [resume](#resume) RPC.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum StepOption {
+    Into,
+    Over,
+    OverAsyncSuspension,
+    Out,
+    Rewind,
+    /// A value added in a later version of the protocol.
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AllocationProfile {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// Allocation information for all class types.
+    pub members: Vec<ClassHeapStats>,
+
+    /// Information about memory usage for the isolate.
+    #[serde(rename = "memoryUsage")]
+    pub memory_usage: MemoryUsage,
+
+    /// The timestamp of the last accumulator reset.
+    ///
+    /// If the accumulators have not been reset, this field is not present.
+    #[serde(rename = "dateLastAccumulatorReset")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_last_accumulator_reset: Option<i64>,
+
+    /// The timestamp of the last manually triggered GC.
+    ///
+    /// If a GC has not been triggered manually, this field is not present.
+    #[serde(rename = "dateLastServiceGC")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_last_service_gc: Option<i64>,
+}
+
+/// A _BoundField_ represents a field bound to a particular value in an
+/// _Instance_.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BoundField {
+    /// Provided for fields of instances that are NOT of the following instance
+    /// kinds:
+    ///   Record
+    ///
+    /// Note: this property is deprecated and will be replaced by `name`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decl: Option<FieldRef>,
+
+    /// The name of a field, or the position of a positional record field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<serde_json::Value>,
+
+    pub value: serde_json::Value,
+}
+
+/// A _BoundVariable_ represents a local variable bound to a particular value
+/// in a _Frame_.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BoundVariable {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    pub name: String,
+
+    pub value: serde_json::Value,
+
+    /// The token position where this variable was declared.
+    #[serde(rename = "declarationTokenPos")]
+    pub declaration_token_pos: i64,
+
+    /// The first token position where this variable is visible to the scope.
+    #[serde(rename = "scopeStartTokenPos")]
+    pub scope_start_token_pos: i64,
+
+    /// The last token position where this variable is visible to the scope.
+    #[serde(rename = "scopeEndTokenPos")]
+    pub scope_end_token_pos: i64,
+}
+
+/// A _Breakpoint_ describes a debugger breakpoint.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Breakpoint {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A unique identifier for an Object. Passed to the
+    /// getObject RPC to reload this Object.
+    ///
+    /// Some objects may get a new id when they are reloaded.
+    pub id: String,
+
+    /// Provided and set to true if the id of an Object is fixed. If true, the id
+    /// of an Object is guaranteed not to change or expire. The object may,
+    /// however, still be _Collected_.
+    #[serde(rename = "fixedId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_id: Option<bool>,
+
+    /// If an object is allocated in the Dart heap, it will have
+    /// a corresponding class object.
+    ///
+    /// The class of a non-instance is not a Dart class, but is instead
+    /// an internal vm object.
+    ///
+    /// Moving an Object into or out of the heap is considered a
+    /// backwards compatible change for types other than Instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<ClassRef>,
+
+    /// The size of this object in the heap.
+    ///
+    /// If an object is not heap-allocated, then this field is omitted.
+    ///
+    /// Note that the size can be zero for some objects. In the current
+    /// VM implementation, this occurs for small integers, which are
+    /// stored entirely within their object pointers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<i64>,
+
+    /// A number identifying this breakpoint to the user.
+    #[serde(rename = "breakpointNumber")]
+    pub breakpoint_number: i64,
+
+    /// Is this breakpoint enabled?
+    pub enabled: bool,
+
+    /// Has this breakpoint been assigned to a specific program location?
+    pub resolved: bool,
+
+    /// Note: this property is deprecated and is always absent from the response.
+    #[serde(rename = "isSyntheticAsyncContinuation")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_synthetic_async_continuation: Option<bool>,
+
+    /// SourceLocation when breakpoint is resolved, UnresolvedSourceLocation
+    /// when a breakpoint is not resolved.
+    pub location: serde_json::Value,
+}
+
+/// _@Class_ is a reference to a _Class_.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ClassRef {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A unique identifier for an Object. Passed to the
+    /// getObject RPC to load this Object.
+    pub id: String,
+
+    /// Provided and set to true if the id of an Object is fixed. If true, the id
+    /// of an Object is guaranteed not to change or expire. The object may,
+    /// however, still be _Collected_.
+    #[serde(rename = "fixedId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_id: Option<bool>,
+
+    /// The name of this class.
+    pub name: String,
+
+    /// The location of this class in the source code.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<SourceLocation>,
+
+    /// The library which contains this class.
+    pub library: LibraryRef,
+
+    /// The type parameters for the class.
+    ///
+    /// Provided if the class is generic.
+    #[serde(rename = "typeParameters")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_parameters: Option<Vec<InstanceRef>>,
+}
+
+/// A _Class_ provides information about a Dart language class.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Class {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A unique identifier for an Object. Passed to the
+    /// getObject RPC to reload this Object.
+    ///
+    /// Some objects may get a new id when they are reloaded.
+    pub id: String,
+
+    /// Provided and set to true if the id of an Object is fixed. If true, the id
+    /// of an Object is guaranteed not to change or expire. The object may,
+    /// however, still be _Collected_.
+    #[serde(rename = "fixedId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_id: Option<bool>,
+
+    /// If an object is allocated in the Dart heap, it will have
+    /// a corresponding class object.
+    ///
+    /// The class of a non-instance is not a Dart class, but is instead
+    /// an internal vm object.
+    ///
+    /// Moving an Object into or out of the heap is considered a
+    /// backwards compatible change for types other than Instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<ClassRef>,
+
+    /// The size of this object in the heap.
+    ///
+    /// If an object is not heap-allocated, then this field is omitted.
+    ///
+    /// Note that the size can be zero for some objects. In the current
+    /// VM implementation, this occurs for small integers, which are
+    /// stored entirely within their object pointers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<i64>,
+
+    /// The name of this class.
+    pub name: String,
+
+    /// The location of this class in the source code.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<SourceLocation>,
+
+    /// The library which contains this class.
+    pub library: LibraryRef,
+
+    /// The type parameters for the class.
+    ///
+    /// Provided if the class is generic.
+    #[serde(rename = "typeParameters")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_parameters: Option<Vec<InstanceRef>>,
+
+    /// The error which occurred during class finalization, if it exists.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorRef>,
+
+    /// Is this an abstract class?
+    #[serde(rename = "abstract")]
+    pub abstract_: bool,
+
+    /// Is this a const class?
+    #[serde(rename = "const")]
+    pub const_: bool,
+
+    /// Are allocations of this class being traced?
+    #[serde(rename = "traceAllocations")]
+    pub trace_allocations: bool,
+
+    /// The superclass of this class, if any.
+    #[serde(rename = "super")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub super_: Option<ClassRef>,
+
+    /// The supertype for this class, if any.
+    ///
+    /// The value will be of the kind: Type.
+    #[serde(rename = "superType")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub super_type: Option<InstanceRef>,
+
+    /// A list of interface types for this class.
+    ///
+    /// The values will be of the kind: Type.
+    pub interfaces: Vec<InstanceRef>,
+
+    /// The mixin type for this class, if any.
+    ///
+    /// The value will be of the kind: Type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mixin: Option<InstanceRef>,
+
+    /// A list of fields in this class. Does not include fields from
+    /// superclasses.
+    pub fields: Vec<FieldRef>,
+
+    /// A list of functions in this class. Does not include functions
+    /// from superclasses.
+    pub functions: Vec<FunctionRef>,
+
+    /// A list of subclasses of this class.
+    pub subclasses: Vec<ClassRef>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ClassHeapStats {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// The class for which this memory information is associated.
+    pub class: ClassRef,
+
+    /// The number of bytes allocated for instances of class since the
+    /// accumulator was last reset.
+    #[serde(rename = "accumulatedSize")]
+    pub accumulated_size: i64,
+
+    /// The number of bytes currently allocated for instances of class.
+    #[serde(rename = "bytesCurrent")]
+    pub bytes_current: i64,
+
+    /// The number of instances of class which have been allocated since
+    /// the accumulator was last reset.
+    #[serde(rename = "instancesAccumulated")]
+    pub instances_accumulated: i64,
+
+    /// The number of instances of class which are currently alive.
+    #[serde(rename = "instancesCurrent")]
+    pub instances_current: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ClassList {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    pub classes: Vec<ClassRef>,
+}
+
+/// _@Code_ is a reference to a _Code_ object.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CodeRef {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A unique identifier for an Object. Passed to the
+    /// getObject RPC to load this Object.
+    pub id: String,
+
+    /// Provided and set to true if the id of an Object is fixed. If true, the id
+    /// of an Object is guaranteed not to change or expire. The object may,
+    /// however, still be _Collected_.
+    #[serde(rename = "fixedId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_id: Option<bool>,
+
+    /// A name for this code object.
+    pub name: String,
+
+    /// What kind of code object is this?
+    pub kind: CodeKind,
+}
+
+/// A _Code_ object represents compiled code in the Dart VM.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Code {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A unique identifier for an Object. Passed to the
+    /// getObject RPC to reload this Object.
+    ///
+    /// Some objects may get a new id when they are reloaded.
+    pub id: String,
+
+    /// Provided and set to true if the id of an Object is fixed. If true, the id
+    /// of an Object is guaranteed not to change or expire. The object may,
+    /// however, still be _Collected_.
+    #[serde(rename = "fixedId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_id: Option<bool>,
+
+    /// If an object is allocated in the Dart heap, it will have
+    /// a corresponding class object.
+    ///
+    /// The class of a non-instance is not a Dart class, but is instead
+    /// an internal vm object.
+    ///
+    /// Moving an Object into or out of the heap is considered a
+    /// backwards compatible change for types other than Instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<ClassRef>,
+
+    /// The size of this object in the heap.
+    ///
+    /// If an object is not heap-allocated, then this field is omitted.
+    ///
+    /// Note that the size can be zero for some objects. In the current
+    /// VM implementation, this occurs for small integers, which are
+    /// stored entirely within their object pointers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<i64>,
+
+    /// A name for this code object.
+    pub name: String,
+
+    /// What kind of code object is this?
+    pub kind: CodeKind,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ContextRef {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A unique identifier for an Object. Passed to the
+    /// getObject RPC to load this Object.
+    pub id: String,
+
+    /// Provided and set to true if the id of an Object is fixed. If true, the id
+    /// of an Object is guaranteed not to change or expire. The object may,
+    /// however, still be _Collected_.
+    #[serde(rename = "fixedId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_id: Option<bool>,
+
+    /// The number of variables in this context.
+    pub length: i64,
+}
+
+/// A _Context_ is a data structure which holds the captured variables for
+/// some closure.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Context {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A unique identifier for an Object. Passed to the
+    /// getObject RPC to reload this Object.
+    ///
+    /// Some objects may get a new id when they are reloaded.
+    pub id: String,
+
+    /// Provided and set to true if the id of an Object is fixed. If true, the id
+    /// of an Object is guaranteed not to change or expire. The object may,
+    /// however, still be _Collected_.
+    #[serde(rename = "fixedId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_id: Option<bool>,
+
+    /// If an object is allocated in the Dart heap, it will have
+    /// a corresponding class object.
+    ///
+    /// The class of a non-instance is not a Dart class, but is instead
+    /// an internal vm object.
+    ///
+    /// Moving an Object into or out of the heap is considered a
+    /// backwards compatible change for types other than Instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<ClassRef>,
+
+    /// The size of this object in the heap.
+    ///
+    /// If an object is not heap-allocated, then this field is omitted.
+    ///
+    /// Note that the size can be zero for some objects. In the current
+    /// VM implementation, this occurs for small integers, which are
+    /// stored entirely within their object pointers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<i64>,
+
+    /// The number of variables in this context.
+    pub length: i64,
+
+    /// The enclosing context for this context.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<ContextRef>,
+
+    /// The variables in this context object.
+    pub variables: Vec<ContextElement>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ContextElement {
+    pub value: serde_json::Value,
+}
+
+/// See [getCpuSamples](#getcpusamples) and [CpuSample](#cpusample).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CpuSamples {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// The sampling rate for the profiler in microseconds.
+    #[serde(rename = "samplePeriod")]
+    pub sample_period: i64,
+
+    /// The maximum possible stack depth for samples.
+    #[serde(rename = "maxStackDepth")]
+    pub max_stack_depth: i64,
+
+    /// The number of samples returned.
+    #[serde(rename = "sampleCount")]
+    pub sample_count: i64,
+
+    /// The start of the period of time in which the returned samples were
+    /// collected.
+    #[serde(rename = "timeOriginMicros")]
+    pub time_origin_micros: i64,
+
+    /// The duration of time covered by the returned samples.
+    #[serde(rename = "timeExtentMicros")]
+    pub time_extent_micros: i64,
+
+    /// The process ID for the VM.
+    pub pid: i64,
+
+    /// A list of functions seen in the relevant samples. These references can
+    /// be looked up using the indicies provided in a `CpuSample` `stack` to
+    /// determine which function was on the stack.
+    pub functions: Vec<ProfileFunction>,
+
+    /// A list of samples collected in the range
+    /// `[timeOriginMicros, timeOriginMicros + timeExtentMicros]`
+    pub samples: Vec<CpuSample>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CpuSamplesEvent {
+    /// The sampling rate for the profiler in microseconds.
+    #[serde(rename = "samplePeriod")]
+    pub sample_period: i64,
+
+    /// The maximum possible stack depth for samples.
+    #[serde(rename = "maxStackDepth")]
+    pub max_stack_depth: i64,
+
+    /// The number of samples returned.
+    #[serde(rename = "sampleCount")]
+    pub sample_count: i64,
+
+    /// The start of the period of time in which the returned samples were
+    /// collected.
+    #[serde(rename = "timeOriginMicros")]
+    pub time_origin_micros: i64,
+
+    /// The duration of time covered by the returned samples.
+    #[serde(rename = "timeExtentMicros")]
+    pub time_extent_micros: i64,
+
+    /// The process ID for the VM.
+    pub pid: i64,
+
+    /// A list of references to functions seen in the relevant samples. These
+    /// references can be looked up using the indicies provided in a
+    /// `CpuSample` `stack` to determine which function was on the stack.
+    pub functions: serde_json::Value,
+
+    /// A list of samples collected in the range
+    /// `[timeOriginMicros, timeOriginMicros + timeExtentMicros]`
+    pub samples: Vec<CpuSample>,
+}
+
+/// See [getCpuSamples](#getcpusamples) and [CpuSamples](#cpusamples).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CpuSample {
+    /// The thread ID representing the thread on which this sample was collected.
+    pub tid: i64,
+
+    /// The time this sample was collected in microseconds.
+    pub timestamp: i64,
+
+    /// The name of VM tag set when this sample was collected. Omitted if the VM
+    /// tag for the sample is not considered valid.
+    #[serde(rename = "vmTag")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vm_tag: Option<String>,
+
+    /// The name of the User tag set when this sample was collected. Omitted if
+    /// no User tag was set when this sample was collected.
+    #[serde(rename = "userTag")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_tag: Option<String>,
+
+    /// Provided and set to true if the sample's stack was truncated. This can
+    /// happen if the stack depth exceeds the `maxStackDepth` in the
+    /// `CpuSamples` response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub truncated: Option<bool>,
+
+    /// The call stack at the time this sample was collected. The stack is to be
+    /// interpreted as top to bottom. Each element in this array is a key into
+    /// the `functions` array in `CpuSamples`.
+    pub stack: Vec<i64>,
+
+    /// The identityHashCode assigned to the allocated object. This hash
+    /// code is the same as the hash code provided in HeapSnapshot and
+    /// CpuSample's returned by getAllocationTraces().
+    #[serde(rename = "identityHashCode")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity_hash_code: Option<i64>,
+
+    /// Matches the index of a class in HeapSnapshot.classes. Provided for
+    /// CpuSample instances returned from a getAllocationTraces().
+    #[serde(rename = "classId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class_id: Option<i64>,
+}
+
+/// _@Error_ is a reference to an _Error_.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ErrorRef {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A unique identifier for an Object. Passed to the
+    /// getObject RPC to load this Object.
+    pub id: String,
+
+    /// Provided and set to true if the id of an Object is fixed. If true, the id
+    /// of an Object is guaranteed not to change or expire. The object may,
+    /// however, still be _Collected_.
+    #[serde(rename = "fixedId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_id: Option<bool>,
+
+    /// What kind of error is this?
+    pub kind: ErrorKind,
+
+    /// A description of the error.
+    pub message: String,
+}
+
+/// An _Error_ represents a Dart language level error. This is distinct from an
+/// [rpc error](#rpc-error).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Error {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A unique identifier for an Object. Passed to the
+    /// getObject RPC to reload this Object.
+    ///
+    /// Some objects may get a new id when they are reloaded.
+    pub id: String,
+
+    /// Provided and set to true if the id of an Object is fixed. If true, the id
+    /// of an Object is guaranteed not to change or expire. The object may,
+    /// however, still be _Collected_.
+    #[serde(rename = "fixedId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_id: Option<bool>,
+
+    /// If an object is allocated in the Dart heap, it will have
+    /// a corresponding class object.
+    ///
+    /// The class of a non-instance is not a Dart class, but is instead
+    /// an internal vm object.
+    ///
+    /// Moving an Object into or out of the heap is considered a
+    /// backwards compatible change for types other than Instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<ClassRef>,
+
+    /// The size of this object in the heap.
+    ///
+    /// If an object is not heap-allocated, then this field is omitted.
+    ///
+    /// Note that the size can be zero for some objects. In the current
+    /// VM implementation, this occurs for small integers, which are
+    /// stored entirely within their object pointers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<i64>,
+
+    /// What kind of error is this?
+    pub kind: ErrorKind,
+
+    /// A description of the error.
+    pub message: String,
+
+    /// If this error is due to an unhandled exception, this
+    /// is the exception thrown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exception: Option<InstanceRef>,
+
+    /// If this error is due to an unhandled exception, this
+    /// is the stacktrace object.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stacktrace: Option<InstanceRef>,
+}
+
+/// An _Event_ is an asynchronous notification from the VM. It is delivered
+/// only when the client has subscribed to an event stream using the
+/// [streamListen](#streamListen) RPC.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Event {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// What kind of event is this?
+    pub kind: EventKind,
+
+    /// The isolate with which this event is associated.
+    ///
+    /// This is provided for all event kinds except for:
+    ///   VMUpdate, VMFlagUpdate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub isolate: Option<IsolateRef>,
+
+    /// The vm with which this event is associated.
+    ///
+    /// This is provided for the event kind:
+    ///   VMUpdate, VMFlagUpdate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vm: Option<VMRef>,
+
+    /// The timestamp (in milliseconds since the epoch) associated with this
+    /// event. For some isolate pause events, the timestamp is from when the
+    /// isolate was paused. For other events, the timestamp is from when the
+    /// event was created.
+    pub timestamp: i64,
+
+    /// The breakpoint which was added, removed, or resolved.
+    ///
+    /// This is provided for the event kinds:
+    ///   PauseBreakpoint
+    ///   BreakpointAdded
+    ///   BreakpointRemoved
+    ///   BreakpointResolved
+    ///   BreakpointUpdated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub breakpoint: Option<Breakpoint>,
+
+    /// The list of breakpoints at which we are currently paused
+    /// for a PauseBreakpoint event.
+    ///
+    /// This list may be empty. For example, while single-stepping, the
+    /// VM sends a PauseBreakpoint event with no breakpoints.
+    ///
+    /// If there is more than one breakpoint set at the program position,
+    /// then all of them will be provided.
+    ///
+    /// This is provided for the event kinds:
+    ///   PauseBreakpoint
+    #[serde(rename = "pauseBreakpoints")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pause_breakpoints: Option<Vec<Breakpoint>>,
+
+    /// The top stack frame associated with this event, if applicable.
+    ///
+    /// This is provided for the event kinds:
+    ///   PauseBreakpoint
+    ///   PauseInterrupted
+    ///   PauseException
+    ///
+    /// For PauseInterrupted events, there will be no top frame if the
+    /// isolate is idle (waiting in the message loop).
+    ///
+    /// For the Resume event, the top frame is provided at
+    /// all times except for the initial resume event that is delivered
+    /// when an isolate begins execution.
+    #[serde(rename = "topFrame")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_frame: Option<Frame>,
+
+    /// The exception associated with this event, if this is a
+    /// PauseException event.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exception: Option<InstanceRef>,
+
+    /// An array of bytes, encoded as a base64 string.
+    ///
+    /// This is provided for the WriteEvent event.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<String>,
+
+    /// The argument passed to dart:developer.inspect.
+    ///
+    /// This is provided for the Inspect event.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inspectee: Option<InstanceRef>,
+
+    /// The garbage collection (GC) operation performed.
+    ///
+    /// This is provided for the event kinds:
+    ///   GC
+    #[serde(rename = "gcType")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gc_type: Option<String>,
+
+    /// The RPC name of the extension that was added.
+    ///
+    /// This is provided for the ServiceExtensionAdded event.
+    #[serde(rename = "extensionRPC")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extension_rpc: Option<String>,
+
+    /// The extension event kind.
+    ///
+    /// This is provided for the Extension event.
+    #[serde(rename = "extensionKind")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extension_kind: Option<String>,
+
+    /// The extension event data.
+    ///
+    /// This is provided for the Extension event.
+    #[serde(rename = "extensionData")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extension_data: Option<serde_json::Value>,
+
+    /// An array of TimelineEvents
+    ///
+    /// This is provided for the TimelineEvents event.
+    #[serde(rename = "timelineEvents")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeline_events: Option<Vec<serde_json::Value>>,
+
+    /// The new set of recorded timeline streams.
+    ///
+    /// This is provided for the TimelineStreamSubscriptionsUpdate event.
+    #[serde(rename = "updatedStreams")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_streams: Option<Vec<String>>,
+
+    /// Is the isolate paused at an await, yield, or yield* statement?
+    ///
+    /// This is provided for the event kinds:
+    ///   PauseBreakpoint
+    ///   PauseInterrupted
+    #[serde(rename = "atAsyncSuspension")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub at_async_suspension: Option<bool>,
+
+    /// The status (success or failure) related to the event.
+    /// This is provided for the event kinds:
+    ///   IsolateReloaded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+
+    /// LogRecord data.
+    ///
+    /// This is provided for the Logging event.
+    #[serde(rename = "logRecord")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_record: Option<LogRecord>,
+
+    /// The service identifier.
+    ///
+    /// This is provided for the event kinds:
+    ///   ServiceRegistered
+    ///   ServiceUnregistered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service: Option<String>,
+
+    /// The RPC method that should be used to invoke the service.
+    ///
+    /// This is provided for the event kinds:
+    ///   ServiceRegistered
+    ///   ServiceUnregistered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+
+    /// The alias of the registered service.
+    ///
+    /// This is provided for the event kinds:
+    ///   ServiceRegistered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+
+    /// The name of the changed flag.
+    ///
+    /// This is provided for the event kinds:
+    ///   VMFlagUpdate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flag: Option<String>,
+
+    /// The new value of the changed flag.
+    ///
+    /// This is provided for the event kinds:
+    ///   VMFlagUpdate
+    #[serde(rename = "newValue")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_value: Option<String>,
+
+    /// Specifies whether this event is the last of a group of events.
+    ///
+    /// This is provided for the event kinds:
+    ///   HeapSnapshot
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last: Option<bool>,
+
+    /// The current UserTag label.
+    #[serde(rename = "updatedTag")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_tag: Option<String>,
+
+    /// The previous UserTag label.
+    #[serde(rename = "previousTag")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_tag: Option<String>,
+
+    /// A CPU profile containing recent samples.
+    #[serde(rename = "cpuSamples")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_samples: Option<CpuSamplesEvent>,
+
+    /// Binary data associated with the event.
+    ///
+    /// This is provided for the event kinds:
+    ///   HeapSnapshot
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+/// An _@Field_ is a reference to a _Field_.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FieldRef {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A unique identifier for an Object. Passed to the
+    /// getObject RPC to load this Object.
+    pub id: String,
+
+    /// Provided and set to true if the id of an Object is fixed. If true, the id
+    /// of an Object is guaranteed not to change or expire. The object may,
+    /// however, still be _Collected_.
+    #[serde(rename = "fixedId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_id: Option<bool>,
+
+    /// The name of this field.
+    pub name: String,
+
+    /// The owner of this field, which can be either a Library or a
+    /// Class.
+    pub owner: serde_json::Value,
+
+    /// The declared type of this field.
+    ///
+    /// The value will always be of one of the kinds:
+    /// Type, TypeParameter, RecordType, FunctionType, BoundedType.
+    #[serde(rename = "declaredType")]
+    pub declared_type: InstanceRef,
+
+    /// Is this field const?
+    #[serde(rename = "const")]
+    pub const_: bool,
+
+    /// Is this field final?
+    #[serde(rename = "final")]
+    pub final_: bool,
+
+    /// Is this field static?
+    #[serde(rename = "static")]
+    pub static_: bool,
+
+    /// The location of this field in the source code.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<SourceLocation>,
+}
+
+/// A _Field_ provides information about a Dart language field or variable.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Field {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A unique identifier for an Object. Passed to the
+    /// getObject RPC to reload this Object.
+    ///
+    /// Some objects may get a new id when they are reloaded.
+    pub id: String,
+
+    /// Provided and set to true if the id of an Object is fixed. If true, the id
+    /// of an Object is guaranteed not to change or expire. The object may,
+    /// however, still be _Collected_.
+    #[serde(rename = "fixedId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_id: Option<bool>,
+
+    /// If an object is allocated in the Dart heap, it will have
+    /// a corresponding class object.
+    ///
+    /// The class of a non-instance is not a Dart class, but is instead
+    /// an internal vm object.
+    ///
+    /// Moving an Object into or out of the heap is considered a
+    /// backwards compatible change for types other than Instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<ClassRef>,
+
+    /// The size of this object in the heap.
+    ///
+    /// If an object is not heap-allocated, then this field is omitted.
+    ///
+    /// Note that the size can be zero for some objects. In the current
+    /// VM implementation, this occurs for small integers, which are
+    /// stored entirely within their object pointers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<i64>,
+
+    /// The name of this field.
+    pub name: String,
+
+    /// The owner of this field, which can be either a Library or a
+    /// Class.
+    pub owner: serde_json::Value,
+
+    /// The declared type of this field.
+    ///
+    /// The value will always be of one of the kinds:
+    /// Type, TypeParameter, RecordType, FunctionType, BoundedType.
+    #[serde(rename = "declaredType")]
+    pub declared_type: InstanceRef,
+
+    /// Is this field const?
+    #[serde(rename = "const")]
+    pub const_: bool,
+
+    /// Is this field final?
+    #[serde(rename = "final")]
+    pub final_: bool,
+
+    /// Is this field static?
+    #[serde(rename = "static")]
+    pub static_: bool,
+
+    /// The location of this field in the source code.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<SourceLocation>,
+
+    /// The value of this field, if the field is static. If uninitialized,
+    /// this will take the value of an uninitialized Sentinel.
+    #[serde(rename = "staticValue")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub static_value: Option<serde_json::Value>,
+}
+
+/// A _Flag_ represents a single VM command line flag.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Flag {
+    /// The name of the flag.
+    pub name: String,
+
+    /// A description of the flag.
+    pub comment: String,
+
+    /// Has this flag been modified from its default setting?
+    pub modified: bool,
+
+    /// The value of this flag as a string.
+    ///
+    /// If this property is absent, then the value of the flag was nullptr.
+    #[serde(rename = "valueAsString")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_as_string: Option<String>,
+}
+
+/// A _FlagList_ represents the complete set of VM command line flags.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FlagList {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A list of all flags in the VM.
+    pub flags: Vec<Flag>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Frame {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    pub index: i64,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function: Option<FunctionRef>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<CodeRef>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<SourceLocation>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vars: Option<Vec<BoundVariable>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<FrameKind>,
+}
+
+/// An _@Function_ is a reference to a _Function_.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FunctionRef {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A unique identifier for an Object. Passed to the
+    /// getObject RPC to load this Object.
+    pub id: String,
+
+    /// Provided and set to true if the id of an Object is fixed. If true, the id
+    /// of an Object is guaranteed not to change or expire. The object may,
+    /// however, still be _Collected_.
+    #[serde(rename = "fixedId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_id: Option<bool>,
+
+    /// The name of this function.
+    pub name: String,
+
+    /// The owner of this function, which can be a Library, Class, or a
+    /// Function.
+    pub owner: serde_json::Value,
+
+    /// Is this function static?
+    #[serde(rename = "static")]
+    pub static_: bool,
+
+    /// Is this function const?
+    #[serde(rename = "const")]
+    pub const_: bool,
+
+    /// Is this function implicitly defined (e.g., implicit getter/setter)?
+    pub implicit: bool,
+
+    /// The location of this function in the source code.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<SourceLocation>,
+}
+
+/// A _Function_ represents a Dart language function.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Function {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A unique identifier for an Object. Passed to the
+    /// getObject RPC to reload this Object.
+    ///
+    /// Some objects may get a new id when they are reloaded.
+    pub id: String,
+
+    /// Provided and set to true if the id of an Object is fixed. If true, the id
+    /// of an Object is guaranteed not to change or expire. The object may,
+    /// however, still be _Collected_.
+    #[serde(rename = "fixedId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_id: Option<bool>,
+
+    /// If an object is allocated in the Dart heap, it will have
+    /// a corresponding class object.
+    ///
+    /// The class of a non-instance is not a Dart class, but is instead
+    /// an internal vm object.
+    ///
+    /// Moving an Object into or out of the heap is considered a
+    /// backwards compatible change for types other than Instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<ClassRef>,
+
+    /// The size of this object in the heap.
+    ///
+    /// If an object is not heap-allocated, then this field is omitted.
+    ///
+    /// Note that the size can be zero for some objects. In the current
+    /// VM implementation, this occurs for small integers, which are
+    /// stored entirely within their object pointers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<i64>,
+
+    /// The name of this function.
+    pub name: String,
+
+    /// The owner of this function, which can be a Library, Class, or a
+    /// Function.
+    pub owner: serde_json::Value,
+
+    /// Is this function static?
+    #[serde(rename = "static")]
+    pub static_: bool,
+
+    /// Is this function const?
+    #[serde(rename = "const")]
+    pub const_: bool,
+
+    /// Is this function implicitly defined (e.g., implicit getter/setter)?
+    pub implicit: bool,
+
+    /// The location of this function in the source code.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<SourceLocation>,
+
+    /// The signature of the function.
+    pub signature: InstanceRef,
+
+    /// The compiled code associated with this function.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<CodeRef>,
+}
+
+/// _@Instance_ is a reference to an _Instance_.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InstanceRef {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A unique identifier for an Object. Passed to the
+    /// getObject RPC to load this Object.
+    pub id: String,
+
+    /// Provided and set to true if the id of an Object is fixed. If true, the id
+    /// of an Object is guaranteed not to change or expire. The object may,
+    /// however, still be _Collected_.
+    #[serde(rename = "fixedId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_id: Option<bool>,
+
+    /// What kind of instance is this?
+    pub kind: InstanceKind,
+
+    /// The identityHashCode assigned to the allocated object. This hash
+    /// code is the same as the hash code provided in HeapSnapshot and
+    /// CpuSample's returned by getAllocationTraces().
+    #[serde(rename = "identityHashCode")]
+    pub identity_hash_code: i64,
+
+    /// Instance references always include their class.
+    pub class: ClassRef,
+
+    /// The value of this instance as a string.
+    ///
+    /// Provided for the instance kinds:
+    ///   Null (null)
+    ///   Bool (true or false)
+    ///   Double (suitable for passing to Double.parse())
+    ///   Int (suitable for passing to int.parse())
+    ///   String (value may be truncated)
+    ///   Float32x4
+    ///   Float64x2
+    ///   Int32x4
+    ///   StackTrace
+    #[serde(rename = "valueAsString")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_as_string: Option<String>,
+
+    /// The valueAsString for String references may be truncated. If so,
+    /// this property is added with the value 'true'.
+    ///
+    /// New code should use 'length' and 'count' instead.
+    #[serde(rename = "valueAsStringIsTruncated")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_as_string_is_truncated: Option<bool>,
+
+    /// The length of a List or the number of associations in a Map or the
+    /// number of codeunits in a String.
+    ///
+    /// Provided for instance kinds:
+    ///   String
+    ///   List
+    ///   Map
+    ///   Set
+    ///   Uint8ClampedList
+    ///   Uint8List
+    ///   Uint16List
+    ///   Uint32List
+    ///   Uint64List
+    ///   Int8List
+    ///   Int16List
+    ///   Int32List
+    ///   Int64List
+    ///   Float32List
+    ///   Float64List
+    ///   Int32x4List
+    ///   Float32x4List
+    ///   Float64x2List
+    ///   Record
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub length: Option<i64>,
+
+    /// The name of a Type instance.
+    ///
+    /// Provided for instance kinds:
+    ///   Type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// The corresponding Class if this Type has a resolved typeClass.
+    ///
+    /// Provided for instance kinds:
+    ///   Type
+    #[serde(rename = "typeClass")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_class: Option<ClassRef>,
+
+    /// The parameterized class of a type parameter.
+    ///
+    /// Provided for instance kinds:
+    ///   TypeParameter
+    #[serde(rename = "parameterizedClass")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameterized_class: Option<ClassRef>,
+
+    /// The pattern of a RegExp instance.
+    ///
+    /// The pattern is always an instance of kind String.
+    ///
+    /// Provided for instance kinds:
+    ///   RegExp
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<Box<InstanceRef>>,
+
+    /// The function associated with a Closure instance.
+    ///
+    /// Provided for instance kinds:
+    ///   Closure
+    #[serde(rename = "closureFunction")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closure_function: Option<FunctionRef>,
+
+    /// The context associated with a Closure instance.
+    ///
+    /// Provided for instance kinds:
+    ///   Closure
+    #[serde(rename = "closureContext")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closure_context: Option<ContextRef>,
+
+    /// The port ID for a ReceivePort.
+    ///
+    /// Provided for instance kinds:
+    ///   ReceivePort
+    #[serde(rename = "portId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port_id: Option<i64>,
+
+    /// The stack trace associated with the allocation of a ReceivePort.
+    ///
+    /// Provided for instance kinds:
+    ///   ReceivePort
+    #[serde(rename = "allocationLocation")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allocation_location: Option<Box<InstanceRef>>,
+
+    /// A name associated with a ReceivePort used for debugging purposes.
+    ///
+    /// Provided for instance kinds:
+    ///   ReceivePort
+    #[serde(rename = "debugName")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub debug_name: Option<String>,
+}
+
+/// An _Instance_ represents an instance of the Dart language class _Object_.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Instance {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A unique identifier for an Object. Passed to the
+    /// getObject RPC to reload this Object.
+    ///
+    /// Some objects may get a new id when they are reloaded.
+    pub id: String,
+
+    /// Provided and set to true if the id of an Object is fixed. If true, the id
+    /// of an Object is guaranteed not to change or expire. The object may,
+    /// however, still be _Collected_.
+    #[serde(rename = "fixedId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_id: Option<bool>,
+
+    /// Instance references always include their class.
+    pub class: ClassRef,
+
+    /// The size of this object in the heap.
+    ///
+    /// If an object is not heap-allocated, then this field is omitted.
+    ///
+    /// Note that the size can be zero for some objects. In the current
+    /// VM implementation, this occurs for small integers, which are
+    /// stored entirely within their object pointers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<i64>,
+
+    /// What kind of instance is this?
+    pub kind: InstanceKind,
+
+    /// The identityHashCode assigned to the allocated object. This hash
+    /// code is the same as the hash code provided in HeapSnapshot and
+    /// CpuSample's returned by getAllocationTraces().
+    #[serde(rename = "identityHashCode")]
+    pub identity_hash_code: i64,
+
+    /// The value of this instance as a string.
+    ///
+    /// Provided for the instance kinds:
+    ///   Bool (true or false)
+    ///   Double (suitable for passing to Double.parse())
+    ///   Int (suitable for passing to int.parse())
+    ///   String (value may be truncated)
+    #[serde(rename = "valueAsString")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_as_string: Option<String>,
+
+    /// The valueAsString for String references may be truncated. If so,
+    /// this property is added with the value 'true'.
+    ///
+    /// New code should use 'length' and 'count' instead.
+    #[serde(rename = "valueAsStringIsTruncated")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_as_string_is_truncated: Option<bool>,
+
+    /// The length of a List or the number of associations in a Map or the
+    /// number of codeunits in a String.
+    ///
+    /// Provided for instance kinds:
+    ///   String
+    ///   List
+    ///   Map
+    ///   Set
+    ///   Uint8ClampedList
+    ///   Uint8List
+    ///   Uint16List
+    ///   Uint32List
+    ///   Uint64List
+    ///   Int8List
+    ///   Int16List
+    ///   Int32List
+    ///   Int64List
+    ///   Float32List
+    ///   Float64List
+    ///   Int32x4List
+    ///   Float32x4List
+    ///   Float64x2List
+    ///   Record
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub length: Option<i64>,
+
+    /// The index of the first element or association or codeunit returned.
+    /// This is only provided when it is non-zero.
+    ///
+    /// Provided for instance kinds:
+    ///   String
+    ///   List
+    ///   Map
+    ///   Set
+    ///   Uint8ClampedList
+    ///   Uint8List
+    ///   Uint16List
+    ///   Uint32List
+    ///   Uint64List
+    ///   Int8List
+    ///   Int16List
+    ///   Int32List
+    ///   Int64List
+    ///   Float32List
+    ///   Float64List
+    ///   Int32x4List
+    ///   Float32x4List
+    ///   Float64x2List
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<i64>,
+
+    /// The number of elements or associations or codeunits returned.
+    /// This is only provided when it is less than length.
+    ///
+    /// Provided for instance kinds:
+    ///   String
+    ///   List
+    ///   Map
+    ///   Set
+    ///   Uint8ClampedList
+    ///   Uint8List
+    ///   Uint16List
+    ///   Uint32List
+    ///   Uint64List
+    ///   Int8List
+    ///   Int16List
+    ///   Int32List
+    ///   Int64List
+    ///   Float32List
+    ///   Float64List
+    ///   Int32x4List
+    ///   Float32x4List
+    ///   Float64x2List
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<i64>,
+
+    /// The name of a Type instance.
+    ///
+    /// Provided for instance kinds:
+    ///   Type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// The corresponding Class if this Type is canonical.
+    ///
+    /// Provided for instance kinds:
+    ///   Type
+    #[serde(rename = "typeClass")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_class: Option<ClassRef>,
+
+    /// The parameterized class of a type parameter:
+    ///
+    /// Provided for instance kinds:
+    ///   TypeParameter
+    #[serde(rename = "parameterizedClass")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameterized_class: Option<ClassRef>,
+
+    /// The type arguments for this type.
+    ///
+    /// Provided for instance kinds:
+    ///   Type
+    #[serde(rename = "typeArguments")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_arguments: Option<TypeArgumentsRef>,
+
+    /// The index of a TypeParameter instance.
+    ///
+    /// Provided for instance kinds:
+    ///   TypeParameter
+    #[serde(rename = "parameterIndex")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameter_index: Option<i64>,
+
+    /// The type bounded by a BoundedType instance
+    /// or
+    /// the referent of a TypeRef instance.
+    ///
+    /// The value will always be of one of the kinds:
+    /// Type, TypeRef, TypeParameter, BoundedType.
+    ///
+    /// Provided for instance kinds:
+    ///   BoundedType
+    ///   TypeRef
+    #[serde(rename = "targetType")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_type: Option<InstanceRef>,
+
+    /// The bound of a TypeParameter or BoundedType.
+    ///
+    /// The value will always be of one of the kinds:
+    /// Type, TypeRef, TypeParameter, BoundedType.
+    ///
+    /// Provided for instance kinds:
+    ///   BoundedType
+    ///   TypeParameter
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bound: Option<InstanceRef>,
+
+    /// The fields of this Instance.
+    ///
+    /// Provided for instance kinds:
+    ///   PlainInstance
+    ///   Record
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fields: Option<Vec<BoundField>>,
+
+    /// The elements of a List or Set instance.
+    ///
+    /// Provided for instance kinds:
+    ///   List
+    ///   Set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elements: Option<serde_json::Value>,
+
+    /// The elements of a Map instance.
+    ///
+    /// Provided for instance kinds:
+    ///   Map
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub associations: Option<Vec<MapAssociation>>,
+
+    /// The bytes of a TypedData instance.
+    ///
+    /// The data is provided as a Base64 encoded string.
+    ///
+    /// Provided for instance kinds:
+    ///   Uint8ClampedList
+    ///   Uint8List
+    ///   Uint16List
+    ///   Uint32List
+    ///   Uint64List
+    ///   Int8List
+    ///   Int16List
+    ///   Int32List
+    ///   Int64List
+    ///   Float32List
+    ///   Float64List
+    ///   Int32x4List
+    ///   Float32x4List
+    ///   Float64x2List
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<String>,
+
+    /// The referent of a MirrorReference instance.
+    ///
+    /// Provided for instance kinds:
+    ///   MirrorReference
+    #[serde(rename = "mirrorReferent")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mirror_referent: Option<InstanceRef>,
+
+    /// The pattern of a RegExp instance.
+    ///
+    /// Provided for instance kinds:
+    ///   RegExp
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<InstanceRef>,
+
+    /// The function associated with a Closure instance.
+    ///
+    /// Provided for instance kinds:
+    ///   Closure
+    #[serde(rename = "closureFunction")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closure_function: Option<FunctionRef>,
+
+    /// The context associated with a Closure instance.
+    ///
+    /// Provided for instance kinds:
+    ///   Closure
+    #[serde(rename = "closureContext")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closure_context: Option<ContextRef>,
+
+    /// Whether this regular expression is case sensitive.
+    ///
+    /// Provided for instance kinds:
+    ///   RegExp
+    #[serde(rename = "isCaseSensitive")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_case_sensitive: Option<bool>,
+
+    /// Whether this regular expression matches multiple lines.
+    ///
+    /// Provided for instance kinds:
+    ///   RegExp
+    #[serde(rename = "isMultiLine")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_multi_line: Option<bool>,
+
+    /// The key for a WeakProperty instance.
+    ///
+    /// Provided for instance kinds:
+    ///   WeakProperty
+    #[serde(rename = "propertyKey")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub property_key: Option<InstanceRef>,
+
+    /// The key for a WeakProperty instance.
+    ///
+    /// Provided for instance kinds:
+    ///   WeakProperty
+    #[serde(rename = "propertyValue")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub property_value: Option<InstanceRef>,
+
+    /// The target for a WeakReference instance.
+    ///
+    /// Provided for instance kinds:
+    ///   WeakReference
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<InstanceRef>,
+
+    /// The type parameters for a Closure instance.
+    ///
+    /// Provided for instance kinds:
+    ///   Closure
+    #[serde(rename = "typeParameters")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_parameters: Option<TypeParametersRef>,
+
+    /// The port ID for a ReceivePort.
+    ///
+    /// Provided for instance kinds:
+    ///   ReceivePort
+    #[serde(rename = "portId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port_id: Option<i64>,
+
+    /// The stack trace associated with the allocation of a ReceivePort.
+    ///
+    /// Provided for instance kinds:
+    ///   ReceivePort
+    #[serde(rename = "allocationLocation")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allocation_location: Option<InstanceRef>,
+
+    /// A name associated with a ReceivePort used for debugging purposes.
+    ///
+    /// Provided for instance kinds:
+    ///   ReceivePort
+    #[serde(rename = "debugName")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub debug_name: Option<String>,
+}
+
+/// See [getInboundReferences](#getinboundreferences).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InboundReferences {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// An array of inbound references to an object.
+    pub references: Vec<InboundReference>,
+}
+
+/// See [getInboundReferences](#getinboundreferences).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InboundReference {
+    /// The object holding the inbound reference.
+    pub source: serde_json::Value,
+
+    /// If source is a List, parentListIndex is the index of the inbound reference.
+    #[serde(rename = "parentListIndex")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_list_index: Option<i64>,
+
+    /// If source is a field of an object, parentField is the field containing
+    /// the inbound reference, or the name or position of a record field.
+    #[serde(rename = "parentField")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_field: Option<serde_json::Value>,
+}
+
+/// See [getInstances](#getinstances).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InstanceSet {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// The number of instances of the requested type currently allocated.
+    #[serde(rename = "totalCount")]
+    pub total_count: i64,
+
+    /// An array of instances of the requested type.
+    pub instances: Vec<serde_json::Value>,
+}
+
+/// _@Isolate_ is a reference to an _Isolate_ object.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IsolateRef {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// The id which is passed to the getIsolate RPC to load this isolate.
+    pub id: String,
+
+    /// A numeric id for this isolate, represented as a string. Unique.
+    pub number: String,
+
+    /// A name identifying this isolate. Not guaranteed to be unique.
+    pub name: String,
+
+    /// Specifies whether the isolate was spawned by the VM or embedder for
+    /// internal use. If `false`, this isolate is likely running user code.
+    #[serde(rename = "isSystemIsolate")]
+    pub is_system_isolate: bool,
+
+    /// The id of the isolate group that this isolate belongs to.
+    #[serde(rename = "isolateGroupId")]
+    pub isolate_group_id: String,
+}
+
+/// An _Isolate_ object provides information about one isolate in the VM.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Isolate {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// The id which is passed to the getIsolate RPC to reload this
+    /// isolate.
+    pub id: String,
+
+    /// A numeric id for this isolate, represented as a string. Unique.
+    pub number: String,
+
+    /// A name identifying this isolate. Not guaranteed to be unique.
+    pub name: String,
+
+    /// Specifies whether the isolate was spawned by the VM or embedder for
+    /// internal use. If `false`, this isolate is likely running user code.
+    #[serde(rename = "isSystemIsolate")]
+    pub is_system_isolate: bool,
+
+    /// The id of the isolate group that this isolate belongs to.
+    #[serde(rename = "isolateGroupId")]
+    pub isolate_group_id: String,
+
+    /// The list of isolate flags provided to this isolate. See Dart_IsolateFlags
+    /// in dart_api.h for the list of accepted isolate flags.
+    #[serde(rename = "isolateFlags")]
+    pub isolate_flags: Vec<IsolateFlag>,
+
+    /// The time that the VM started in milliseconds since the epoch.
+    ///
+    /// Suitable to pass to DateTime.fromMillisecondsSinceEpoch.
+    #[serde(rename = "startTime")]
+    pub start_time: i64,
+
+    /// Is the isolate in a runnable state?
+    pub runnable: bool,
+
+    /// The number of live ports for this isolate.
+    #[serde(rename = "livePorts")]
+    pub live_ports: i64,
+
+    /// Will this isolate pause when exiting?
+    #[serde(rename = "pauseOnExit")]
+    pub pause_on_exit: bool,
+
+    /// The last pause event delivered to the isolate. If the isolate is
+    /// running, this will be a resume event.
+    #[serde(rename = "pauseEvent")]
+    pub pause_event: Event,
+
+    /// The root library for this isolate.
+    ///
+    /// Guaranteed to be initialized when the IsolateRunnable event fires.
+    #[serde(rename = "rootLib")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root_lib: Option<LibraryRef>,
+
+    /// A list of all libraries for this isolate.
+    ///
+    /// Guaranteed to be initialized when the IsolateRunnable event fires.
+    pub libraries: Vec<LibraryRef>,
+
+    /// A list of all breakpoints for this isolate.
+    pub breakpoints: Vec<Breakpoint>,
+
+    /// The error that is causing this isolate to exit, if applicable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<Error>,
+
+    /// The current pause on exception mode for this isolate.
+    #[serde(rename = "exceptionPauseMode")]
+    pub exception_pause_mode: ExceptionPauseMode,
+
+    /// The list of service extension RPCs that are registered for this isolate,
+    /// if any.
+    #[serde(rename = "extensionRPCs")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extension_rpcs: Option<Vec<String>>,
+}
+
+/// Represents the value of a single isolate flag. See [Isolate](#isolate).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IsolateFlag {
+    /// The name of the flag.
+    pub name: String,
+
+    /// The value of this flag as a string.
+    #[serde(rename = "valueAsString")]
+    pub value_as_string: String,
+}
+
+/// _@IsolateGroup_ is a reference to an _IsolateGroup_ object.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IsolateGroupRef {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// The id which is passed to the getIsolateGroup RPC to load this isolate group.
+    pub id: String,
+
+    /// A numeric id for this isolate group, represented as a string. Unique.
+    pub number: String,
+
+    /// A name identifying this isolate group. Not guaranteed to be unique.
+    pub name: String,
+
+    /// Specifies whether the isolate group was spawned by the VM or embedder for
+    /// internal use. If `false`, this isolate group is likely running user code.
+    #[serde(rename = "isSystemIsolateGroup")]
+    pub is_system_isolate_group: bool,
+}
+
+/// An _IsolateGroup_ object provides information about an isolate group in
+/// the VM.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IsolateGroup {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// The id which is passed to the getIsolateGroup RPC to reload this
+    /// isolate.
+    pub id: String,
+
+    /// A numeric id for this isolate, represented as a string. Unique.
+    pub number: String,
+
+    /// A name identifying this isolate group. Not guaranteed to be unique.
+    pub name: String,
+
+    /// Specifies whether the isolate group was spawned by the VM or embedder for
+    /// internal use. If `false`, this isolate group is likely running user code.
+    #[serde(rename = "isSystemIsolateGroup")]
+    pub is_system_isolate_group: bool,
+
+    /// A list of all isolates in this isolate group.
+    pub isolates: Vec<IsolateRef>,
+}
+
+/// _@Library_ is a reference to a _Library_.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LibraryRef {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A unique identifier for an Object. Passed to the
+    /// getObject RPC to load this Object.
+    pub id: String,
+
+    /// Provided and set to true if the id of an Object is fixed. If true, the id
+    /// of an Object is guaranteed not to change or expire. The object may,
+    /// however, still be _Collected_.
+    #[serde(rename = "fixedId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_id: Option<bool>,
+
+    /// The name of this library.
+    pub name: String,
+
+    /// The uri of this library.
+    pub uri: String,
+}
+
+/// A _Library_ provides information about a Dart language library.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Library {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A unique identifier for an Object. Passed to the
+    /// getObject RPC to reload this Object.
+    ///
+    /// Some objects may get a new id when they are reloaded.
+    pub id: String,
+
+    /// Provided and set to true if the id of an Object is fixed. If true, the id
+    /// of an Object is guaranteed not to change or expire. The object may,
+    /// however, still be _Collected_.
+    #[serde(rename = "fixedId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_id: Option<bool>,
+
+    /// If an object is allocated in the Dart heap, it will have
+    /// a corresponding class object.
+    ///
+    /// The class of a non-instance is not a Dart class, but is instead
+    /// an internal vm object.
+    ///
+    /// Moving an Object into or out of the heap is considered a
+    /// backwards compatible change for types other than Instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<ClassRef>,
+
+    /// The size of this object in the heap.
+    ///
+    /// If an object is not heap-allocated, then this field is omitted.
+    ///
+    /// Note that the size can be zero for some objects. In the current
+    /// VM implementation, this occurs for small integers, which are
+    /// stored entirely within their object pointers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<i64>,
+
+    /// The name of this library.
+    pub name: String,
+
+    /// The uri of this library.
+    pub uri: String,
+
+    /// Is this library debuggable? Default true.
+    pub debuggable: bool,
+
+    /// A list of the imports for this library.
+    pub dependencies: Vec<LibraryDependency>,
+
+    /// A list of the scripts which constitute this library.
+    pub scripts: Vec<ScriptRef>,
+
+    /// A list of the top-level variables in this library.
+    pub variables: Vec<FieldRef>,
+
+    /// A list of the top-level functions in this library.
+    pub functions: Vec<FunctionRef>,
+
+    /// A list of all classes in this library.
+    pub classes: Vec<ClassRef>,
+}
+
+/// A _LibraryDependency_ provides information about an import or export.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LibraryDependency {
+    /// Is this dependency an import (rather than an export)?
+    #[serde(rename = "isImport")]
+    pub is_import: bool,
+
+    /// Is this dependency deferred?
+    #[serde(rename = "isDeferred")]
+    pub is_deferred: bool,
+
+    /// The prefix of an 'as' import, or null.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+
+    /// The library being imported or exported.
+    pub target: LibraryRef,
+
+    /// The list of symbols made visible from this dependency.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shows: Option<Vec<String>>,
+
+    /// The list of symbols hidden from this dependency.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hides: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LogRecord {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// The log message.
+    pub message: InstanceRef,
+
+    /// The timestamp.
+    pub time: i64,
+
+    /// The severity level (a value between 0 and 2000).
+    ///
+    /// See the package:logging `Level` class for an overview of the possible
+    /// values.
+    pub level: i64,
+
+    /// A monotonically increasing sequence number.
+    #[serde(rename = "sequenceNumber")]
+    pub sequence_number: i64,
+
+    /// The name of the source of the log message.
+    #[serde(rename = "loggerName")]
+    pub logger_name: InstanceRef,
+
+    /// The zone where the log was emitted.
+    pub zone: InstanceRef,
+
+    /// An error object associated with this log event.
+    pub error: InstanceRef,
+
+    /// A stack trace associated with this log event.
+    #[serde(rename = "stackTrace")]
+    pub stack_trace: InstanceRef,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MapAssociation {
+    pub key: serde_json::Value,
+
+    pub value: serde_json::Value,
+}
+
+/// A _MemoryUsage_ object provides heap usage information for a specific
+/// isolate at a given point in time.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MemoryUsage {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// The amount of non-Dart memory that is retained by Dart objects. For
+    /// example, memory associated with Dart objects through APIs such as
+    /// Dart_NewFinalizableHandle, Dart_NewWeakPersistentHandle and
+    /// Dart_NewExternalTypedData.  This usage is only as accurate as the values
+    /// supplied to these APIs from the VM embedder. This external memory applies
+    /// GC pressure, but is separate from heapUsage and heapCapacity.
+    #[serde(rename = "externalUsage")]
+    pub external_usage: i64,
+
+    /// The total capacity of the heap in bytes. This is the amount of memory used
+    /// by the Dart heap from the perspective of the operating system.
+    #[serde(rename = "heapCapacity")]
+    pub heap_capacity: i64,
+
+    /// The current heap memory usage in bytes. Heap usage is always less than or
+    /// equal to the heap capacity.
+    #[serde(rename = "heapUsage")]
+    pub heap_usage: i64,
+}
+
+/// A _Message_ provides information about a pending isolate message and the
+/// function that will be invoked to handle it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Message {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// The index in the isolate's message queue. The 0th message being the next
+    /// message to be processed.
+    pub index: i64,
+
+    /// An advisory name describing this message.
+    pub name: String,
+
+    /// An instance id for the decoded message. This id can be passed to other
+    /// RPCs, for example, getObject or evaluate.
+    #[serde(rename = "messageObjectId")]
+    pub message_object_id: String,
+
+    /// The size (bytes) of the encoded message.
+    pub size: i64,
+
+    /// A reference to the function that will be invoked to handle this message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handler: Option<FunctionRef>,
+
+    /// The source location of handler.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<SourceLocation>,
+}
+
+/// A _NativeFunction_ object is used to represent native functions in profiler
+/// samples. See [CpuSamples](#cpusamples);
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NativeFunction {
+    /// The name of the native function this object represents.
+    pub name: String,
+}
+
+/// A _PortList_ contains a list of ports associated with some isolate.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PortList {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    pub ports: Vec<InstanceRef>,
+}
+
+/// A _ProfileFunction_ contains profiling information about a Dart or native
+/// function.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProfileFunction {
+    /// The kind of function this object represents.
+    pub kind: String,
+
+    /// The number of times function appeared on the stack during sampling events.
+    #[serde(rename = "inclusiveTicks")]
+    pub inclusive_ticks: i64,
+
+    /// The number of times function appeared on the top of the stack during
+    /// sampling events.
+    #[serde(rename = "exclusiveTicks")]
+    pub exclusive_ticks: i64,
+
+    /// The resolved URL for the script containing function.
+    #[serde(rename = "resolvedUrl")]
+    pub resolved_url: String,
+
+    /// The function captured during profiling.
+    pub function: serde_json::Value,
+}
+
+/// A _ProtocolList_ contains a list of all protocols supported by the service
+/// instance.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProtocolList {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A list of supported protocols provided by this service.
+    pub protocols: Vec<Protocol>,
+}
+
+/// See [getSupportedProtocols](#getsupportedprotocols).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Protocol {
+    /// The name of the supported protocol.
+    #[serde(rename = "protocolName")]
+    pub protocol_name: String,
+
+    /// The major revision of the protocol.
+    pub major: i64,
+
+    /// The minor revision of the protocol.
+    pub minor: i64,
+}
+
+/// See [getProcessMemoryUsage](#getprocessmemoryusage).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProcessMemoryUsage {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    pub root: ProcessMemoryItem,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProcessMemoryItem {
+    /// A short name for this bucket of memory.
+    pub name: String,
+
+    /// A longer description for this item.
+    pub description: String,
+
+    /// The amount of memory in bytes.
+    /// This is a retained size, not a shallow size. That is, it includes the size
+    /// of children.
+    pub size: i64,
+
+    /// Subdivisions of this bucket of memory.
+    pub children: Vec<ProcessMemoryItem>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReloadReport {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// Did the reload succeed or fail?
+    pub success: bool,
+}
+
+/// See [RetainingPath](#retainingpath).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RetainingObject {
+    /// An object that is part of a retaining path.
+    pub value: serde_json::Value,
+
+    /// The offset of the retaining object in a containing list.
+    #[serde(rename = "parentListIndex")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_list_index: Option<i64>,
+
+    /// The key mapping to the retaining object in a containing map.
+    #[serde(rename = "parentMapKey")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_map_key: Option<serde_json::Value>,
+
+    /// The name of the field containing the retaining object within an object.
+    #[serde(rename = "parentField")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_field: Option<serde_json::Value>,
+}
+
+/// See [getRetainingPath](#getretainingpath).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RetainingPath {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// The length of the retaining path.
+    pub length: i64,
+
+    /// The type of GC root which is holding a reference to the specified object.
+    /// Possible values include:
+    ///  * class table
+    ///  * local handle
+    ///  * persistent handle
+    ///  * stack
+    ///  * user global
+    ///  * weak persistent handle
+    ///  * unknown
+    #[serde(rename = "gcRootType")]
+    pub gc_root_type: String,
+
+    /// The chain of objects which make up the retaining path.
+    pub elements: Vec<RetainingObject>,
+}
+
+/// A _Sentinel_ is used to indicate that the normal response is not
+/// available.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Sentinel {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// What kind of sentinel is this?
+    pub kind: SentinelKind,
+
+    /// A reasonable string representation of this sentinel.
+    #[serde(rename = "valueAsString")]
+    pub value_as_string: String,
+}
+
+/// _@Script_ is a reference to a _Script_.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScriptRef {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A unique identifier for an Object. Passed to the
+    /// getObject RPC to load this Object.
+    pub id: String,
+
+    /// Provided and set to true if the id of an Object is fixed. If true, the id
+    /// of an Object is guaranteed not to change or expire. The object may,
+    /// however, still be _Collected_.
+    #[serde(rename = "fixedId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_id: Option<bool>,
+
+    /// The uri from which this script was loaded.
+    pub uri: String,
+}
+
+/// A _Script_ provides information about a Dart language script.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Script {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A unique identifier for an Object. Passed to the
+    /// getObject RPC to reload this Object.
+    ///
+    /// Some objects may get a new id when they are reloaded.
+    pub id: String,
+
+    /// Provided and set to true if the id of an Object is fixed. If true, the id
+    /// of an Object is guaranteed not to change or expire. The object may,
+    /// however, still be _Collected_.
+    #[serde(rename = "fixedId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_id: Option<bool>,
+
+    /// If an object is allocated in the Dart heap, it will have
+    /// a corresponding class object.
+    ///
+    /// The class of a non-instance is not a Dart class, but is instead
+    /// an internal vm object.
+    ///
+    /// Moving an Object into or out of the heap is considered a
+    /// backwards compatible change for types other than Instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<ClassRef>,
+
+    /// The size of this object in the heap.
+    ///
+    /// If an object is not heap-allocated, then this field is omitted.
+    ///
+    /// Note that the size can be zero for some objects. In the current
+    /// VM implementation, this occurs for small integers, which are
+    /// stored entirely within their object pointers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<i64>,
+
+    /// The uri from which this script was loaded.
+    pub uri: String,
+
+    /// The library which owns this script.
+    pub library: LibraryRef,
+
+    #[serde(rename = "lineOffset")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line_offset: Option<i64>,
+
+    #[serde(rename = "columnOffset")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column_offset: Option<i64>,
+
+    /// The source code for this script. This can be null for certain built-in
+    /// scripts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+
+    /// A table encoding a mapping from token position to line and column. This
+    /// field is null if sources aren't available.
+    #[serde(rename = "tokenPosTable")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_pos_table: Option<Vec<Vec<i64>>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScriptList {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    pub scripts: Vec<ScriptRef>,
+}
+
+/// The _SourceLocation_ class is used to designate a position or range in
+/// some script.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SourceLocation {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// The script containing the source location.
+    pub script: ScriptRef,
+
+    /// The first token of the location.
+    #[serde(rename = "tokenPos")]
+    pub token_pos: i64,
+
+    /// The last token of the location if this is a range.
+    #[serde(rename = "endTokenPos")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_token_pos: Option<i64>,
+
+    /// The line associated with this location. Only provided for non-synthetic
+    /// token positions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<i64>,
+
+    /// The column associated with this location. Only provided for non-synthetic
+    /// token positions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<i64>,
+}
+
+/// The _SourceReport_ class represents a set of reports tied to source
+/// locations in an isolate.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SourceReport {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A list of ranges in the program source.  These ranges correspond
+    /// to ranges of executable code in the user's program (functions,
+    /// methods, constructors, etc.)
+    ///
+    /// Note that ranges may nest in other ranges, in the case of nested
+    /// functions.
+    ///
+    /// Note that ranges may be duplicated, in the case of mixins.
+    pub ranges: Vec<SourceReportRange>,
+
+    /// A list of scripts, referenced by index in the report's ranges.
+    pub scripts: Vec<ScriptRef>,
+}
+
+/// The _SourceReportCoverage_ class represents coverage information for one
+/// [SourceReportRange](#sourcereportrange).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SourceReportCoverage {
+    /// A list of token positions (or line numbers if reportLines was enabled) in
+    /// a SourceReportRange which have been executed.  The list is sorted.
+    pub hits: Vec<i64>,
+
+    /// A list of token positions (or line numbers if reportLines was enabled) in
+    /// a SourceReportRange which have not been executed.  The list is sorted.
+    pub misses: Vec<i64>,
+}
+
+/// The _SourceReportRange_ class represents a range of executable code
+/// (function, method, constructor, etc) in the running program. It is part of
+/// a [SourceReport](#sourcereport).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SourceReportRange {
+    /// An index into the script table of the SourceReport, which indicates
+    /// which script contains this range of code.
+    #[serde(rename = "scriptIndex")]
+    pub script_index: i64,
+
+    /// The token position at which this range begins.
+    #[serde(rename = "startPos")]
+    pub start_pos: i64,
+
+    /// The token position at which this range ends.  Inclusive.
+    #[serde(rename = "endPos")]
+    pub end_pos: i64,
+
+    /// Has this range been compiled by the Dart VM?
+    pub compiled: bool,
+
+    /// The error while attempting to compile this range, if this
+    /// report was generated with forceCompile=true.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorRef>,
+
+    /// Code coverage information for this range.  Provided only when the
+    /// Coverage report has been requested and the range has been
+    /// compiled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coverage: Option<SourceReportCoverage>,
+
+    /// Possible breakpoint information for this range, represented as a
+    /// sorted list of token positions (or line numbers if reportLines was
+    /// enabled).  Provided only when the when the PossibleBreakpoint report has
+    /// been requested and the range has been compiled.
+    #[serde(rename = "possibleBreakpoints")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub possible_breakpoints: Option<Vec<i64>>,
+}
+
+/// The _Stack_ class represents the various components of a Dart stack trace
+/// for a given isolate.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Stack {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A list of frames that make up the synchronous stack, rooted at the message
+    /// loop (i.e., the frames since the last asynchronous gap or the isolate's
+    /// entrypoint).
+    pub frames: Vec<Frame>,
+
+    /// A list of frames representing the asynchronous path. Comparable to
+    /// `awaiterFrames`, if provided, although some frames may be different.
+    #[serde(rename = "asyncCausalFrames")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub async_causal_frames: Option<Vec<Frame>>,
+
+    /// A list of frames representing the asynchronous path. Comparable to
+    /// `asyncCausalFrames`, if provided, although some frames may be different.
+    #[serde(rename = "awaiterFrames")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub awaiter_frames: Option<Vec<Frame>>,
+
+    /// A list of messages in the isolate's message queue.
+    pub messages: Vec<Message>,
+
+    /// Specifies whether or not this stack is complete or has been artificially
+    /// truncated.
+    pub truncated: bool,
+}
+
+/// The _Success_ type is used to indicate that an operation completed
+/// successfully.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Success {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Timeline {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A list of timeline events. No order is guaranteed for these events; in
+    /// particular, these events may be unordered with respect to their
+    /// timestamps.
+    #[serde(rename = "traceEvents")]
+    pub trace_events: Vec<serde_json::Value>,
+
+    /// The start of the period of time in which traceEvents were collected.
+    #[serde(rename = "timeOriginMicros")]
+    pub time_origin_micros: i64,
+
+    /// The duration of time covered by the timeline.
+    #[serde(rename = "timeExtentMicros")]
+    pub time_extent_micros: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TimelineFlags {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// The name of the recorder currently in use. Recorder types include, but are
+    /// not limited to: Callback, Endless, Fuchsia, Macos, Ring, Startup, and
+    /// Systrace.
+    /// Set to "null" if no recorder is currently set.
+    #[serde(rename = "recorderName")]
+    pub recorder_name: String,
+
+    /// The list of all available timeline streams.
+    #[serde(rename = "availableStreams")]
+    pub available_streams: Vec<String>,
+
+    /// The list of timeline streams that are currently enabled.
+    #[serde(rename = "recordedStreams")]
+    pub recorded_streams: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Timestamp {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A timestamp in microseconds since epoch.
+    pub timestamp: i64,
+}
+
+/// _@TypeArguments_ is a reference to a _TypeArguments_ object.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TypeArgumentsRef {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A unique identifier for an Object. Passed to the
+    /// getObject RPC to load this Object.
+    pub id: String,
+
+    /// Provided and set to true if the id of an Object is fixed. If true, the id
+    /// of an Object is guaranteed not to change or expire. The object may,
+    /// however, still be _Collected_.
+    #[serde(rename = "fixedId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_id: Option<bool>,
+
+    /// A name for this type argument list.
+    pub name: String,
+}
+
+/// A _TypeArguments_ object represents the type argument vector for some
+/// instantiated generic type.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TypeArguments {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A unique identifier for an Object. Passed to the
+    /// getObject RPC to reload this Object.
+    ///
+    /// Some objects may get a new id when they are reloaded.
+    pub id: String,
+
+    /// Provided and set to true if the id of an Object is fixed. If true, the id
+    /// of an Object is guaranteed not to change or expire. The object may,
+    /// however, still be _Collected_.
+    #[serde(rename = "fixedId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_id: Option<bool>,
+
+    /// If an object is allocated in the Dart heap, it will have
+    /// a corresponding class object.
+    ///
+    /// The class of a non-instance is not a Dart class, but is instead
+    /// an internal vm object.
+    ///
+    /// Moving an Object into or out of the heap is considered a
+    /// backwards compatible change for types other than Instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<ClassRef>,
+
+    /// The size of this object in the heap.
+    ///
+    /// If an object is not heap-allocated, then this field is omitted.
+    ///
+    /// Note that the size can be zero for some objects. In the current
+    /// VM implementation, this occurs for small integers, which are
+    /// stored entirely within their object pointers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<i64>,
+
+    /// A name for this type argument list.
+    pub name: String,
+
+    /// A list of types.
+    ///
+    /// The value will always be one of the kinds:
+    /// Type, TypeRef, TypeParameter, BoundedType.
+    pub types: Vec<InstanceRef>,
+}
+
+/// _@TypeParameters_ is a reference to a _TypeParameters_ object.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TypeParametersRef {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A unique identifier for an Object. Passed to the
+    /// getObject RPC to load this Object.
+    pub id: String,
+
+    /// Provided and set to true if the id of an Object is fixed. If true, the id
+    /// of an Object is guaranteed not to change or expire. The object may,
+    /// however, still be _Collected_.
+    #[serde(rename = "fixedId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_id: Option<bool>,
+}
+
+/// A _TypeParameters_ object represents the type argument vector for some
+/// uninstantiated generic type.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TypeParameters {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A unique identifier for an Object. Passed to the
+    /// getObject RPC to reload this Object.
+    ///
+    /// Some objects may get a new id when they are reloaded.
+    pub id: String,
+
+    /// Provided and set to true if the id of an Object is fixed. If true, the id
+    /// of an Object is guaranteed not to change or expire. The object may,
+    /// however, still be _Collected_.
+    #[serde(rename = "fixedId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_id: Option<bool>,
+
+    /// If an object is allocated in the Dart heap, it will have
+    /// a corresponding class object.
+    ///
+    /// The class of a non-instance is not a Dart class, but is instead
+    /// an internal vm object.
+    ///
+    /// Moving an Object into or out of the heap is considered a
+    /// backwards compatible change for types other than Instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<ClassRef>,
+
+    /// The size of this object in the heap.
+    ///
+    /// If an object is not heap-allocated, then this field is omitted.
+    ///
+    /// Note that the size can be zero for some objects. In the current
+    /// VM implementation, this occurs for small integers, which are
+    /// stored entirely within their object pointers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<i64>,
+
+    /// The names of the type parameters.
+    pub names: InstanceRef,
+
+    /// The bounds set on each type parameter.
+    pub bounds: TypeArgumentsRef,
+
+    /// The default types for each type parameter.
+    pub defaults: TypeArgumentsRef,
+}
+
+/// The _UnresolvedSourceLocation_ class is used to refer to an unresolved
+/// breakpoint location. As such, it is meant to approximate the final
+/// location of the breakpoint but it is not exact.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UnresolvedSourceLocation {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// The script containing the source location if the script has been loaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<ScriptRef>,
+
+    /// The uri of the script containing the source location if the script
+    /// has yet to be loaded.
+    #[serde(rename = "scriptUri")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script_uri: Option<String>,
+
+    /// An approximate token position for the source location. This may
+    /// change when the location is resolved.
+    #[serde(rename = "tokenPos")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_pos: Option<i64>,
+
+    /// An approximate line number for the source location. This may
+    /// change when the location is resolved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<i64>,
+
+    /// An approximate column number for the source location. This may
+    /// change when the location is resolved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UriList {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A list of URIs.
+    pub uris: serde_json::Value,
+}
+
+/// See [Versioning](#versioning).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Version {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// The major version number is incremented when the protocol is changed
+    /// in a potentially incompatible way.
+    pub major: i64,
+
+    /// The minor version number is incremented when the protocol is changed
+    /// in a backwards compatible way.
+    pub minor: i64,
+}
+
+/// _@VM_ is a reference to a _VM_ object.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VMRef {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A name identifying this vm. Not guaranteed to be unique.
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VM {
+    /// Every response returned by the VM Service has the
+    /// type property. This allows the client distinguish
+    /// between different kinds of responses.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// A name identifying this vm. Not guaranteed to be unique.
+    pub name: String,
+
+    /// Word length on target architecture (e.g. 32, 64).
+    #[serde(rename = "architectureBits")]
+    pub architecture_bits: i64,
+
+    /// The CPU we are actually running on.
+    #[serde(rename = "hostCPU")]
+    pub host_cpu: String,
+
+    /// The operating system we are running on.
+    #[serde(rename = "operatingSystem")]
+    pub operating_system: String,
+
+    /// The CPU we are generating code for.
+    #[serde(rename = "targetCPU")]
+    pub target_cpu: String,
+
+    /// The Dart VM version string.
+    pub version: String,
+
+    /// The process id for the VM.
+    pub pid: i64,
+
+    /// The time that the VM started in milliseconds since the epoch.
+    ///
+    /// Suitable to pass to DateTime.fromMillisecondsSinceEpoch.
+    #[serde(rename = "startTime")]
+    pub start_time: i64,
+
+    /// A list of isolates running in the VM.
+    pub isolates: Vec<IsolateRef>,
+
+    /// A list of isolate groups running in the VM.
+    #[serde(rename = "isolateGroups")]
+    pub isolate_groups: Vec<IsolateGroupRef>,
+
+    /// A list of system isolates running in the VM.
+    #[serde(rename = "systemIsolates")]
+    pub system_isolates: Vec<IsolateRef>,
+
+    /// A list of isolate groups which contain system isolates running in the VM.
+    #[serde(rename = "systemIsolateGroups")]
+    pub system_isolate_groups: Vec<IsolateGroupRef>,
+}
+
+/// A request ready to be serialized and sent to the vm service.
+#[derive(Serialize, Debug)]
+pub struct Request {
+    pub jsonrpc: &'static str,
+    pub id: i32,
+    pub method: &'static str,
+    pub params: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Builds requests for every method in the protocol.
+///
+/// Clones share the id counter, so ids stay unique to a connection.
+#[derive(Clone)]
+pub struct Requests {
+    id: std::sync::Arc<std::sync::atomic::AtomicI32>,
+}
+
+impl Default for Requests {
+    fn default() -> Self {
+        Requests {
+            id: std::sync::Arc::new(std::sync::atomic::AtomicI32::new(0)),
+        }
+    }
+}
+
+impl Requests {
+    pub fn new() -> Self {
+        Requests::default()
+    }
+
+    /// The _addBreakpoint_ RPC is used to add a breakpoint at a specific line
+    /// of some script.
+    ///
+    /// Responds with `Breakpoint|Sentinel`.
+    pub fn add_breakpoint(
+        &self,
+        isolate_id: &str,
+        script_id: &str,
+        line: i64,
+        column: Option<i64>,
+    ) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        params.insert(
+            "scriptId".to_string(),
+            serde_json::to_value(script_id).unwrap(),
+        );
+        params.insert("line".to_string(), serde_json::to_value(line).unwrap());
+        if let Some(column) = column {
+            params.insert("column".to_string(), serde_json::to_value(column).unwrap());
+        }
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "addBreakpoint",
+            params,
+        }
+    }
+
+    /// The _addBreakpointAtEntry_ RPC is used to add a breakpoint at the
+    /// entrypoint of some function.
+    ///
+    /// Responds with `Breakpoint|Sentinel`.
+    pub fn add_breakpoint_at_entry(&self, isolate_id: &str, function_id: &str) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        params.insert(
+            "functionId".to_string(),
+            serde_json::to_value(function_id).unwrap(),
+        );
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "addBreakpointAtEntry",
+            params,
+        }
+    }
+
+    /// The _addBreakpoint_ RPC is used to add a breakpoint at a specific line
+    /// of some script. This RPC is useful when a script has not yet been
+    /// assigned an id, for example, if a script is in a deferred library
+    /// which has not yet been loaded.
+    ///
+    /// Responds with `Breakpoint|Sentinel`.
+    pub fn add_breakpoint_with_script_uri(
+        &self,
+        isolate_id: &str,
+        script_uri: &str,
+        line: i64,
+        column: Option<i64>,
+    ) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        params.insert(
+            "scriptUri".to_string(),
+            serde_json::to_value(script_uri).unwrap(),
+        );
+        params.insert("line".to_string(), serde_json::to_value(line).unwrap());
+        if let Some(column) = column {
+            params.insert("column".to_string(), serde_json::to_value(column).unwrap());
+        }
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "addBreakpointWithScriptUri",
+            params,
+        }
+    }
+
+    /// Clears all CPU profiling samples.
+    ///
+    /// Responds with `Success|Sentinel`.
+    pub fn clear_cpu_samples(&self, isolate_id: &str) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "clearCpuSamples",
+            params,
+        }
+    }
+
+    /// Clears all VM timeline events.
+    ///
+    /// Responds with `Success`.
+    pub fn clear_vm_timeline(&self) -> Request {
+        let params = serde_json::Map::new();
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "clearVMTimeline",
+            params,
+        }
+    }
+
+    /// The _evaluate_ RPC is used to evaluate an expression in the context of
+    /// some target.
+    ///
+    /// Responds with `@Instance|@Error|Sentinel`.
+    pub fn evaluate(
+        &self,
+        isolate_id: &str,
+        target_id: &str,
+        expression: &str,
+        scope: Option<serde_json::Value>,
+        disable_breakpoints: Option<bool>,
+    ) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        params.insert(
+            "targetId".to_string(),
+            serde_json::to_value(target_id).unwrap(),
+        );
+        params.insert(
+            "expression".to_string(),
+            serde_json::to_value(expression).unwrap(),
+        );
+        if let Some(scope) = scope {
+            params.insert("scope".to_string(), serde_json::to_value(scope).unwrap());
+        }
+        if let Some(disable_breakpoints) = disable_breakpoints {
+            params.insert(
+                "disableBreakpoints".to_string(),
+                serde_json::to_value(disable_breakpoints).unwrap(),
+            );
+        }
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "evaluate",
+            params,
+        }
+    }
+
+    /// The _evaluateInFrame_ RPC is used to evaluate an expression in the
+    /// context of a particular stack frame. _frameIndex_ is the index of the
+    /// desired Frame, with an index of _0_ indicating the top (most recent)
+    /// frame.
+    ///
+    /// Responds with `@Instance|@Error|Sentinel`.
+    pub fn evaluate_in_frame(
+        &self,
+        isolate_id: &str,
+        frame_index: i64,
+        expression: &str,
+        scope: Option<serde_json::Value>,
+        disable_breakpoints: Option<bool>,
+    ) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        params.insert(
+            "frameIndex".to_string(),
+            serde_json::to_value(frame_index).unwrap(),
+        );
+        params.insert(
+            "expression".to_string(),
+            serde_json::to_value(expression).unwrap(),
+        );
+        if let Some(scope) = scope {
+            params.insert("scope".to_string(), serde_json::to_value(scope).unwrap());
+        }
+        if let Some(disable_breakpoints) = disable_breakpoints {
+            params.insert(
+                "disableBreakpoints".to_string(),
+                serde_json::to_value(disable_breakpoints).unwrap(),
+            );
+        }
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "evaluateInFrame",
+            params,
+        }
+    }
+
+    /// The _getAllocationProfile_ RPC is used to retrieve allocation information
+    /// for a given isolate.
+    ///
+    /// Responds with `AllocationProfile|Sentinel`.
+    pub fn get_allocation_profile(
+        &self,
+        isolate_id: &str,
+        reset: Option<bool>,
+        gc: Option<bool>,
+    ) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        if let Some(reset) = reset {
+            params.insert("reset".to_string(), serde_json::to_value(reset).unwrap());
+        }
+        if let Some(gc) = gc {
+            params.insert("gc".to_string(), serde_json::to_value(gc).unwrap());
+        }
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "getAllocationProfile",
+            params,
+        }
+    }
+
+    /// The _getAllocationTraces_ RPC allows for the retrieval of allocation
+    /// traces for objects of a specific set of types (see
+    /// [setTraceClassAllocation](#settraceclassallocation)). Only samples
+    /// collected in the time range `[timeOriginMicros, timeOriginMicros +
+    /// timeExtentMicros]` will be reported.
+    ///
+    /// Responds with `CpuSamples`.
+    pub fn get_allocation_traces(
+        &self,
+        isolate_id: &str,
+        time_origin_micros: Option<i64>,
+        time_extent_micros: Option<i64>,
+        class_id: Option<&str>,
+    ) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        if let Some(time_origin_micros) = time_origin_micros {
+            params.insert(
+                "timeOriginMicros".to_string(),
+                serde_json::to_value(time_origin_micros).unwrap(),
+            );
+        }
+        if let Some(time_extent_micros) = time_extent_micros {
+            params.insert(
+                "timeExtentMicros".to_string(),
+                serde_json::to_value(time_extent_micros).unwrap(),
+            );
+        }
+        if let Some(class_id) = class_id {
+            params.insert(
+                "classId".to_string(),
+                serde_json::to_value(class_id).unwrap(),
+            );
+        }
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "getAllocationTraces",
+            params,
+        }
+    }
+
+    /// The _getClassList_ RPC is used to retrieve a _ClassList_ containing all
+    /// classes for an isolate based on the isolate's _isolateId_.
+    ///
+    /// Responds with `ClassList|Sentinel`.
+    pub fn get_class_list(&self, isolate_id: &str) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "getClassList",
+            params,
+        }
+    }
+
+    /// The _getCpuSamples_ RPC is used to retrieve samples collected by the CPU
+    /// profiler. See [CpuSamples](#cpusamples) for a detailed description of the
+    /// response.
+    ///
+    /// Responds with `CpuSamples|Sentinel`.
+    pub fn get_cpu_samples(
+        &self,
+        isolate_id: &str,
+        time_origin_micros: i64,
+        time_extent_micros: i64,
+    ) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        params.insert(
+            "timeOriginMicros".to_string(),
+            serde_json::to_value(time_origin_micros).unwrap(),
+        );
+        params.insert(
+            "timeExtentMicros".to_string(),
+            serde_json::to_value(time_extent_micros).unwrap(),
+        );
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "getCpuSamples",
+            params,
+        }
+    }
+
+    /// The _getFlagList_ RPC returns a list of all command line flags in the VM
+    /// along with their current values.
+    ///
+    /// Responds with `FlagList`.
+    pub fn get_flag_list(&self) -> Request {
+        let params = serde_json::Map::new();
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "getFlagList",
+            params,
+        }
+    }
+
+    /// Returns a set of inbound references to the object specified by _targetId_.
+    /// Up to _limit_ references will be returned.
+    ///
+    /// Responds with `InboundReferences|Sentinel`.
+    pub fn get_inbound_references(&self, isolate_id: &str, target_id: &str, limit: i64) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        params.insert(
+            "targetId".to_string(),
+            serde_json::to_value(target_id).unwrap(),
+        );
+        params.insert("limit".to_string(), serde_json::to_value(limit).unwrap());
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "getInboundReferences",
+            params,
+        }
+    }
+
+    /// The _getInstances_ RPC is used to retrieve a set of instances which are of
+    /// a specific class.
+    ///
+    /// Responds with `InstanceSet|Sentinel`.
+    pub fn get_instances(
+        &self,
+        isolate_id: &str,
+        object_id: &str,
+        limit: i64,
+        include_subclasses: Option<bool>,
+        include_implementers: Option<bool>,
+    ) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        params.insert(
+            "objectId".to_string(),
+            serde_json::to_value(object_id).unwrap(),
+        );
+        params.insert("limit".to_string(), serde_json::to_value(limit).unwrap());
+        if let Some(include_subclasses) = include_subclasses {
+            params.insert(
+                "includeSubclasses".to_string(),
+                serde_json::to_value(include_subclasses).unwrap(),
+            );
+        }
+        if let Some(include_implementers) = include_implementers {
+            params.insert(
+                "includeImplementers".to_string(),
+                serde_json::to_value(include_implementers).unwrap(),
+            );
+        }
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "getInstances",
+            params,
+        }
+    }
+
+    /// The _getInstancesAsList_ RPC is used to retrieve a set of instances which
+    /// are of a specific class. This RPC returns an `@Instance` corresponding to
+    /// a Dart `List<dynamic>` that contains the requested instances.
+    ///
+    /// Responds with `@Instance|Sentinel`.
+    pub fn get_instances_as_list(
+        &self,
+        isolate_id: &str,
+        object_id: &str,
+        include_subclasses: Option<bool>,
+        include_implementers: Option<bool>,
+    ) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        params.insert(
+            "objectId".to_string(),
+            serde_json::to_value(object_id).unwrap(),
+        );
+        if let Some(include_subclasses) = include_subclasses {
+            params.insert(
+                "includeSubclasses".to_string(),
+                serde_json::to_value(include_subclasses).unwrap(),
+            );
+        }
+        if let Some(include_implementers) = include_implementers {
+            params.insert(
+                "includeImplementers".to_string(),
+                serde_json::to_value(include_implementers).unwrap(),
+            );
+        }
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "getInstancesAsList",
+            params,
+        }
+    }
+
+    /// The _getIsolate_ RPC is used to lookup an _Isolate_ object by its _id_.
+    ///
+    /// Responds with `Isolate|Sentinel`.
+    pub fn get_isolate(&self, isolate_id: &str) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "getIsolate",
+            params,
+        }
+    }
+
+    /// The _getIsolateGroup_ RPC is used to lookup an _IsolateGroup_ object by
+    /// its _id_.
+    ///
+    /// Responds with `IsolateGroup|Sentinel`.
+    pub fn get_isolate_group(&self, isolate_group_id: &str) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateGroupId".to_string(),
+            serde_json::to_value(isolate_group_id).unwrap(),
+        );
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "getIsolateGroup",
+            params,
+        }
+    }
+
+    /// The _getIsolateGroupMemoryUsage_ RPC is used to lookup an isolate group's
+    /// memory usage statistics by its _id_.
+    ///
+    /// Responds with `MemoryUsage|Sentinel`.
+    pub fn get_isolate_group_memory_usage(&self, isolate_group_id: &str) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateGroupId".to_string(),
+            serde_json::to_value(isolate_group_id).unwrap(),
+        );
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "getIsolateGroupMemoryUsage",
+            params,
+        }
+    }
+
+    /// The _getMemoryUsage_ RPC is used to lookup an isolate's memory usage
+    /// statistics by its _id_.
+    ///
+    /// Responds with `MemoryUsage|Sentinel`.
+    pub fn get_memory_usage(&self, isolate_id: &str) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "getMemoryUsage",
+            params,
+        }
+    }
+
+    /// The _getObject_ RPC is used to lookup an _object_ from some isolate by its
+    /// _id_.
+    ///
+    /// Responds with `Object|Sentinel`.
+    pub fn get_object(
+        &self,
+        isolate_id: &str,
+        object_id: &str,
+        offset: Option<i64>,
+        count: Option<i64>,
+    ) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        params.insert(
+            "objectId".to_string(),
+            serde_json::to_value(object_id).unwrap(),
+        );
+        if let Some(offset) = offset {
+            params.insert("offset".to_string(), serde_json::to_value(offset).unwrap());
+        }
+        if let Some(count) = count {
+            params.insert("count".to_string(), serde_json::to_value(count).unwrap());
+        }
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "getObject",
+            params,
+        }
+    }
+
+    /// The _getPorts_ RPC is used to retrieve the list of `ReceivePort` instances
+    /// for a given isolate.
+    ///
+    /// Responds with `PortList`.
+    pub fn get_ports(&self, isolate_id: &str) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "getPorts",
+            params,
+        }
+    }
+
+    /// Returns a description of major uses of memory known to the VM.
+    ///
+    /// Responds with `ProcessMemoryUsage`.
+    pub fn get_process_memory_usage(&self) -> Request {
+        let params = serde_json::Map::new();
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "getProcessMemoryUsage",
+            params,
+        }
+    }
+
+    /// The _getRetainingPath_ RPC is used to lookup a path from an object
+    /// specified by _targetId_ to a GC root (i.e., the object which is preventing
+    /// this object from being garbage collected).
+    ///
+    /// Responds with `RetainingPath|Sentinel`.
+    pub fn get_retaining_path(&self, isolate_id: &str, target_id: &str, limit: i64) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        params.insert(
+            "targetId".to_string(),
+            serde_json::to_value(target_id).unwrap(),
+        );
+        params.insert("limit".to_string(), serde_json::to_value(limit).unwrap());
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "getRetainingPath",
+            params,
+        }
+    }
+
+    /// The _getScripts_ RPC is used to retrieve a _ScriptList_ containing all
+    /// scripts for an isolate based on the isolate's _isolateId_.
+    ///
+    /// Responds with `ScriptList|Sentinel`.
+    pub fn get_scripts(&self, isolate_id: &str) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "getScripts",
+            params,
+        }
+    }
+
+    /// The _getSourceReport_ RPC is used to generate a set of reports tied to
+    /// source locations in an isolate.
+    ///
+    /// Responds with `SourceReport|Sentinel`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_source_report(
+        &self,
+        isolate_id: &str,
+        reports: &[SourceReportKind],
+        script_id: Option<&str>,
+        token_pos: Option<i64>,
+        end_token_pos: Option<i64>,
+        force_compile: Option<bool>,
+        report_lines: Option<bool>,
+    ) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        params.insert(
+            "reports".to_string(),
+            serde_json::to_value(reports).unwrap(),
+        );
+        if let Some(script_id) = script_id {
+            params.insert(
+                "scriptId".to_string(),
+                serde_json::to_value(script_id).unwrap(),
+            );
+        }
+        if let Some(token_pos) = token_pos {
+            params.insert(
+                "tokenPos".to_string(),
+                serde_json::to_value(token_pos).unwrap(),
+            );
+        }
+        if let Some(end_token_pos) = end_token_pos {
+            params.insert(
+                "endTokenPos".to_string(),
+                serde_json::to_value(end_token_pos).unwrap(),
+            );
+        }
+        if let Some(force_compile) = force_compile {
+            params.insert(
+                "forceCompile".to_string(),
+                serde_json::to_value(force_compile).unwrap(),
+            );
+        }
+        if let Some(report_lines) = report_lines {
+            params.insert(
+                "reportLines".to_string(),
+                serde_json::to_value(report_lines).unwrap(),
+            );
+        }
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "getSourceReport",
+            params,
+        }
+    }
+
+    /// The _getStack_ RPC is used to retrieve the current execution stack and
+    /// message queue for an isolate. The isolate does not need to be paused.
+    ///
+    /// Responds with `Stack|Sentinel`.
+    pub fn get_stack(&self, isolate_id: &str, limit: Option<i64>) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        if let Some(limit) = limit {
+            params.insert("limit".to_string(), serde_json::to_value(limit).unwrap());
+        }
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "getStack",
+            params,
+        }
+    }
+
+    /// The _getSupportedProtocols_ RPC is used to determine which protocols are
+    /// supported by the current server.
+    ///
+    /// Responds with `ProtocolList`.
+    pub fn get_supported_protocols(&self) -> Request {
+        let params = serde_json::Map::new();
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "getSupportedProtocols",
+            params,
+        }
+    }
+
+    /// The _getVersion_ RPC is used to determine what version of the Service
+    /// Protocol is served by a VM.
+    ///
+    /// Responds with `Version`.
+    pub fn get_version(&self) -> Request {
+        let params = serde_json::Map::new();
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "getVersion",
+            params,
+        }
+    }
+
+    /// The _getVM_ RPC returns global information about a Dart virtual machine.
+    ///
+    /// Responds with `VM`.
+    pub fn get_vm(&self) -> Request {
+        let params = serde_json::Map::new();
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "getVM",
+            params,
+        }
+    }
+
+    /// The _getVMTimeline_ RPC is used to retrieve an object which contains VM
+    /// timeline events.
+    ///
+    /// Responds with `Timeline`.
+    pub fn get_vm_timeline(
+        &self,
+        time_origin_micros: Option<i64>,
+        time_extent_micros: Option<i64>,
+    ) -> Request {
+        let mut params = serde_json::Map::new();
+        if let Some(time_origin_micros) = time_origin_micros {
+            params.insert(
+                "timeOriginMicros".to_string(),
+                serde_json::to_value(time_origin_micros).unwrap(),
+            );
+        }
+        if let Some(time_extent_micros) = time_extent_micros {
+            params.insert(
+                "timeExtentMicros".to_string(),
+                serde_json::to_value(time_extent_micros).unwrap(),
+            );
+        }
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "getVMTimeline",
+            params,
+        }
+    }
+
+    /// The _getVMTimelineFlags_ RPC returns information about the current VM
+    /// timeline configuration.
+    ///
+    /// Responds with `TimelineFlags`.
+    pub fn get_vm_timeline_flags(&self) -> Request {
+        let params = serde_json::Map::new();
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "getVMTimelineFlags",
+            params,
+        }
+    }
+
+    /// The _getVMTimelineMicros_ RPC returns the current time stamp from the
+    /// clock used by the timeline, similar to
+    /// [Timeline.now](https://api.dart.dev/stable/dart-developer/Timeline/now.html)
+    /// in `dart:developer` and
+    /// [Dart_TimelineGetMicros](https://github.com/dart-lang/sdk/blob/master/runtime/include/dart_tools_api.h)
+    /// in the VM embedding API.
+    ///
+    /// Responds with `Timestamp`.
+    pub fn get_vm_timeline_micros(&self) -> Request {
+        let params = serde_json::Map::new();
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "getVMTimelineMicros",
+            params,
+        }
+    }
+
+    /// The _invoke_ RPC is used to perform regular method invocation on some
+    /// receiver, as if by dart:mirror's ObjectMirror.invoke. Note this does not
+    /// provide a way to perform getter, setter or constructor invocation.
+    ///
+    /// Responds with `@Instance|@Error|Sentinel`.
+    pub fn invoke(
+        &self,
+        isolate_id: &str,
+        target_id: &str,
+        selector: &str,
+        argument_ids: &[&str],
+        disable_breakpoints: Option<bool>,
+    ) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        params.insert(
+            "targetId".to_string(),
+            serde_json::to_value(target_id).unwrap(),
+        );
+        params.insert(
+            "selector".to_string(),
+            serde_json::to_value(selector).unwrap(),
+        );
+        params.insert(
+            "argumentIds".to_string(),
+            serde_json::to_value(argument_ids).unwrap(),
+        );
+        if let Some(disable_breakpoints) = disable_breakpoints {
+            params.insert(
+                "disableBreakpoints".to_string(),
+                serde_json::to_value(disable_breakpoints).unwrap(),
+            );
+        }
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "invoke",
+            params,
+        }
+    }
+
+    /// The _kill_ RPC is used to kill an isolate as if by dart:isolate's
+    /// `Isolate.kill(IMMEDIATE)`.
+    ///
+    /// Responds with `Success|Sentinel`.
+    pub fn kill(&self, isolate_id: &str) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "kill",
+            params,
+        }
+    }
+
+    /// The _lookupPackageUris_ RPC is used to convert a list of URIs to their
+    /// unresolved paths, such as `package:` URIs for the files of a package.
+    ///
+    /// Responds with `UriList`.
+    pub fn lookup_package_uris(&self, isolate_id: &str, uris: &[&str]) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        params.insert("uris".to_string(), serde_json::to_value(uris).unwrap());
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "lookupPackageUris",
+            params,
+        }
+    }
+
+    /// The _lookupResolvedPackageUris_ RPC is used to convert a list of URIs to
+    /// their resolved (or absolute) paths, such as `file:` URIs for `package:`
+    /// URIs.
+    ///
+    /// Responds with `UriList`.
+    pub fn lookup_resolved_package_uris(
+        &self,
+        isolate_id: &str,
+        uris: &[&str],
+        local: Option<bool>,
+    ) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        params.insert("uris".to_string(), serde_json::to_value(uris).unwrap());
+        if let Some(local) = local {
+            params.insert("local".to_string(), serde_json::to_value(local).unwrap());
+        }
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "lookupResolvedPackageUris",
+            params,
+        }
+    }
+
+    /// The _pause_ RPC is used to interrupt a running isolate. The RPC enqueues
+    /// the interrupt request and potentially returns before the isolate is
+    /// paused.
+    ///
+    /// Responds with `Success|Sentinel`.
+    pub fn pause(&self, isolate_id: &str) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "pause",
+            params,
+        }
+    }
+
+    /// Registers a service that can be invoked by other VM service clients, where
+    /// `service` is the name of the service to advertise and `alias` is an
+    /// alternative name for the registered service.
+    ///
+    /// Responds with `Success`.
+    pub fn register_service(&self, service: &str, alias: &str) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "service".to_string(),
+            serde_json::to_value(service).unwrap(),
+        );
+        params.insert("alias".to_string(), serde_json::to_value(alias).unwrap());
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "registerService",
+            params,
+        }
+    }
+
+    /// The _reloadSources_ RPC is used to perform a hot reload of an isolate's
+    /// sources.
+    ///
+    /// Responds with `ReloadReport|Sentinel`.
+    pub fn reload_sources(
+        &self,
+        isolate_id: &str,
+        force: Option<bool>,
+        pause: Option<bool>,
+        root_lib_uri: Option<&str>,
+        packages_uri: Option<&str>,
+    ) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        if let Some(force) = force {
+            params.insert("force".to_string(), serde_json::to_value(force).unwrap());
+        }
+        if let Some(pause) = pause {
+            params.insert("pause".to_string(), serde_json::to_value(pause).unwrap());
+        }
+        if let Some(root_lib_uri) = root_lib_uri {
+            params.insert(
+                "rootLibUri".to_string(),
+                serde_json::to_value(root_lib_uri).unwrap(),
+            );
+        }
+        if let Some(packages_uri) = packages_uri {
+            params.insert(
+                "packagesUri".to_string(),
+                serde_json::to_value(packages_uri).unwrap(),
+            );
+        }
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "reloadSources",
+            params,
+        }
+    }
+
+    /// The _removeBreakpoint_ RPC is used to remove a breakpoint by its _id_.
+    ///
+    /// Responds with `Success|Sentinel`.
+    pub fn remove_breakpoint(&self, isolate_id: &str, breakpoint_id: &str) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        params.insert(
+            "breakpointId".to_string(),
+            serde_json::to_value(breakpoint_id).unwrap(),
+        );
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "removeBreakpoint",
+            params,
+        }
+    }
+
+    /// Requests a dump of the Dart heap of the given isolate.
+    ///
+    /// Responds with `Success|Sentinel`.
+    pub fn request_heap_snapshot(&self, isolate_id: &str) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "requestHeapSnapshot",
+            params,
+        }
+    }
+
+    /// The _resume_ RPC is used to resume execution of a paused isolate.
+    ///
+    /// Responds with `Success|Sentinel`.
+    pub fn resume(
+        &self,
+        isolate_id: &str,
+        step: Option<StepOption>,
+        frame_index: Option<i64>,
+    ) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        if let Some(step) = step {
+            params.insert("step".to_string(), serde_json::to_value(step).unwrap());
+        }
+        if let Some(frame_index) = frame_index {
+            params.insert(
+                "frameIndex".to_string(),
+                serde_json::to_value(frame_index).unwrap(),
+            );
+        }
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "resume",
+            params,
+        }
+    }
+
+    /// The _setBreakpointState_ RPC allows for breakpoints to be enabled or
+    /// disabled, without requiring for the breakpoint to be completely removed.
+    ///
+    /// Responds with `Breakpoint`.
+    pub fn set_breakpoint_state(
+        &self,
+        isolate_id: &str,
+        breakpoint_id: &str,
+        enable: bool,
+    ) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        params.insert(
+            "breakpointId".to_string(),
+            serde_json::to_value(breakpoint_id).unwrap(),
+        );
+        params.insert("enable".to_string(), serde_json::to_value(enable).unwrap());
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "setBreakpointState",
+            params,
+        }
+    }
+
+    /// The _setExceptionPauseMode_ RPC is used to control if an isolate pauses
+    /// when an exception is thrown.
+    ///
+    /// Responds with `Success`.
+    pub fn set_exception_pause_mode(&self, isolate_id: &str, mode: ExceptionPauseMode) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        params.insert("mode".to_string(), serde_json::to_value(mode).unwrap());
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "setExceptionPauseMode",
+            params,
+        }
+    }
+
+    /// The _setFlag_ RPC is used to set a VM flag at runtime. Returns an error if
+    /// the named flag does not exist, the flag may not be set at runtime, or the
+    /// value is of the wrong type for the flag.
+    ///
+    /// Responds with `Success|Error`.
+    pub fn set_flag(&self, name: &str, value: &str) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert("name".to_string(), serde_json::to_value(name).unwrap());
+        params.insert("value".to_string(), serde_json::to_value(value).unwrap());
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "setFlag",
+            params,
+        }
+    }
+
+    /// The _setIsolatePauseMode_ RPC is used to control if or when an isolate
+    /// will pause due to a change in execution state.
+    ///
+    /// Responds with `Success`.
+    pub fn set_isolate_pause_mode(
+        &self,
+        isolate_id: &str,
+        exception_pause_mode: Option<ExceptionPauseMode>,
+        should_pause_on_exit: Option<bool>,
+    ) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        if let Some(exception_pause_mode) = exception_pause_mode {
+            params.insert(
+                "exceptionPauseMode".to_string(),
+                serde_json::to_value(exception_pause_mode).unwrap(),
+            );
+        }
+        if let Some(should_pause_on_exit) = should_pause_on_exit {
+            params.insert(
+                "shouldPauseOnExit".to_string(),
+                serde_json::to_value(should_pause_on_exit).unwrap(),
+            );
+        }
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "setIsolatePauseMode",
+            params,
+        }
+    }
+
+    /// The _setLibraryDebuggable_ RPC is used to enable or disable whether
+    /// breakpoints and stepping work for a given library.
+    ///
+    /// Responds with `Success|Sentinel`.
+    pub fn set_library_debuggable(
+        &self,
+        isolate_id: &str,
+        library_id: &str,
+        is_debuggable: bool,
+    ) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        params.insert(
+            "libraryId".to_string(),
+            serde_json::to_value(library_id).unwrap(),
+        );
+        params.insert(
+            "isDebuggable".to_string(),
+            serde_json::to_value(is_debuggable).unwrap(),
+        );
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "setLibraryDebuggable",
+            params,
+        }
+    }
+
+    /// The _setName_ RPC is used to change the debugging name for an isolate.
+    ///
+    /// Responds with `Success|Sentinel`.
+    pub fn set_name(&self, isolate_id: &str, name: &str) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        params.insert("name".to_string(), serde_json::to_value(name).unwrap());
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "setName",
+            params,
+        }
+    }
+
+    /// The _setTraceClassAllocation_ RPC allows for enabling or disabling
+    /// allocation tracing for a specific type of object. Allocation traces can be
+    /// retrieved with the _getAllocationTraces_ RPC.
+    ///
+    /// Responds with `Success|Sentinel`.
+    pub fn set_trace_class_allocation(
+        &self,
+        isolate_id: &str,
+        class_id: &str,
+        enable: bool,
+    ) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "isolateId".to_string(),
+            serde_json::to_value(isolate_id).unwrap(),
+        );
+        params.insert(
+            "classId".to_string(),
+            serde_json::to_value(class_id).unwrap(),
+        );
+        params.insert("enable".to_string(), serde_json::to_value(enable).unwrap());
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "setTraceClassAllocation",
+            params,
+        }
+    }
+
+    /// The _setVMName_ RPC is used to change the debugging name for the vm.
+    ///
+    /// Responds with `Success`.
+    pub fn set_vm_name(&self, name: &str) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert("name".to_string(), serde_json::to_value(name).unwrap());
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "setVMName",
+            params,
+        }
+    }
+
+    /// The _setVMTimelineFlags_ RPC is used to set which timeline streams are
+    /// enabled.
+    ///
+    /// Responds with `Success`.
+    pub fn set_vm_timeline_flags(&self, recorded_streams: &[&str]) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "recordedStreams".to_string(),
+            serde_json::to_value(recorded_streams).unwrap(),
+        );
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "setVMTimelineFlags",
+            params,
+        }
+    }
+
+    /// The _streamCancel_ RPC cancels a stream subscription in the VM.
+    ///
+    /// Responds with `Success`.
+    pub fn stream_cancel(&self, stream_id: &str) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "streamId".to_string(),
+            serde_json::to_value(stream_id).unwrap(),
+        );
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "streamCancel",
+            params,
+        }
+    }
+
+    /// The _streamCpuSamplesWithUserTag_ RPC allows for clients to specify which
+    /// CPU samples collected by the profiler should be sent over the `Profiler`
+    /// stream. When called, the VM will stream `CpuSamples` events containing
+    /// `CpuSample`'s collected while a user tag contained in `userTags` was
+    /// active.
+    ///
+    /// Responds with `Success`.
+    pub fn stream_cpu_samples_with_user_tag(&self, user_tags: &[&str]) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "userTags".to_string(),
+            serde_json::to_value(user_tags).unwrap(),
+        );
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "streamCpuSamplesWithUserTag",
+            params,
+        }
+    }
+
+    /// The _streamListen_ RPC subscribes to a stream in the VM. Once
+    /// subscribed, the client will begin receiving events from the stream.
+    ///
+    /// Responds with `Success`.
+    pub fn stream_listen(&self, stream_id: &str) -> Request {
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "streamId".to_string(),
+            serde_json::to_value(stream_id).unwrap(),
+        );
+        Request {
+            jsonrpc: "2.0",
+            id: self.next_id(),
+            method: "streamListen",
+            params,
+        }
+    }
+
+    pub fn next_id(&self) -> i32 {
+        self.id.fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+    }
+}
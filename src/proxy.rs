@@ -0,0 +1,371 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{mpsc, Arc, Mutex};
+
+use websocket::sync::Server;
+use websocket::OwnedMessage;
+
+use super::vmservice::*;
+
+/// Error code for cancelling a stream the client doesn't listen to.
+const STREAM_NOT_SUBSCRIBED: i64 = 104;
+
+/// Error code for registering a service which another client provides.
+const SERVICE_ALREADY_REGISTERED: i64 = 111;
+
+/// Error code for calling a service whose client has gone away.
+const SERVICE_DISAPPEARED: i64 = 112;
+
+/// Error code for requests with missing or malformed params.
+const INVALID_PARAMS: i64 = -32602;
+
+type ClientSender = Arc<Mutex<websocket::sender::Writer<std::net::TcpStream>>>;
+
+/// Serves the vm service to other clients, such as DevTools or an IDE, over
+/// the single connection this tool holds.
+///
+/// Requests are forwarded with ids from this connection and answered with the
+/// client's own, so clients can't collide with each other or with the tool.
+/// Each stream is subscribed to once and its events are passed to every
+/// client listening to it. Services registered by a client stay registered
+/// with the vm service when it disconnects, and are taken over by the next
+/// client to register them. Devfs uploads are not proxied.
+pub struct Proxy {
+    vm_service: VMService,
+    methods: VMServiceMethods,
+    state: Arc<Mutex<ProxyState>>,
+}
+
+#[derive(Default)]
+struct ProxyState {
+    clients: HashMap<usize, ClientSender>,
+
+    /// Clients listening to each stream, by stream id.
+    listeners: HashMap<String, Vec<usize>>,
+
+    /// Streams the proxy has subscribed to on the vm service.
+    subscribed: HashSet<String>,
+
+    /// The client providing each service registered through the proxy.
+    services: HashMap<String, usize>,
+
+    /// Calls to a client's service awaiting its response, by the id they were
+    /// sent to the client with.
+    pending: HashMap<String, (usize, mpsc::Sender<serde_json::Value>)>,
+
+    next_client: usize,
+    next_request: usize,
+}
+
+impl Proxy {
+    /// Listen for clients on `port`, or on any free port if it is 0, and
+    /// return the address they connect to.
+    pub fn start(vm_service: &VMService, methods: &VMServiceMethods, port: u16) -> Result<String, ()> {
+        let server = Server::bind(("127.0.0.1", port))
+            .map_err(|err| println!("Failed to serve the vm service on port {}: {}", port, err))?;
+        let address = match server.local_addr() {
+            Ok(address) => format!("ws://{}/ws", address),
+            Err(err) => {
+                println!("Failed to serve the vm service: {}", err);
+                return Err(());
+            }
+        };
+        let proxy = Arc::new(Proxy {
+            vm_service: vm_service.clone(),
+            methods: methods.clone(),
+            state: Arc::new(Mutex::new(ProxyState::default())),
+        });
+        std::thread::spawn(move || {
+            for request in server.filter_map(Result::ok) {
+                let proxy = Arc::clone(&proxy);
+                std::thread::spawn(move || {
+                    if let Ok(client) = request.accept() {
+                        proxy.serve(client);
+                    }
+                });
+            }
+        });
+        Ok(address)
+    }
+
+    /// Handle a client's messages until it disconnects.
+    fn serve(self: &Arc<Self>, client: websocket::sync::Client<std::net::TcpStream>) {
+        let (mut receiver, sender) = match client.split() {
+            Ok(split) => split,
+            Err(_) => return,
+        };
+        let sender = Arc::new(Mutex::new(sender));
+        let client_id = {
+            let mut state = self.state.lock().unwrap();
+            let client_id = state.next_client;
+            state.next_client += 1;
+            state.clients.insert(client_id, Arc::clone(&sender));
+            client_id
+        };
+        for message in receiver.incoming_messages() {
+            let text = match message {
+                Ok(OwnedMessage::Text(text)) => text,
+                Ok(OwnedMessage::Ping(data)) => {
+                    let _ = sender.lock().unwrap().send_message(&OwnedMessage::Pong(data));
+                    continue;
+                }
+                Ok(OwnedMessage::Close(_)) | Err(_) => break,
+                Ok(_) => continue,
+            };
+            let message: serde_json::Value = match serde_json::from_str(&text) {
+                Ok(message) => message,
+                Err(_) => continue,
+            };
+            match (message["method"].as_str(), message.get("id")) {
+                (Some(_), Some(_)) => {
+                    // Requests may take a while, such as getCpuSamples, and
+                    // shouldn't hold up the client's other requests.
+                    let proxy = Arc::clone(self);
+                    let sender = Arc::clone(&sender);
+                    std::thread::spawn(move || {
+                        let response = match proxy.handle_request(client_id, &message) {
+                            Ok(result) => serde_json::json!({
+                                "jsonrpc": "2.0",
+                                "id": message["id"],
+                                "result": result,
+                            }),
+                            Err(error) => serde_json::json!({
+                                "jsonrpc": "2.0",
+                                "id": message["id"],
+                                "error": error,
+                            }),
+                        };
+                        let _ = sender
+                            .lock()
+                            .unwrap()
+                            .send_message(&OwnedMessage::Text(response.to_string()));
+                    });
+                }
+                (None, Some(id)) => {
+                    // The response to a call of one of the client's services.
+                    let pending = self.state.lock().unwrap().pending.remove(&id.to_string());
+                    if let Some((_, waiter)) = pending {
+                        let _ = waiter.send(message);
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.disconnect(client_id);
+    }
+
+    /// Forget a client, failing any calls waiting on its services.
+    fn disconnect(&self, client_id: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.clients.remove(&client_id);
+        for listeners in state.listeners.values_mut() {
+            listeners.retain(|x| *x != client_id);
+        }
+        state.pending.retain(|_, (owner, _)| *owner != client_id);
+    }
+
+    fn handle_request(
+        self: &Arc<Self>,
+        client_id: usize,
+        request: &serde_json::Value,
+    ) -> Result<serde_json::Value, serde_json::Value> {
+        let method = request["method"].as_str().unwrap_or_default();
+        let params = request["params"].as_object().cloned().unwrap_or_default();
+        match method {
+            "streamListen" => self.stream_listen(client_id, string_param(&params, "streamId")?),
+            "streamCancel" => {
+                let stream_id = string_param(&params, "streamId")?;
+                let mut state = self.state.lock().unwrap();
+                let listeners = state.listeners.entry(stream_id.to_string()).or_default();
+                if !listeners.contains(&client_id) {
+                    return Err(error(STREAM_NOT_SUBSCRIBED, "Stream not subscribed"));
+                }
+                // The proxy stays subscribed, since the tool or another client
+                // may listen again.
+                listeners.retain(|x| *x != client_id);
+                Ok(serde_json::json!({"type": "Success"}))
+            }
+            "registerService" => self.register_service(
+                client_id,
+                string_param(&params, "service")?,
+                string_param(&params, "alias")?,
+            ),
+            _ => self
+                .vm_service
+                .clone()
+                .try_call_method(&self.methods.clone().forward(method, params)),
+        }
+    }
+
+    fn stream_listen(
+        self: &Arc<Self>,
+        client_id: usize,
+        stream_id: &str,
+    ) -> Result<serde_json::Value, serde_json::Value> {
+        let first = {
+            let mut state = self.state.lock().unwrap();
+            let listeners = state.listeners.entry(stream_id.to_string()).or_default();
+            if listeners.contains(&client_id) {
+                return Err(error(STREAM_ALREADY_SUBSCRIBED, "Stream already subscribed"));
+            }
+            listeners.push(client_id);
+            state.subscribed.insert(stream_id.to_string())
+        };
+        if first {
+            let (sender, receiver) = mpsc::channel();
+            let subscribed = self
+                .vm_service
+                .clone()
+                .listen_raw(&mut self.methods.clone(), stream_id, sender);
+            if subscribed.is_err() {
+                let mut state = self.state.lock().unwrap();
+                state.subscribed.remove(stream_id);
+                state.listeners.remove(stream_id);
+                return Err(error(INVALID_PARAMS, &format!("Failed to listen to {}", stream_id)));
+            }
+            let state = Arc::clone(&self.state);
+            std::thread::spawn(move || {
+                for notification in receiver {
+                    broadcast(&state, &notification);
+                }
+            });
+        }
+        Ok(serde_json::json!({"type": "Success"}))
+    }
+
+    /// Register a client's service with the vm service, which sends calls to
+    /// it to the proxy to pass on.
+    fn register_service(
+        self: &Arc<Self>,
+        client_id: usize,
+        service: &str,
+        alias: &str,
+    ) -> Result<serde_json::Value, serde_json::Value> {
+        {
+            let mut state = self.state.lock().unwrap();
+            if let Some(owner) = state.services.get(service).cloned() {
+                // Take over a service left behind by a client which is gone.
+                if state.clients.contains_key(&owner) {
+                    return Err(error(SERVICE_ALREADY_REGISTERED, "Service already registered"));
+                }
+                state.services.insert(service.to_string(), client_id);
+                return Ok(serde_json::json!({"type": "Success"}));
+            }
+        }
+        // Services of the tool itself, such as compileExpression.
+        if self.vm_service.has_handler(service) {
+            return Err(error(SERVICE_ALREADY_REGISTERED, "Service already registered"));
+        }
+        let proxy = Arc::clone(self);
+        let name = service.to_string();
+        self.vm_service.clone().register_handler(
+            service,
+            Arc::new(move |params| proxy.call_service(&name, params)),
+        );
+        let result = self
+            .vm_service
+            .clone()
+            .try_call_method(&self.methods.clone().register_service(service, alias))?;
+        self.state
+            .lock()
+            .unwrap()
+            .services
+            .insert(service.to_string(), client_id);
+        Ok(result)
+    }
+
+    /// Pass a call from the vm service on to the client providing `service`,
+    /// and wait for its response.
+    fn call_service(
+        &self,
+        service: &str,
+        params: &serde_json::Value,
+    ) -> Result<serde_json::Value, serde_json::Value> {
+        let (waiter, response) = mpsc::channel();
+        let (sender, request) = {
+            let mut state = self.state.lock().unwrap();
+            let owner = state.services.get(service).cloned();
+            let (owner, sender) = match owner.and_then(|x| state.clients.get(&x).map(|y| (x, y.clone()))) {
+                Some(client) => client,
+                None => return Err(error(SERVICE_DISAPPEARED, "Service has disappeared")),
+            };
+            let id = format!("proxy-{}", state.next_request);
+            state.next_request += 1;
+            // Keyed the way the response's id is looked up.
+            let key = serde_json::Value::from(id.clone()).to_string();
+            state.pending.insert(key, (owner, waiter));
+            let request = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": service,
+                "params": params,
+            });
+            (sender, request)
+        };
+        let sent = sender
+            .lock()
+            .unwrap()
+            .send_message(&OwnedMessage::Text(request.to_string()));
+        if sent.is_err() {
+            return Err(error(SERVICE_DISAPPEARED, "Service has disappeared"));
+        }
+        // The sender is dropped if the client disconnects first.
+        let response = response
+            .recv()
+            .map_err(|_| error(SERVICE_DISAPPEARED, "Service has disappeared"))?;
+        match response.get("error") {
+            Some(err) => Err(err.clone()),
+            None => Ok(response["result"].clone()),
+        }
+    }
+}
+
+/// Send a stream event to every client listening to its stream, as a binary
+/// frame if it carries data.
+fn broadcast(state: &Arc<Mutex<ProxyState>>, notification: &RawNotification) {
+    let metadata = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "streamNotify",
+        "params": notification.params,
+    })
+    .to_string();
+    let message = match notification.data.as_ref() {
+        Some(data) => {
+            let offset = 4 + metadata.len() as u32;
+            let mut frame = offset.to_le_bytes().to_vec();
+            frame.extend_from_slice(metadata.as_bytes());
+            frame.extend_from_slice(data);
+            OwnedMessage::Binary(frame)
+        }
+        None => OwnedMessage::Text(metadata),
+    };
+    let senders: Vec<ClientSender> = {
+        let state = state.lock().unwrap();
+        state
+            .listeners
+            .get(&notification.stream_id)
+            .map(|listeners| {
+                listeners
+                    .iter()
+                    .filter_map(|x| state.clients.get(x).cloned())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    for sender in senders {
+        let _ = sender.lock().unwrap().send_message(&message);
+    }
+}
+
+fn string_param<'a>(
+    params: &'a serde_json::Map<String, serde_json::Value>,
+    name: &str,
+) -> Result<&'a str, serde_json::Value> {
+    params
+        .get(name)
+        .and_then(|x| x.as_str())
+        .ok_or_else(|| error(INVALID_PARAMS, &format!("Missing parameter {}", name)))
+}
+
+fn error(code: i64, message: &str) -> serde_json::Value {
+    serde_json::json!({"code": code, "message": message})
+}
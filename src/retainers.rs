@@ -0,0 +1,171 @@
+use super::debugger::{describe, describe_error};
+use super::explorer::is_object_id;
+use super::vmservice::*;
+
+/// Number of instances of a class whose retaining paths are found.
+const INSTANCE_LIMIT: i64 = 5;
+
+/// The longest retaining path requested.
+const PATH_LIMIT: i64 = 100;
+
+/// An object and the path of references keeping it alive.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Retained {
+    pub object: serde_json::Value,
+
+    #[serde(rename = "retainingPath")]
+    pub retaining_path: RetainingPath,
+}
+
+/// Find the retaining paths of `target`, an object id or the name of a class
+/// whose live instances are looked up.
+///
+/// Garbage is collected first, so only instances which are really retained
+/// are found, such as `State` objects leaked across a hot reload.
+pub fn find_retainers(
+    vm_service: &mut VMService,
+    methods: &mut VMServiceMethods,
+    isolate_id: &str,
+    target: &str,
+) -> Result<Vec<Retained>, ()> {
+    let objects = if is_object_id(target) {
+        let object: serde_json::Value = vm_service
+            .try_call_method(&methods.get_object(isolate_id, target))
+            .map_err(|err| println!("{}", describe_error(&err)))?;
+        vec![object]
+    } else {
+        instances_of(vm_service, methods, isolate_id, target)?
+    };
+    let mut retained = Vec::new();
+    for object in objects {
+        let object_id = match object["id"].as_str() {
+            Some(id) => id.to_string(),
+            None => continue,
+        };
+        let path = vm_service
+            .try_call_method(&methods.get_retaining_path(isolate_id, &object_id, PATH_LIMIT));
+        match path {
+            Ok(path) => retained.push(Retained {
+                object: object,
+                retaining_path: path,
+            }),
+            Err(err) => println!("{}: {}", object_id, describe_error(&err)),
+        }
+    }
+    Ok(retained)
+}
+
+/// Up to [INSTANCE_LIMIT] live instances of each class named `class_name`.
+fn instances_of(
+    vm_service: &mut VMService,
+    methods: &mut VMServiceMethods,
+    isolate_id: &str,
+    class_name: &str,
+) -> Result<Vec<serde_json::Value>, ()> {
+    let profile: AllocationProfile =
+        vm_service.call_method(&methods.get_allocation_profile(isolate_id, true, false))?;
+    // Several libraries may declare a class with the same name.
+    let classes: Vec<&ClassHeapStats> = profile
+        .members
+        .iter()
+        .filter(|x| x.class.name == class_name)
+        .collect();
+    if classes.is_empty() {
+        println!("No class named {} has been allocated.", class_name);
+        return Err(());
+    }
+    let mut instances = Vec::new();
+    for stats in classes.iter() {
+        if stats.instances_current == 0 {
+            continue;
+        }
+        let set: InstanceSet = vm_service
+            .try_call_method(&methods.get_instances(isolate_id, &stats.class.id, INSTANCE_LIMIT))
+            .map_err(|err| println!("{}", describe_error(&err)))?;
+        println!(
+            "{} live instances of {} ({}), showing {}.",
+            set.total_count,
+            class_name,
+            stats.class.id,
+            set.instances.len()
+        );
+        instances.extend(set.instances);
+    }
+    if instances.is_empty() {
+        println!("No instances of {} are alive.", class_name);
+    }
+    Ok(instances)
+}
+
+/// Print each object followed by the objects holding it, out to the GC root.
+pub fn print_retainers(retained: &[Retained]) {
+    for entry in retained.iter() {
+        let elements = &entry.retaining_path.elements;
+        println!(
+            "{}  ({})",
+            describe(&entry.object),
+            entry.object["id"].as_str().unwrap_or_default()
+        );
+        // Each element says where the next one along holds it.
+        for (held, holder) in elements.iter().zip(elements.iter().skip(1)) {
+            println!("  {} in {}", slot(held), describe(&holder.value));
+        }
+        let root = &entry.retaining_path.gc_root_type;
+        if !root.is_empty() {
+            println!("  held by the {} root", root);
+        } else if (elements.len() as i64) < entry.retaining_path.length {
+            println!("  ...");
+        }
+    }
+}
+
+/// Write the retaining paths as JSON.
+pub fn write_retainers(retained: &[Retained], path: &str) -> Result<(), ()> {
+    let file = std::fs::File::create(path)
+        .map_err(|err| println!("Failed to write {}: {}", path, err))?;
+    serde_json::to_writer_pretty(file, retained)
+        .map_err(|err| println!("Failed to write {}: {}", path, err))?;
+    println!("Wrote {}", path);
+    Ok(())
+}
+
+/// Where an object on a retaining path is held by the next one.
+fn slot(object: &RetainingObject) -> String {
+    match (
+        object.parent_field.as_ref(),
+        object.parent_list_index,
+        object.parent_map_key.as_ref(),
+    ) {
+        (Some(field), _, _) => match field.as_str().or_else(|| field["name"].as_str()) {
+            Some(name) => format!(".{}", name),
+            None => format!(".{}", field),
+        },
+        (None, Some(index), _) => format!("[{}]", index),
+        (None, None, Some(key)) => format!("[{}]", describe(key)),
+        (None, None, None) => String::from("<reference>"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(parent: serde_json::Value) -> RetainingObject {
+        let mut object = serde_json::json!({"value": {"type": "@Instance"}});
+        object.as_object_mut().unwrap().extend(parent.as_object().unwrap().clone());
+        serde_json::from_value(object).unwrap()
+    }
+
+    #[test]
+    fn slots_name_the_reference() {
+        assert_eq!(slot(&object(serde_json::json!({"parentField": "_items"}))), "._items");
+        let field = serde_json::json!({"parentField": {"type": "@Field", "name": "child"}});
+        assert_eq!(slot(&object(field)), ".child");
+        assert_eq!(slot(&object(serde_json::json!({"parentListIndex": 3}))), "[3]");
+        let key = serde_json::json!({
+            "parentMapKey": {"type": "@Instance", "kind": "String", "valueAsString": "home"},
+        });
+        assert_eq!(slot(&object(key)), "[\"home\"]");
+        assert_eq!(slot(&object(serde_json::json!({}))), "<reference>");
+    }
+}
@@ -0,0 +1,71 @@
+use super::vmservice::*;
+
+/// The per-view screenshot extension, preferred when the isolate has it.
+const VIEW_SCREENSHOT_EXTENSION: &'static str = "ext.flutter.screenshot";
+
+/// The first bytes of every PNG file.
+const PNG_SIGNATURE: &'static [u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Capture a PNG of `view`.
+///
+/// Only the view itself is captured when its isolate registered the
+/// per-view extension, otherwise the whole screen is.
+pub fn capture(
+    vm_service: &mut VMService,
+    methods: &mut VMServiceMethods,
+    view: &FlutterView,
+) -> Result<Vec<u8>, ()> {
+    let per_view = vm_service
+        .isolate(&view.isolate.id)
+        .map_or(false, |x| x.extension_rpcs.iter().any(|x| x == VIEW_SCREENSHOT_EXTENSION));
+    let screenshot: Screenshot = if per_view {
+        let response = vm_service.call_extension(
+            methods,
+            VIEW_SCREENSHOT_EXTENSION,
+            &view.isolate.id,
+            &[("viewId", &view.id)],
+        )?;
+        serde_json::from_value(response)
+            .map_err(|err| println!("Unexpected screenshot response: {}", err))?
+    } else {
+        vm_service
+            .try_call_method(&methods.screenshot())
+            .map_err(|err| println!("Screenshot failed: {}", err))?
+    };
+    let png = base64::decode(&screenshot.screenshot)
+        .map_err(|err| println!("Invalid screenshot data: {}", err))?;
+    if !png.starts_with(PNG_SIGNATURE) {
+        println!("The screenshot of {} is not a PNG.", view.id);
+        return Err(());
+    }
+    Ok(png)
+}
+
+/// Capture every view and write each to `dir`, named after the view and the
+/// time it was taken.
+pub fn write_screenshots(
+    vm_service: &mut VMService,
+    methods: &mut VMServiceMethods,
+    flutter_views: &FlutterViewList,
+    dir: &std::path::Path,
+) -> Result<(), ()> {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|x| x.as_millis())
+        .unwrap_or(0);
+    for view in flutter_views.views.iter() {
+        let png = capture(vm_service, methods, view)?;
+        // View ids look like _flutterView/0x7f8, which can't be a file name.
+        let name: String = view
+            .id
+            .chars()
+            .map(|x| if x.is_ascii_alphanumeric() { x } else { '-' })
+            .collect();
+        let path = dir.join(format!("screenshot-{}-{}.png", name.trim_matches('-'), timestamp));
+        std::fs::create_dir_all(dir)
+            .and_then(|_| std::fs::write(&path, &png))
+            .map_err(|err| println!("Failed to write {}: {}", path.display(), err))?;
+        println!("Wrote {}", path.display());
+    }
+    Ok(())
+}
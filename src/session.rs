@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use super::debugger::*;
+use super::devfs::*;
+use super::explorer::*;
+use super::frames::*;
+use super::inspector::*;
+use super::memory::*;
+use super::timeline::*;
+use super::vmservice::*;
+
+/// Name of the devfs the project is uploaded to.
+pub const DEVFS_NAME: &'static str = "flutter_gallery";
+
+/// Number of files uploaded to the devfs at once.
+pub const SYNC_CONCURRENCY: usize = 4;
+
+/// How long to wait for the framework to register its reassemble extension.
+const REASSEMBLE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The devfs path the incremental dill is written to.
+const INCREMENTAL_DILL: &'static str = "lib/main.dart.incremental.dill";
+
+/// A connected app and everything the session keeps for it.
+pub struct Target {
+    /// The vm service address, which identifies the target in reports.
+    pub address: String,
+
+    pub vm_service: VMService,
+    pub methods: VMServiceMethods,
+    pub devfs: DevFS,
+
+    /// The root uri of the devfs on the device.
+    pub devfs_uri: String,
+
+    pub frame_monitor: FrameMonitor,
+    pub timeline: TimelineRecorder,
+    pub inspector: Inspector,
+    pub explorer: Explorer,
+
+    /// The last memory snapshot of each isolate, by isolate id.
+    pub memory_snapshots: HashMap<String, MemorySnapshot>,
+}
+
+/// The outcome of reloading one target.
+#[derive(Debug)]
+pub struct ReloadResult {
+    pub address: String,
+
+    /// Number of views reloaded.
+    pub views: usize,
+
+    /// Whether every view accepted the new code.
+    pub success: bool,
+
+    /// Why the reload failed before reloadSources was called.
+    pub error: Option<String>,
+
+    /// Compressed bytes of the incremental dill and other changed files.
+    pub bytes_sent: usize,
+
+    pub elapsed: Duration,
+}
+
+impl Target {
+    /// Upload `dill` and any other changed files, then reload and reassemble
+    /// every view.
+    ///
+    /// Isolates paused at start or exit are dealt with first, since they
+    /// can't be reloaded.
+    pub fn reload(&mut self, dill: &std::path::Path, breakpoints: &[String]) -> ReloadResult {
+        let start = Instant::now();
+        let mut result = ReloadResult {
+            address: self.address.clone(),
+            views: 0,
+            success: false,
+            error: None,
+            bytes_sent: 0,
+            elapsed: Duration::from_secs(0),
+        };
+        if let Err(error) = self.reload_views(dill, breakpoints, &mut result) {
+            result.error = Some(error);
+        }
+        result.elapsed = start.elapsed();
+        result
+    }
+
+    fn reload_views(
+        &mut self,
+        dill: &std::path::Path,
+        breakpoints: &[String],
+        result: &mut ReloadResult,
+    ) -> Result<(), String> {
+        resume_paused_isolates(&mut self.vm_service, &mut self.methods, breakpoints)
+            .map_err(|_| "failed to resume paused isolates".to_string())?;
+        let flutter_views = current_views(&mut self.vm_service, &mut self.methods)
+            .map_err(|_| "failed to list views".to_string())?;
+
+        let phase = Instant::now();
+        // The dill goes up alongside any other files which changed, such as
+        // assets.
+        self.devfs
+            .add_file_as(dill, INCREMENTAL_DILL)
+            .map_err(|_| format!("{} is missing", dill.display()))?;
+        let summary = self
+            .devfs
+            .sync(self.vm_service.uploader(), DEVFS_NAME, SYNC_CONCURRENCY, false);
+        result.bytes_sent += summary.bytes_sent;
+        if !summary.failed.is_empty() {
+            return Err(format!("failed to upload {} files", summary.failed.len()));
+        }
+        self.timeline.record("upload", phase);
+
+        result.success = true;
+        for view in flutter_views.views.iter() {
+            let phase = Instant::now();
+            let report: ReloadReport = self
+                .vm_service
+                .call_method(&self.methods.reload_sources(
+                    &view.isolate.id,
+                    false,
+                    &format!("{}{}", self.devfs_uri, INCREMENTAL_DILL),
+                    &format!("{}.packages", self.devfs_uri),
+                ))
+                .map_err(|_| format!("reloadSources failed for {}", view.isolate.name))?;
+            self.timeline.record("reloadSources", phase);
+            result.views += 1;
+            result.success &= report.success;
+            if !report.success {
+                continue;
+            }
+            // After a restart the framework may not have registered its
+            // extensions yet.
+            if !self.vm_service.wait_for_extension(
+                &view.isolate.id,
+                "ext.flutter.reassemble",
+                REASSEMBLE_TIMEOUT,
+            ) {
+                println!("{} cannot reassemble yet, skipping.", view.isolate.name);
+                continue;
+            }
+            let phase = Instant::now();
+            self.vm_service
+                .send_notification(&self.methods.reassemble(&view.isolate.id))
+                .map_err(|_| format!("reassemble failed for {}", view.isolate.name))?;
+            self.timeline.record("reassemble", phase);
+        }
+        Ok(())
+    }
+}
+
+/// Reload every target on its own thread.
+///
+/// The targets are handed back in their original order along with their
+/// results. A target whose reload panicked is dropped, and reported as a
+/// failed row.
+pub fn reload_all(
+    targets: Vec<Target>,
+    dill: &std::path::Path,
+    breakpoints: &[String],
+) -> (Vec<Target>, Vec<ReloadResult>) {
+    let handles: Vec<_> = targets
+        .into_iter()
+        .map(|mut target| {
+            let address = target.address.clone();
+            let dill = dill.to_path_buf();
+            let breakpoints = breakpoints.to_vec();
+            let handle = std::thread::spawn(move || {
+                let result = target.reload(&dill, &breakpoints);
+                (target, result)
+            });
+            (address, handle)
+        })
+        .collect();
+    let start = Instant::now();
+    let mut targets = Vec::new();
+    let mut results = Vec::new();
+    for (address, handle) in handles {
+        match handle.join() {
+            Ok((target, result)) => {
+                targets.push(target);
+                results.push(result);
+            }
+            Err(_) => results.push(ReloadResult {
+                address: address,
+                views: 0,
+                success: false,
+                error: Some("the reload panicked, disconnected".to_string()),
+                bytes_sent: 0,
+                elapsed: start.elapsed(),
+            }),
+        }
+    }
+    (targets, results)
+}
+
+/// Print a row for each target's reload.
+pub fn print_results(results: &[ReloadResult]) {
+    let width = results.iter().map(|x| x.address.len()).max().unwrap_or(0);
+    for result in results.iter() {
+        let status = match (result.error.as_ref(), result.success) {
+            (Some(error), _) => format!("failed: {}", error),
+            (None, true) => format!("reloaded {} views", result.views),
+            (None, false) => "rejected".to_string(),
+        };
+        println!(
+            "{:<width$}  {:>8}  {:>7}ms  {}",
+            result.address,
+            format_bytes(result.bytes_sent as i64),
+            result.elapsed.as_millis(),
+            status,
+            width = width
+        );
+    }
+}
+
+/// The flutter views whose isolates are currently runnable.
+///
+/// Isolate ids change when the app restarts, so views are listed again for
+/// every command rather than once at startup.
+pub fn current_views(
+    vm_service: &mut VMService,
+    methods: &mut VMServiceMethods,
+) -> Result<FlutterViewList, ()> {
+    let flutter_views: FlutterViewList = vm_service.call_method(&methods.list_views())?;
+    let views = flutter_views
+        .views
+        .into_iter()
+        .filter(|view| vm_service.isolate(&view.isolate.id).map_or(false, |x| x.runnable))
+        .collect();
+    Ok(FlutterViewList { views: views })
+}
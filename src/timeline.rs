@@ -0,0 +1,181 @@
+use std::time::Instant;
+
+use super::vmservice::*;
+
+/// Streams recorded when none are given.
+pub const DEFAULT_STREAMS: &[&str] = &["Dart", "Embedder", "GC"];
+
+/// Category of the events recorded by the tool itself.
+const TOOL_CATEGORY: &str = "flutter_tools";
+
+/// A phase of the tool's own work, such as a compile or an upload.
+struct Span {
+    name: String,
+    start: Instant,
+    end: Instant,
+}
+
+/// Records the vm timeline along with spans of the tool's own work, and
+/// writes both as a single Chrome trace.
+pub struct TimelineRecorder {
+    /// A local instant and the vm timeline clock at that instant, taken when
+    /// recording starts so tool spans can be placed on the vm's clock.
+    anchor: Option<(Instant, i64)>,
+
+    recording: bool,
+
+    spans: Vec<Span>,
+}
+
+impl TimelineRecorder {
+    pub fn new() -> Self {
+        TimelineRecorder {
+            anchor: None,
+            recording: false,
+            spans: Vec::new(),
+        }
+    }
+
+    /// Clear the vm timeline and start recording `streams`.
+    pub fn start(
+        &mut self,
+        vm_service: &mut VMService,
+        methods: &mut VMServiceMethods,
+        streams: &[&str],
+    ) -> Result<(), ()> {
+        let _: serde_json::Value = vm_service.call_method(&methods.set_vm_timeline_flags(streams))?;
+        let _: serde_json::Value = vm_service.call_method(&methods.clear_vm_timeline())?;
+        // The vm read its clock somewhere during the round trip, so assume
+        // the midpoint.
+        let before = Instant::now();
+        let now: Timestamp = vm_service.call_method(&methods.get_vm_timeline_micros())?;
+        let after = Instant::now();
+        self.anchor = Some((before + (after - before) / 2, now.timestamp));
+        self.recording = true;
+        self.spans.clear();
+        Ok(())
+    }
+
+    /// Stop recording, keeping the recorded events for a later dump.
+    pub fn stop(&mut self, vm_service: &mut VMService, methods: &mut VMServiceMethods) -> Result<(), ()> {
+        let _: serde_json::Value = vm_service.call_method(&methods.set_vm_timeline_flags(&[]))?;
+        self.recording = false;
+        Ok(())
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// Record a span of the tool's work from `start` until now. Spans are
+    /// dropped when the timeline is not recording.
+    pub fn record(&mut self, name: &str, start: Instant) {
+        if !self.recording {
+            return;
+        }
+        self.spans.push(Span {
+            name: name.to_string(),
+            start: start,
+            end: Instant::now(),
+        });
+    }
+
+    /// Write the vm timeline to `path` as a Chrome trace, which can be opened
+    /// in chrome://tracing or Perfetto. With `tool_spans` the tool's own
+    /// spans are merged in.
+    pub fn dump(
+        &self,
+        vm_service: &mut VMService,
+        methods: &mut VMServiceMethods,
+        path: &std::path::Path,
+        tool_spans: bool,
+    ) -> Result<usize, ()> {
+        let timeline: Timeline = vm_service.call_method(&methods.get_vm_timeline())?;
+        let mut events = timeline.trace_events;
+        if tool_spans {
+            events.extend(self.span_events());
+        }
+        let count = events.len();
+        let file = std::fs::File::create(path).map_err(|_| ())?;
+        serde_json::to_writer(
+            file,
+            &serde_json::json!({
+                "traceEvents": events,
+                "displayTimeUnit": "ms",
+            }),
+        )
+        .map_err(|_| ())?;
+        Ok(count)
+    }
+
+    /// The tool's spans as complete events on the vm's clock, in a process
+    /// of their own.
+    fn span_events(&self) -> Vec<serde_json::Value> {
+        let (anchor, anchor_micros) = match self.anchor {
+            Some(anchor) => anchor,
+            None => return Vec::new(),
+        };
+        let pid = std::process::id();
+        let mut events = vec![serde_json::json!({
+            "name": "process_name",
+            "ph": "M",
+            "pid": pid,
+            "args": {"name": TOOL_CATEGORY},
+        })];
+        let micros = |instant: Instant| {
+            if instant >= anchor {
+                anchor_micros + (instant - anchor).as_micros() as i64
+            } else {
+                anchor_micros - (anchor - instant).as_micros() as i64
+            }
+        };
+        for span in self.spans.iter() {
+            events.push(serde_json::json!({
+                "name": span.name,
+                "cat": TOOL_CATEGORY,
+                "ph": "X",
+                "ts": micros(span.start),
+                "dur": (span.end - span.start).as_micros() as i64,
+                "pid": pid,
+                "tid": 0,
+            }));
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn spans_are_placed_on_the_vm_clock() {
+        let anchor = Instant::now();
+        let mut recorder = TimelineRecorder::new();
+        assert!(recorder.span_events().is_empty());
+
+        recorder.anchor = Some((anchor, 1_000_000));
+        recorder.spans.push(Span {
+            name: String::from("compile"),
+            start: anchor + Duration::from_millis(5),
+            end: anchor + Duration::from_millis(7),
+        });
+        let events = recorder.span_events();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["ph"], "M");
+        assert_eq!(events[1]["name"], "compile");
+        assert_eq!(events[1]["ts"], 1_005_000);
+        assert_eq!(events[1]["dur"], 2_000);
+    }
+
+    #[test]
+    fn spans_are_only_recorded_while_recording() {
+        let mut recorder = TimelineRecorder::new();
+        recorder.record("upload", Instant::now());
+        assert!(recorder.spans.is_empty());
+        recorder.recording = true;
+        recorder.record("upload", Instant::now());
+        assert_eq!(recorder.spans.len(), 1);
+    }
+}
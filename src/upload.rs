@@ -0,0 +1,224 @@
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{self, Read, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::vmservice::ConnectOptions;
+
+/// Attempts made before an upload is given up on.
+const MAX_ATTEMPTS: u32 = 4;
+
+/// Delay before the first retry, doubled for each retry after.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(250);
+
+/// Size of the chunks read from the source file.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The outcome of a successful upload.
+#[derive(Debug)]
+pub struct UploadReport {
+    /// Size of the file before compression.
+    pub bytes_read: usize,
+
+    /// Compressed bytes sent in the successful attempt.
+    pub bytes_sent: usize,
+
+    pub attempts: u32,
+}
+
+/// Uploads files to a devfs over http, reusing one connection pool.
+///
+/// Clones share the pool.
+#[derive(Clone)]
+pub struct Uploader {
+    client: reqwest::Client,
+    address: String,
+}
+
+impl Uploader {
+    /// Create an uploader for the devfs http endpoint at `address`, with the
+    /// same certificates and headers as the vm service connection.
+    pub fn new(address: &str, options: &ConnectOptions) -> Result<Self, ()> {
+        let mut builder = reqwest::Client::builder();
+        for pem in options.ca_certificates.iter() {
+            let certificate = reqwest::Certificate::from_pem(pem)
+                .map_err(|err| println!("Invalid CA certificate: {}", err))?;
+            builder = builder.add_root_certificate(certificate);
+        }
+        let mut headers = reqwest::header::HeaderMap::new();
+        for (name, value) in options.headers.iter() {
+            let name = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| println!("Invalid header name: {}", name))?;
+            let value = reqwest::header::HeaderValue::from_str(value)
+                .map_err(|_| println!("Invalid value for header {}", name))?;
+            headers.insert(name, value);
+        }
+        let client = builder
+            .danger_accept_invalid_certs(options.accept_invalid_certs)
+            .default_headers(headers)
+            .build()
+            .map_err(|err| println!("Failed to create the upload client: {}", err))?;
+        Ok(Uploader {
+            client: client,
+            address: address.to_string(),
+        })
+    }
+
+    /// Upload the file at `path` to `device_uri` in the devfs `fs_name`.
+    ///
+    /// The file is compressed while it is sent, so it is never held in memory
+    /// as a whole. Connection failures and server errors are retried with
+    /// exponential backoff; other error responses fail immediately.
+    pub fn upload(
+        &self,
+        path: &std::path::Path,
+        device_uri: &str,
+        fs_name: &str,
+    ) -> Result<UploadReport, ()> {
+        let dev_fs_uri = base64::encode(device_uri.as_bytes());
+        let mut backoff = INITIAL_BACKOFF;
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let file = std::fs::File::open(path).map_err(|err| {
+                println!("Failed to open {}: {}", path.display(), err);
+            })?;
+            let body = GzipReader::new(file);
+            let (bytes_read, bytes_sent) = (body.bytes_read.clone(), body.bytes_sent.clone());
+            let result = self
+                .client
+                .put(&self.address)
+                .header("dev_fs_name", fs_name)
+                .header("dev_fs_uri_b64", dev_fs_uri.as_str())
+                .header("user-agent", "Dart/2.1 (dart:io)")
+                .body(reqwest::Body::new(body))
+                .send();
+            let retry = match result {
+                Ok(ref response) if response.status().is_success() => {
+                    return Ok(UploadReport {
+                        bytes_read: bytes_read.load(Ordering::SeqCst),
+                        bytes_sent: bytes_sent.load(Ordering::SeqCst),
+                        attempts: attempts,
+                    });
+                }
+                Ok(ref response) => {
+                    let status = response.status();
+                    println!("Uploading {} failed: {}", device_uri, status);
+                    status.is_server_error() || status.as_u16() == 429
+                }
+                Err(ref err) => {
+                    println!("Uploading {} failed: {}", device_uri, err);
+                    true
+                }
+            };
+            if !retry || attempts >= MAX_ATTEMPTS {
+                return Err(());
+            }
+            std::thread::sleep(backoff);
+            backoff *= 2;
+        }
+    }
+}
+
+/// Compresses a reader with gzip as it is read.
+struct GzipReader<R> {
+    source: R,
+    encoder: Option<GzEncoder<Vec<u8>>>,
+
+    /// Compressed output not yet read.
+    pending: Vec<u8>,
+    offset: usize,
+
+    bytes_read: Arc<AtomicUsize>,
+    bytes_sent: Arc<AtomicUsize>,
+}
+
+impl<R: Read> GzipReader<R> {
+    fn new(source: R) -> Self {
+        GzipReader {
+            source: source,
+            encoder: Some(GzEncoder::new(Vec::new(), Compression::default())),
+            pending: Vec::new(),
+            offset: 0,
+            bytes_read: Arc::new(AtomicUsize::new(0)),
+            bytes_sent: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Compress the next chunk of the source into `pending`. Returns false
+    /// once the source and the encoder are exhausted.
+    fn fill(&mut self) -> io::Result<bool> {
+        let mut encoder = match self.encoder.take() {
+            Some(encoder) => encoder,
+            None => return Ok(false),
+        };
+        let mut chunk = vec![0; CHUNK_SIZE];
+        let read = self.source.read(&mut chunk)?;
+        self.bytes_read.fetch_add(read, Ordering::SeqCst);
+        self.offset = 0;
+        if read == 0 {
+            self.pending = encoder.finish()?;
+        } else {
+            encoder.write_all(&chunk[..read])?;
+            self.pending = std::mem::replace(encoder.get_mut(), Vec::new());
+            self.encoder = Some(encoder);
+        }
+        Ok(true)
+    }
+}
+
+impl<R: Read> Read for GzipReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // The encoder only emits output once a block fills, so keep feeding
+        // it until there is something to return.
+        while self.offset == self.pending.len() {
+            if !self.fill()? {
+                return Ok(0);
+            }
+        }
+        let count = buf.len().min(self.pending.len() - self.offset);
+        buf[..count].copy_from_slice(&self.pending[self.offset..self.offset + count]);
+        self.offset += count;
+        self.bytes_sent.fetch_add(count, Ordering::SeqCst);
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gzip_reader_round_trips() {
+        // Larger than a chunk, so the encoder is fed several times.
+        let source: Vec<u8> = b"flutter "
+            .iter()
+            .cycle()
+            .take(CHUNK_SIZE * 2 + 17)
+            .cloned()
+            .collect();
+        let mut reader = GzipReader::new(&source[..]);
+        let bytes_read = Arc::clone(&reader.bytes_read);
+        let bytes_sent = Arc::clone(&reader.bytes_sent);
+        // Read in small pieces to exercise partial reads of `pending`.
+        let mut compressed = Vec::new();
+        let mut buf = [0; 100];
+        loop {
+            let count = reader.read(&mut buf).unwrap();
+            if count == 0 {
+                break;
+            }
+            compressed.extend_from_slice(&buf[..count]);
+        }
+        assert_eq!(bytes_read.load(Ordering::SeqCst), source.len());
+        assert_eq!(bytes_sent.load(Ordering::SeqCst), compressed.len());
+
+        let mut decompressed = Vec::new();
+        flate2::read::GzDecoder::new(&compressed[..])
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, source);
+    }
+}
@@ -1,26 +1,353 @@
-use libflate::gzip::Encoder;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync;
 use std::sync::mpsc;
 
+use futures::{Future, Stream};
 use websocket;
 
+pub use super::protocol::*;
+use super::protocol::{Request, Requests};
+use super::upload::*;
+
+/// The oldest protocol version this client can talk to.
+pub const MIN_PROTOCOL_VERSION: (i64, i64) = (3, 0);
+
+/// The newest major protocol version this client understands.
+pub const MAX_PROTOCOL_MAJOR_VERSION: i64 = 3;
+
+/// The first protocol version with a public reloadSources.
+const PUBLIC_RELOAD_SOURCES_VERSION: (i64, i64) = (3, 0);
+
+/// The first protocol version with a public registerService.
+const PUBLIC_REGISTER_SERVICE_VERSION: (i64, i64) = (3, 22);
+
+/// The first protocol version with getIsolateGroup.
+const ISOLATE_GROUPS_VERSION: (i64, i64) = (3, 38);
+
+/// The first protocol version with setIsolatePauseMode.
+const ISOLATE_PAUSE_MODE_VERSION: (i64, i64) = (3, 53);
+
+/// The largest file written to a devfs over the websocket.
+pub const MAX_DEVFS_WRITE_BYTES: usize = 64 * 1024;
+
+/// Builds requests with ids unique to a connection.
+///
+/// Public methods are built by the [Requests] generated from the protocol
+/// specification, which also hands out the ids. Clones share the id
+/// counter, so they can be used from other threads with clones of the same
+/// [VMService].
+#[derive(Clone)]
 pub struct VMServiceMethods {
-    id: i32,
+    requests: Requests,
+
+    /// The protocol version of the vm service, once known. Until then the
+    /// private methods older vms understand are used.
+    version: Option<Version>,
 }
 
 impl VMServiceMethods {
     pub fn new() -> Self {
-        VMServiceMethods { id: 0 }
+        VMServiceMethods {
+            requests: Requests::new(),
+            version: None,
+        }
+    }
+
+    pub fn set_version(&mut self, version: Version) {
+        self.version = Some(version);
+    }
+
+    pub fn version(&self) -> Option<&Version> {
+        self.version.as_ref()
+    }
+
+    /// Whether the vm service implements protocol `major.minor` or later.
+    pub fn supports(&self, major: i64, minor: i64) -> bool {
+        match self.version.as_ref() {
+            Some(version) => (version.major, version.minor) >= (major, minor),
+            None => false,
+        }
+    }
+
+    pub fn get_version<'a>(&mut self) -> VMServiceMethod<'a> {
+        self.requests.get_version().into()
+    }
+
+    /// Returns None if the vm service does not support isolate groups.
+    pub fn get_isolate_group<'a>(
+        &mut self,
+        isolate_group_id: &'a str,
+    ) -> Option<VMServiceMethod<'a>> {
+        if !self.supports(ISOLATE_GROUPS_VERSION.0, ISOLATE_GROUPS_VERSION.1) {
+            return None;
+        }
+        Some(self.requests.get_isolate_group(isolate_group_id).into())
+    }
+
+    pub fn get_vm<'a>(&mut self) -> VMServiceMethod<'a> {
+        self.requests.get_vm().into()
+    }
+
+    pub fn get_isolate<'a>(&mut self, isolate_id: &'a str) -> VMServiceMethod<'a> {
+        self.requests.get_isolate(isolate_id).into()
+    }
+
+    pub fn get_scripts<'a>(&mut self, isolate_id: &'a str) -> VMServiceMethod<'a> {
+        self.requests.get_scripts(isolate_id).into()
+    }
+
+    pub fn get_object<'a>(
+        &mut self,
+        isolate_id: &'a str,
+        object_id: &'a str,
+    ) -> VMServiceMethod<'a> {
+        self.requests
+            .get_object(isolate_id, object_id, None, None)
+            .into()
+    }
+
+    /// Request `count` of the elements or associations of a list or map,
+    /// starting from `offset`.
+    pub fn get_object_range<'a>(
+        &mut self,
+        isolate_id: &'a str,
+        object_id: &'a str,
+        offset: i64,
+        count: i64,
+    ) -> VMServiceMethod<'a> {
+        self.requests
+            .get_object(isolate_id, object_id, Some(offset), Some(count))
+            .into()
+    }
+
+    /// Request up to `limit` of the live instances of a class.
+    pub fn get_instances<'a>(
+        &mut self,
+        isolate_id: &'a str,
+        class_id: &'a str,
+        limit: i64,
+    ) -> VMServiceMethod<'a> {
+        self.requests
+            .get_instances(isolate_id, class_id, limit, None, None)
+            .into()
+    }
+
+    /// Request the path from a GC root to `target_id`, up to `limit` objects
+    /// long.
+    pub fn get_retaining_path<'a>(
+        &mut self,
+        isolate_id: &'a str,
+        target_id: &'a str,
+        limit: i64,
+    ) -> VMServiceMethod<'a> {
+        self.requests
+            .get_retaining_path(isolate_id, target_id, limit)
+            .into()
+    }
+
+    /// Request up to `limit` of the objects which refer to `target_id`.
+    pub fn get_inbound_references<'a>(
+        &mut self,
+        isolate_id: &'a str,
+        target_id: &'a str,
+        limit: i64,
+    ) -> VMServiceMethod<'a> {
+        self.requests
+            .get_inbound_references(isolate_id, target_id, limit)
+            .into()
+    }
+
+    /// Request a source report for a script, such as `Coverage`.
+    ///
+    /// With `force_compile` functions which have not run are compiled, so
+    /// they are reported as misses rather than left out.
+    pub fn get_source_report<'a>(
+        &mut self,
+        isolate_id: &'a str,
+        reports: &'a [SourceReportKind],
+        script_id: &'a str,
+        force_compile: bool,
+    ) -> VMServiceMethod<'a> {
+        self.requests
+            .get_source_report(
+                isolate_id,
+                reports,
+                Some(script_id),
+                None,
+                None,
+                Some(force_compile),
+                None,
+            )
+            .into()
+    }
+
+    pub fn stream_listen<'a>(&mut self, stream_id: &'a str) -> VMServiceMethod<'a> {
+        self.requests.stream_listen(stream_id).into()
+    }
+
+    pub fn add_breakpoint<'a>(
+        &mut self,
+        isolate_id: &'a str,
+        script_id: &'a str,
+        line: i64,
+    ) -> VMServiceMethod<'a> {
+        self.requests
+            .add_breakpoint(isolate_id, script_id, line, None)
+            .into()
+    }
+
+    pub fn add_breakpoint_with_script_uri<'a>(
+        &mut self,
+        isolate_id: &'a str,
+        script_uri: &'a str,
+        line: i64,
+    ) -> VMServiceMethod<'a> {
+        self.requests
+            .add_breakpoint_with_script_uri(isolate_id, script_uri, line, None)
+            .into()
+    }
+
+    pub fn remove_breakpoint<'a>(
+        &mut self,
+        isolate_id: &'a str,
+        breakpoint_id: &'a str,
+    ) -> VMServiceMethod<'a> {
+        self.requests
+            .remove_breakpoint(isolate_id, breakpoint_id)
+            .into()
+    }
+
+    pub fn pause<'a>(&mut self, isolate_id: &'a str) -> VMServiceMethod<'a> {
+        self.requests.pause(isolate_id).into()
+    }
+
+    /// Resume an isolate, optionally stepping.
+    ///
+    /// `frame_index` is only used with [StepOption::Rewind].
+    pub fn resume<'a>(
+        &mut self,
+        isolate_id: &'a str,
+        step: Option<StepOption>,
+        frame_index: Option<i64>,
+    ) -> VMServiceMethod<'a> {
+        self.requests.resume(isolate_id, step, frame_index).into()
+    }
+
+    pub fn get_stack<'a>(&mut self, isolate_id: &'a str) -> VMServiceMethod<'a> {
+        self.requests.get_stack(isolate_id, None).into()
+    }
+
+    /// Set when an isolate pauses on exceptions, using setIsolatePauseMode
+    /// where the deprecated setExceptionPauseMode may be gone.
+    pub fn set_exception_pause_mode<'a>(
+        &mut self,
+        isolate_id: &'a str,
+        mode: ExceptionPauseMode,
+    ) -> VMServiceMethod<'a> {
+        if self.supports(ISOLATE_PAUSE_MODE_VERSION.0, ISOLATE_PAUSE_MODE_VERSION.1) {
+            self.requests
+                .set_isolate_pause_mode(isolate_id, Some(mode), None)
+                .into()
+        } else {
+            self.requests
+                .set_exception_pause_mode(isolate_id, mode)
+                .into()
+        }
+    }
+
+    pub fn evaluate<'a>(
+        &mut self,
+        isolate_id: &'a str,
+        target_id: &'a str,
+        expression: &'a str,
+    ) -> VMServiceMethod<'a> {
+        self.requests
+            .evaluate(isolate_id, target_id, expression, None, None)
+            .into()
+    }
+
+    /// Call the method `selector` of an instance, with arguments given by
+    /// object id.
+    pub fn invoke<'a>(
+        &mut self,
+        isolate_id: &'a str,
+        target_id: &'a str,
+        selector: &'a str,
+        argument_ids: &'a [&'a str],
+    ) -> VMServiceMethod<'a> {
+        self.requests
+            .invoke(isolate_id, target_id, selector, argument_ids, None)
+            .into()
+    }
+
+    pub fn evaluate_in_frame<'a>(
+        &mut self,
+        isolate_id: &'a str,
+        frame_index: i64,
+        expression: &'a str,
+    ) -> VMServiceMethod<'a> {
+        self.requests
+            .evaluate_in_frame(isolate_id, frame_index, expression, None, None)
+            .into()
+    }
+
+    pub fn get_memory_usage<'a>(&mut self, isolate_id: &'a str) -> VMServiceMethod<'a> {
+        self.requests.get_memory_usage(isolate_id).into()
+    }
+
+    /// Request an allocation profile, optionally after a full gc and/or
+    /// resetting the accumulated allocation counters.
+    pub fn get_allocation_profile<'a>(
+        &mut self,
+        isolate_id: &'a str,
+        gc: bool,
+        reset: bool,
+    ) -> VMServiceMethod<'a> {
+        self.requests
+            .get_allocation_profile(isolate_id, Some(reset), Some(gc))
+            .into()
+    }
+
+    pub fn clear_cpu_samples<'a>(&mut self, isolate_id: &'a str) -> VMServiceMethod<'a> {
+        self.requests.clear_cpu_samples(isolate_id).into()
+    }
+
+    /// Request the cpu samples collected in a window of the vm's timeline clock.
+    pub fn get_cpu_samples<'a>(
+        &mut self,
+        isolate_id: &'a str,
+        time_origin_micros: i64,
+        time_extent_micros: i64,
+    ) -> VMServiceMethod<'a> {
+        self.requests
+            .get_cpu_samples(isolate_id, time_origin_micros, time_extent_micros)
+            .into()
+    }
+
+    pub fn get_vm_timeline_micros<'a>(&mut self) -> VMServiceMethod<'a> {
+        self.requests.get_vm_timeline_micros().into()
+    }
+
+    /// Request the timeline streams which are available and being recorded.
+    pub fn get_vm_timeline_flags<'a>(&mut self) -> VMServiceMethod<'a> {
+        self.requests.get_vm_timeline_flags().into()
     }
 
-    // pub fn get_version<'a>(&mut self) -> VMServiceMethod<'a> {
-    //     VMServiceMethod::GetVersion { id: self.next_id() }
-    // }
+    /// Set the timeline streams to record, an empty list stops recording.
+    pub fn set_vm_timeline_flags<'a>(
+        &mut self,
+        recorded_streams: &'a [&'a str],
+    ) -> VMServiceMethod<'a> {
+        self.requests.set_vm_timeline_flags(recorded_streams).into()
+    }
+
+    pub fn clear_vm_timeline<'a>(&mut self) -> VMServiceMethod<'a> {
+        self.requests.clear_vm_timeline().into()
+    }
 
-    // pub fn get_vm<'a>(&mut self) -> VMServiceMethod<'a> {
-    //     VMServiceMethod::GetVM { id: self.next_id() }
-    // }
+    pub fn get_vm_timeline<'a>(&mut self) -> VMServiceMethod<'a> {
+        self.requests.get_vm_timeline(None, None).into()
+    }
 
     pub fn reload_sources<'a>(
         &mut self,
@@ -29,7 +356,22 @@ impl VMServiceMethods {
         root_lib_uri: &'a str,
         packages_uri: &'a str,
     ) -> VMServiceMethod<'a> {
-        VMServiceMethod::ReloadSources {
+        if self.supports(
+            PUBLIC_RELOAD_SOURCES_VERSION.0,
+            PUBLIC_RELOAD_SOURCES_VERSION.1,
+        ) {
+            return self
+                .requests
+                .reload_sources(
+                    isolate_id,
+                    None,
+                    Some(pause),
+                    Some(root_lib_uri),
+                    Some(packages_uri),
+                )
+                .into();
+        }
+        VMServiceMethod::PrivateReloadSources {
             id: self.next_id(),
             params: ReloadSourcesParams {
                 isolate_id: isolate_id,
@@ -47,23 +389,51 @@ impl VMServiceMethods {
         }
     }
 
-    // pub fn list_devfs<'a>(&mut self, fs_name: &'a str) -> VMServiceMethod<'a> {
-    //     VMServiceMethod::ListDevFS {
-    //         id: self.next_id(),
-    //         params: ListDevFsParams { fs_name: fs_name },
-    //     }
-    // }
+    /// List the names of the devfs filesystems.
+    pub fn list_devfs<'a>(&mut self) -> VMServiceMethod<'a> {
+        VMServiceMethod::ListDevFS { id: self.next_id() }
+    }
+
+    /// List the files in a devfs with their sizes and modification times.
+    pub fn list_devfs_files<'a>(&mut self, fs_name: &'a str) -> VMServiceMethod<'a> {
+        VMServiceMethod::ListDevFSFiles {
+            id: self.next_id(),
+            params: ListDevFsParams { fs_name: fs_name },
+        }
+    }
+
+    /// Read back a file from a devfs, where `path` is relative to its root.
+    pub fn read_devfs_file<'a>(&mut self, fs_name: &'a str, path: &'a str) -> VMServiceMethod<'a> {
+        VMServiceMethod::ReadDevFSFile {
+            id: self.next_id(),
+            params: DevFSFileParams {
+                fs_name: fs_name,
+                path: path,
+            },
+        }
+    }
 
     pub fn list_views<'a>(&mut self) -> VMServiceMethod<'a> {
         VMServiceMethod::ListViews { id: self.next_id() }
     }
 
+    /// Capture the whole screen of the device as a PNG.
+    pub fn screenshot<'a>(&mut self) -> VMServiceMethod<'a> {
+        VMServiceMethod::Screenshot { id: self.next_id() }
+    }
+
     pub fn register_service<'a>(
         &mut self,
         service: &'a str,
         alias: &'a str,
     ) -> VMServiceMethod<'a> {
-        VMServiceMethod::RegisterService {
+        if self.supports(
+            PUBLIC_REGISTER_SERVICE_VERSION.0,
+            PUBLIC_REGISTER_SERVICE_VERSION.1,
+        ) {
+            return self.requests.register_service(service, alias).into();
+        }
+        VMServiceMethod::PrivateRegisterService {
             id: self.next_id(),
             params: RegisterServiceParams {
                 alias: alias,
@@ -72,24 +442,29 @@ impl VMServiceMethods {
         }
     }
 
-    // DANGER: DO NOT CALL ON DILL FILE.
-    // pub fn write_devfs_file<'a>(
-    //     &mut self,
-    //     fs_name: &'a str,
-    //     uri: &'a str,
-    //     file: &mut std::fs::File,
-    // ) -> VMServiceMethod<'a> {
-    //     let mut buffer = Vec::new();
-    //     file.read_to_end(&mut buffer).unwrap();
-    //     VMServiceMethod::WriteDevFSFile {
-    //         id: self.next_id(),
-    //         params: WriteDevFSParams {
-    //             fs_name: fs_name,
-    //             uri: uri,
-    //             file_contents: base64::encode(&buffer),
-    //         },
-    //     }
-    // }
+    /// Write a small text file to a devfs over the websocket.
+    ///
+    /// The whole file is base64 encoded into a single message, so anything
+    /// larger than [MAX_DEVFS_WRITE_BYTES], and binary files such as dills,
+    /// must go through the [Uploader] instead. Returns `None` for those.
+    pub fn write_devfs_file<'a>(
+        &mut self,
+        fs_name: &'a str,
+        path: &'a str,
+        contents: &str,
+    ) -> Option<VMServiceMethod<'a>> {
+        if contents.len() > MAX_DEVFS_WRITE_BYTES {
+            return None;
+        }
+        Some(VMServiceMethod::WriteDevFSFile {
+            id: self.next_id(),
+            params: WriteDevFSParams {
+                fs_name: fs_name,
+                path: path,
+                file_contents: base64::encode(contents.as_bytes()),
+            },
+        })
+    }
 
     pub fn delete_devfs<'a>(&mut self, fs_name: &'a str) -> VMServiceMethod<'a> {
         VMServiceMethod::DeleteDevFS {
@@ -98,6 +473,40 @@ impl VMServiceMethods {
         }
     }
 
+    /// Call the service extension `method` on an isolate. Extensions take
+    /// string arguments.
+    pub fn service_extension<'a>(
+        &mut self,
+        method: &'a str,
+        isolate_id: &str,
+        args: &[(&str, &str)],
+    ) -> VMServiceMethod<'a> {
+        let mut params = serde_json::Map::new();
+        params.insert("isolateId".to_string(), isolate_id.into());
+        for (name, value) in args.iter() {
+            params.insert(name.to_string(), (*value).into());
+        }
+        VMServiceMethod::Other {
+            id: self.next_id(),
+            name: method,
+            params: params,
+        }
+    }
+
+    /// Call `method` with `params` as given, for requests passed on from
+    /// another client.
+    pub fn forward<'a>(
+        &mut self,
+        method: &'a str,
+        params: serde_json::Map<String, serde_json::Value>,
+    ) -> VMServiceMethod<'a> {
+        VMServiceMethod::Other {
+            id: self.next_id(),
+            name: method,
+            params: params,
+        }
+    }
+
     pub fn reassemble<'a>(&mut self, isolate_id: &'a str) -> VMServiceMethod<'a> {
         VMServiceMethod::Reassemble {
             id: self.next_id(),
@@ -108,9 +517,7 @@ impl VMServiceMethods {
     }
 
     fn next_id(&mut self) -> i32 {
-        let next_id = self.id;
-        self.id += 1;
-        next_id
+        self.requests.next_id()
     }
 }
 
@@ -118,23 +525,20 @@ impl VMServiceMethods {
 #[derive(Debug, Serialize)]
 #[serde(tag = "method")]
 pub enum VMServiceMethod<'a> {
-    #[serde(rename = "getVersion")]
-    GetVersion { id: i32 },
-
-    #[serde(rename = "getVM")]
-    GetVM { id: i32 },
-
     #[serde(rename = "_flutter.listViews")]
     ListViews { id: i32 },
 
+    #[serde(rename = "_flutter.screenshot")]
+    Screenshot { id: i32 },
+
     #[serde(rename = "_reloadSources")]
-    ReloadSources {
+    PrivateReloadSources {
         id: i32,
         params: ReloadSourcesParams<'a>,
     },
 
     #[serde(rename = "_registerService")]
-    RegisterService {
+    PrivateRegisterService {
         id: i32,
         params: RegisterServiceParams<'a>,
     },
@@ -157,30 +561,70 @@ pub enum VMServiceMethod<'a> {
         params: WriteDevFSParams<'a>,
     },
     #[serde(rename = "_listDevFS")]
-    ListDevFS {
+    ListDevFS { id: i32 },
+
+    #[serde(rename = "_listDevFSFiles")]
+    ListDevFSFiles {
         id: i32,
         params: ListDevFsParams<'a>,
     },
 
+    #[serde(rename = "_readDevFSFile")]
+    ReadDevFSFile {
+        id: i32,
+        params: DevFSFileParams<'a>,
+    },
+
     #[serde(rename = "ext.flutter.reassemble")]
     Reassemble {
         id: i32,
         params: ReassembleParams<'a>,
     },
+
+    /// A method whose name isn't known ahead of time, such as a service
+    /// extension registered by an isolate or a call forwarded for a proxy
+    /// client. Serialized by [VMServiceMethod::to_json].
+    #[serde(skip_serializing)]
+    Other {
+        id: i32,
+        name: &'a str,
+        params: serde_json::Map<String, serde_json::Value>,
+    },
+}
+
+impl<'a> From<Request> for VMServiceMethod<'a> {
+    fn from(request: Request) -> Self {
+        VMServiceMethod::Other {
+            id: request.id,
+            name: request.method,
+            params: request.params,
+        }
+    }
 }
 
 impl<'a> VMServiceMethod<'a> {
+    fn to_json(&self) -> String {
+        match self {
+            VMServiceMethod::Other { id, name, params } => {
+                serde_json::json!({"method": name, "id": id, "params": params}).to_string()
+            }
+            _ => serde_json::to_string(self).unwrap(),
+        }
+    }
+
     fn id(&self) -> i32 {
         match self {
-            VMServiceMethod::GetVM { id } => *id,
-            VMServiceMethod::GetVersion { id } => *id,
+            VMServiceMethod::Other { id, .. } => *id,
             VMServiceMethod::ListViews { id } => *id,
-            VMServiceMethod::ReloadSources { id, .. } => *id,
-            VMServiceMethod::RegisterService { id, .. } => *id,
+            VMServiceMethod::Screenshot { id } => *id,
+            VMServiceMethod::PrivateReloadSources { id, .. } => *id,
+            VMServiceMethod::PrivateRegisterService { id, .. } => *id,
             VMServiceMethod::CreateDevFS { id, .. } => *id,
             VMServiceMethod::WriteDevFSFile { id, .. } => *id,
             VMServiceMethod::DeleteDevFS { id, .. } => *id,
             VMServiceMethod::ListDevFS { id, .. } => *id,
+            VMServiceMethod::ListDevFSFiles { id, .. } => *id,
+            VMServiceMethod::ReadDevFSFile { id, .. } => *id,
             VMServiceMethod::Reassemble { id, .. } => *id,
         }
     }
@@ -209,12 +653,20 @@ pub struct WriteDevFSParams<'a> {
     #[serde(rename = "fsName")]
     fs_name: &'a str,
 
-    uri: &'a str,
+    path: &'a str,
 
     #[serde(rename = "fileContents")]
     file_contents: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct DevFSFileParams<'a> {
+    #[serde(rename = "fsName")]
+    fs_name: &'a str,
+
+    path: &'a str,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ReloadSourcesParams<'a> {
     #[serde(rename = "isolateId")]
@@ -241,10 +693,45 @@ pub struct CreateDevFSParams<'a> {
     fs_name: &'a str,
 }
 
+/// Parameters of the compileExpression request the vm service sends to the
+/// client which registered the compileExpression service.
+#[derive(Debug, Deserialize)]
+pub struct CompileExpressionParams {
+    pub expression: String,
+
+    /// Names of the local variables in scope.
+    #[serde(default)]
+    pub definitions: Vec<String>,
+
+    /// Names of the type parameters in scope.
+    #[serde(rename = "typeDefinitions", default)]
+    pub type_definitions: Vec<String>,
+
+    #[serde(rename = "libraryUri")]
+    pub library_uri: String,
+
+    /// The enclosing class, if any.
+    pub klass: Option<String>,
+
+    #[serde(rename = "isStatic", default)]
+    pub is_static: bool,
+}
+
+/// Handles a request sent by the vm service to this client, such as a call to
+/// a service registered with registerService.
+///
+/// Receives the request params and returns either a result or an error object.
+pub type RequestHandler = sync::Arc<
+    dyn Fn(&serde_json::Value) -> Result<serde_json::Value, serde_json::Value> + Send + Sync,
+>;
+
 /// The VMService is a handle to the dart vm service.
+///
+/// Clones share the connection.
+#[derive(Clone)]
 pub struct VMService {
     coordinator: sync::Arc<sync::Mutex<VMServiceCoordinator>>,
-    http_address: String,
+    uploader: Uploader,
 }
 
 impl VMService {
@@ -252,52 +739,180 @@ impl VMService {
     ///
     /// If successful, returns a handle to a [VMService].
     pub fn connect(addr: &str) -> Result<Self, ()> {
+        VMService::connect_with(addr, &ConnectOptions::default())
+    }
+
+    /// Connect to a vmservice at `addr`, which may be a `wss://` address of
+    /// a TLS terminating proxy, with the given certificates and headers.
+    pub fn connect_with(addr: &str, options: &ConnectOptions) -> Result<Self, ()> {
+        let coordinator = if addr.starts_with("wss://") {
+            VMService::connect_tls(addr, options)?
+        } else {
+            VMService::connect_plain(addr, options)?
+        };
+
+        // We expect a url of the form http://address:port/ws, or https:// behind TLS.
+        let mut http_address: String = addr
+            .replacen("wss://", "https://", 1)
+            .replacen("ws://", "http://", 1);
+        if http_address.ends_with("ws") {
+            http_address.truncate(http_address.len() - 2);
+        }
+
+        let vmservice = VMService {
+            coordinator: coordinator,
+            uploader: Uploader::new(&http_address, options)?,
+        };
+        Ok(vmservice)
+    }
+
+    fn connect_plain(
+        addr: &str,
+        options: &ConnectOptions,
+    ) -> Result<sync::Arc<sync::Mutex<VMServiceCoordinator>>, ()> {
         // Connect to websocket vm service.
         let client = websocket::ClientBuilder::new(addr)
-            .unwrap()
+            .map_err(|err| println!("Invalid vm service address {}: {}", addr, err))?
+            .custom_headers(&options.websocket_headers())
             .connect_insecure()
-            .unwrap();
+            .map_err(|err| println!("Failed to connect to {}: {}", addr, err))?;
 
         // There is a way to do this without a mutex but I haven't spent time refactoing it.
         let (mut receiver, sender) = client.split().unwrap();
-        let coordinator = sync::Arc::new(sync::Mutex::new(VMServiceCoordinator::new(sender)));
+        let coordinator = sync::Arc::new(sync::Mutex::new(VMServiceCoordinator::new(
+            Outgoing::Plain(sender),
+        )));
         let coordinator_ = sync::Arc::clone(&coordinator);
 
-        // We expect a url of the form http://address:port/ws
-        let mut http_address: String = addr.replace("ws://", "http://");
-        http_address.truncate(http_address.len() - 2);
-
         // thread responsible for receiving messages from the vm service and
         // returning them to the main thread.
         std::thread::spawn(move || {
             for message in receiver.incoming_messages() {
-                let message = message.expect("Failed to unwrap incoming message");
-                let response: Response = match message {
-                    websocket::OwnedMessage::Text(data) => serde_json::from_str(&data).unwrap(),
-                    websocket::OwnedMessage::Ping(data) => serde_json::from_slice(&data).unwrap(),
-                    websocket::OwnedMessage::Binary(data) => serde_json::from_slice(&data).unwrap(),
-                    websocket::OwnedMessage::Pong(_) => {
-                        continue;
-                    }
-                    websocket::OwnedMessage::Close(_) => {
-                        continue;
+                let message = match message {
+                    Ok(message) => message,
+                    Err(err) => {
+                        println!("Lost connection to the vm service: {}", err);
+                        break;
                     }
                 };
-                let mut coordinator_ = coordinator.lock().unwrap();
-                coordinator_.complete_job(response);
+                if !route(&coordinator, message) {
+                    break;
+                }
             }
+            coordinator.lock().unwrap().close();
         });
-        let vmservice = VMService {
-            coordinator: coordinator_,
-            http_address: http_address,
-        };
-        Ok(vmservice)
+        Ok(coordinator_)
+    }
+
+    /// A TLS stream cannot be split into a reader and a writer, so TLS
+    /// connections are driven by the async client on their own thread, with
+    /// outgoing messages passed to it over a channel.
+    fn connect_tls(
+        addr: &str,
+        options: &ConnectOptions,
+    ) -> Result<sync::Arc<sync::Mutex<VMServiceCoordinator>>, ()> {
+        let connector = options.tls_connector()?;
+        let headers = options.websocket_headers();
+        let (outgoing, outgoing_receiver) = futures::sync::mpsc::unbounded();
+        let coordinator = sync::Arc::new(sync::Mutex::new(VMServiceCoordinator::new(
+            Outgoing::Tls(outgoing),
+        )));
+        let coordinator_ = sync::Arc::clone(&coordinator);
+        let (ready, ready_receiver) = mpsc::channel();
+        let address = addr.to_string();
+        std::thread::spawn(move || {
+            let mut runtime = match tokio::runtime::current_thread::Runtime::new() {
+                Ok(runtime) => runtime,
+                Err(err) => {
+                    let _ = ready.send(Err(err.to_string()));
+                    return;
+                }
+            };
+            let builder = match websocket::ClientBuilder::new(&address) {
+                Ok(builder) => builder.custom_headers(&headers),
+                Err(err) => {
+                    let _ = ready.send(Err(err.to_string()));
+                    return;
+                }
+            };
+            let client = match runtime.block_on(builder.async_connect_secure(Some(connector))) {
+                Ok((client, _)) => client,
+                Err(err) => {
+                    let _ = ready.send(Err(err.to_string()));
+                    return;
+                }
+            };
+            let _ = ready.send(Ok(()));
+            let (sink, stream) = client.split();
+            let writes = outgoing_receiver
+                .map_err(|()| websocket::WebSocketError::ProtocolError("outgoing channel failed"))
+                .forward(sink)
+                .map(|_| ());
+            let router = sync::Arc::clone(&coordinator);
+            let reads = stream.for_each(move |message| {
+                if route(&router, message) {
+                    Ok(())
+                } else {
+                    Err(websocket::WebSocketError::NoDataAvailable)
+                }
+            });
+            match runtime.block_on(reads.select(writes)) {
+                // Closed by the vm service, already reported by the router.
+                Ok(_) | Err((websocket::WebSocketError::NoDataAvailable, _)) => {}
+                Err((err, _)) => println!("Lost connection to the vm service: {}", err),
+            }
+            coordinator.lock().unwrap().close();
+        });
+        match ready_receiver.recv() {
+            Ok(Ok(())) => Ok(coordinator_),
+            Ok(Err(err)) => {
+                println!("Failed to connect to {}: {}", addr, err);
+                Err(())
+            }
+            Err(_) => Err(()),
+        }
+    }
+
+    /// Ask the vm service for its protocol version and record it in
+    /// `methods`, so later requests use the methods it supports.
+    ///
+    /// Fails if the version is outside the range this client understands.
+    pub fn negotiate_version(&mut self, methods: &mut VMServiceMethods) -> Result<Version, ()> {
+        let version: Version = self.call_method(&methods.get_version())?;
+        if (version.major, version.minor) < MIN_PROTOCOL_VERSION {
+            println!(
+                "The vm service speaks protocol {}.{}, but {}.{} or later is required. Please upgrade Flutter.",
+                version.major, version.minor, MIN_PROTOCOL_VERSION.0, MIN_PROTOCOL_VERSION.1
+            );
+            return Err(());
+        }
+        if version.major > MAX_PROTOCOL_MAJOR_VERSION {
+            println!(
+                "The vm service speaks protocol {}.{}, which is newer than the {}.x this tool supports. Please update this tool.",
+                version.major, version.minor, MAX_PROTOCOL_MAJOR_VERSION
+            );
+            return Err(());
+        }
+        methods.set_version(version.clone());
+        Ok(version)
     }
 
     /// Call a method on the vm service and block for the response.
     ///
     /// Requires an annotated type to infer the correct deserialziation.
     pub fn call_method<'a, T>(&mut self, method: &'a VMServiceMethod) -> Result<T, ()>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        self.try_call_method(method).map_err(|_| ())
+    }
+
+    /// Call a method on the vm service and block for the response, returning
+    /// the error object if the call fails.
+    pub fn try_call_method<'a, T>(
+        &mut self,
+        method: &'a VMServiceMethod,
+    ) -> Result<T, serde_json::Value>
     where
         T: serde::de::DeserializeOwned,
     {
@@ -305,12 +920,34 @@ impl VMService {
             let mut coordinator = self.coordinator.lock().unwrap();
             coordinator.post_job(method)
         };
-        match channel.recv().unwrap().result {
-            Some(result) => Ok(serde_json::from_value(result).unwrap()),
-            None => Err(()),
+        let response = channel.recv().map_err(|_| connection_closed())?;
+        match (response.result, response.error) {
+            (Some(result), _) => serde_json::from_value(result).map_err(|err| {
+                println!("Unexpected response from the vm service: {}", err);
+                serde_json::json!({"code": INTERNAL_ERROR, "message": err.to_string()})
+            }),
+            (None, Some(error)) => Err(error),
+            (None, None) => Err(serde_json::Value::Null),
         }
     }
 
+    /// Handle requests for `method` sent by the vm service to this client.
+    ///
+    /// Services must also be registered with the vm service via registerService.
+    pub fn register_handler(&mut self, method: &str, handler: RequestHandler) {
+        let mut coordinator = self.coordinator.lock().unwrap();
+        coordinator.handlers.insert(method.to_string(), handler);
+    }
+
+    /// Whether requests for `method` are already handled by this client.
+    pub fn has_handler(&self, method: &str) -> bool {
+        self.coordinator
+            .lock()
+            .unwrap()
+            .handlers
+            .contains_key(method)
+    }
+
     /// Send a notification to the vm service without awaiting a response.
     pub fn send_notification<'a>(
         &mut self,
@@ -320,88 +957,581 @@ impl VMService {
             let mut coordinator_ = self.coordinator.lock().unwrap();
             coordinator_.post_job(method)
         };
-        match channel.recv().unwrap().error {
+        match channel.recv().map_err(|_| connection_closed())?.error {
             None => Ok(()),
             Some(err) => Err(err),
         }
     }
 
-    pub fn send_unawaited<'a>(&mut self, method: &'a VMServiceMethod) {
-        let mut coordinator = self.coordinator.lock().unwrap();
-        coordinator.forget_job(method)
-    }
-
-    /// Writes a file to the vmservice devfs.
+    /// Forward events from the stream `stream_id` to `sender`.
     ///
-    /// In the case of a shared host vmservice, this could be replaced
-    /// with a request that sends that absolute filepath to the dill.
-    pub fn write_file<'a>(
+    /// The stream is only subscribed to on the vm service the first time it
+    /// is listened to, later listeners share the same subscription.
+    pub fn listen(
         &mut self,
-        content: &mut std::fs::File,
-        device_uri: &'a str,
-        fs_name: &'a str,
+        methods: &mut VMServiceMethods,
+        stream_id: &str,
+        sender: mpsc::Sender<Notification>,
+    ) -> Result<(), ()> {
+        self.add_listener(methods, stream_id, Listener::Typed(sender))
+    }
+
+    /// Forward events from the stream `stream_id` to `sender` as they were
+    /// sent, including those [Event] can't represent.
+    pub fn listen_raw(
+        &mut self,
+        methods: &mut VMServiceMethods,
+        stream_id: &str,
+        sender: mpsc::Sender<RawNotification>,
+    ) -> Result<(), ()> {
+        self.add_listener(methods, stream_id, Listener::Raw(sender))
+    }
+
+    fn add_listener(
+        &mut self,
+        methods: &mut VMServiceMethods,
+        stream_id: &str,
+        listener: Listener,
     ) -> Result<(), ()> {
-        let client = reqwest::Client::new();
-        let dev_fs_uri = base64::encode(device_uri.as_bytes());
-        let mut encoder = Encoder::new(vec![]).unwrap();
-        std::io::copy(content, &mut encoder).unwrap();
-        let encoded_data = encoder.finish().into_result().unwrap();
-
-        client
-            .put(&self.http_address)
-            .header("dev_fs_name", fs_name)
-            .header("dev_fs_uri_b64", dev_fs_uri)
-            .header("user-agent", "Dart/2.1 (dart:io)")
-            .body(encoded_data)
-            .send()
-            .unwrap();
+        let first = {
+            let mut coordinator = self.coordinator.lock().unwrap();
+            coordinator.add_listener(stream_id, Some(listener))
+        };
+        if !first {
+            return Ok(());
+        }
+        match self.send_notification(&methods.stream_listen(stream_id)) {
+            Ok(()) => Ok(()),
+            // Stream already subscribed, by a previous session on this connection.
+            Err(ref err) if err["code"] == STREAM_ALREADY_SUBSCRIBED => Ok(()),
+            Err(_) => Err(()),
+        }
+    }
+
+    /// Keep a registry of the vm's isolates, seeded with getVM and then
+    /// updated from Isolate stream events.
+    pub fn track_isolates(&mut self, methods: &mut VMServiceMethods) -> Result<(), ()> {
+        // Subscribe before seeding so no event is missed; an isolate seen in
+        // both is simply updated twice.
+        let first = {
+            let mut coordinator = self.coordinator.lock().unwrap();
+            coordinator.add_listener("Isolate", None)
+        };
+        if first {
+            match self.send_notification(&methods.stream_listen("Isolate")) {
+                Ok(()) => {}
+                Err(ref err) if err["code"] == STREAM_ALREADY_SUBSCRIBED => {}
+                Err(_) => return Err(()),
+            }
+        }
+        let vm: VM = self.call_method(&methods.get_vm())?;
+        for isolate_ref in vm.isolates {
+            // An isolate which exited after getVM answers with a Sentinel.
+            let response: serde_json::Value =
+                self.call_method(&methods.get_isolate(&isolate_ref.id))?;
+            if response["type"] == "Sentinel" {
+                continue;
+            }
+            let isolate: Isolate = serde_json::from_value(response).map_err(|err| {
+                println!("Unexpected response from the vm service: {}", err);
+            })?;
+            let mut coordinator = self.coordinator.lock().unwrap();
+            coordinator.seed_isolate(&isolate_ref, &isolate);
+        }
         Ok(())
     }
+
+    /// The isolate with `isolate_id`, if it is alive.
+    pub fn isolate(&self, isolate_id: &str) -> Option<IsolateState> {
+        let coordinator = self.coordinator.lock().unwrap();
+        coordinator.isolates.get(isolate_id).cloned()
+    }
+
+    /// The live isolates of the vm.
+    pub fn isolates(&self) -> Vec<IsolateState> {
+        let coordinator = self.coordinator.lock().unwrap();
+        coordinator.isolates.values().cloned().collect()
+    }
+
+    /// Record whether a debugger is attached to `isolate_id`.
+    pub fn set_debugged(&self, isolate_id: &str, debugged: bool) {
+        let mut coordinator = self.coordinator.lock().unwrap();
+        if debugged {
+            coordinator.debugged.insert(isolate_id.to_string());
+        } else {
+            coordinator.debugged.remove(isolate_id);
+        }
+    }
+
+    /// Whether a debugger is attached to `isolate_id`.
+    pub fn is_debugged(&self, isolate_id: &str) -> bool {
+        self.coordinator
+            .lock()
+            .unwrap()
+            .debugged
+            .contains(isolate_id)
+    }
+
+    /// Block until `isolate_id` has registered the service extension `rpc`,
+    /// returning false if it has not within `timeout`.
+    pub fn wait_for_extension(
+        &self,
+        isolate_id: &str,
+        rpc: &str,
+        timeout: std::time::Duration,
+    ) -> bool {
+        let start = std::time::Instant::now();
+        loop {
+            let registered = self
+                .isolate(isolate_id)
+                .map_or(false, |x| x.extension_rpcs.iter().any(|x| x == rpc));
+            if registered {
+                return true;
+            }
+            if start.elapsed() >= timeout {
+                return false;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+    }
+
+    pub fn send_unawaited<'a>(&mut self, method: &'a VMServiceMethod) {
+        let mut coordinator = self.coordinator.lock().unwrap();
+        coordinator.forget_job(method)
+    }
+
+    /// Call a service extension on an isolate and return its JSON result.
+    ///
+    /// Failures are printed along with the extension name.
+    pub fn call_extension(
+        &mut self,
+        methods: &mut VMServiceMethods,
+        method: &str,
+        isolate_id: &str,
+        args: &[(&str, &str)],
+    ) -> Result<serde_json::Value, ()> {
+        self.try_call_method(&methods.service_extension(method, isolate_id, args))
+            .map_err(|err| println!("{} failed: {}", method, err))
+    }
+
+    /// The uploader for the vm service's devfs.
+    pub fn uploader(&self) -> &Uploader {
+        &self.uploader
+    }
+
+    /// Writes a file to the vmservice devfs.
+    ///
+    /// In the case of a shared host vmservice, this could be replaced
+    /// with a request that sends that absolute filepath to the dill.
+    pub fn write_file<'a>(
+        &mut self,
+        path: &std::path::Path,
+        device_uri: &'a str,
+        fs_name: &'a str,
+    ) -> Result<UploadReport, ()> {
+        self.uploader.upload(path, device_uri, fs_name)
+    }
+}
+
+/// Deliver a frame from the vm service to its destination.
+///
+/// Returns false once the vm service has closed the connection.
+fn route(
+    coordinator: &sync::Arc<sync::Mutex<VMServiceCoordinator>>,
+    message: websocket::OwnedMessage,
+) -> bool {
+    match message {
+        websocket::OwnedMessage::Text(data) => route_text(coordinator, &data, None),
+        websocket::OwnedMessage::Binary(data) => match split_binary(&data) {
+            Some((metadata, payload)) => route_text(coordinator, metadata, Some(payload.to_vec())),
+            None => println!("Ignoring malformed binary message from the vm service."),
+        },
+        websocket::OwnedMessage::Ping(data) => {
+            let mut coordinator = coordinator.lock().unwrap();
+            let _ = coordinator
+                .sender
+                .send_message(&websocket::OwnedMessage::Pong(data));
+        }
+        websocket::OwnedMessage::Pong(_) => {}
+        websocket::OwnedMessage::Close(_) => {
+            println!("The vm service closed the connection.");
+            let mut coordinator = coordinator.lock().unwrap();
+            let _ = coordinator
+                .sender
+                .send_message(&websocket::OwnedMessage::Close(None));
+            return false;
+        }
+    }
+    true
+}
+
+/// Route a JSON-RPC message: a response to a pending call, a stream event, or
+/// a request to a service registered by this client. `data` is the payload of
+/// a binary stream event.
+fn route_text(
+    coordinator: &sync::Arc<sync::Mutex<VMServiceCoordinator>>,
+    text: &str,
+    data: Option<Vec<u8>>,
+) {
+    let value: serde_json::Value = match serde_json::from_str(text) {
+        Ok(value) => value,
+        Err(err) => {
+            println!("Ignoring malformed message from the vm service: {}", err);
+            return;
+        }
+    };
+    match (value["method"].as_str(), value.get("id")) {
+        (Some("streamNotify"), _) => coordinator
+            .lock()
+            .unwrap()
+            .notify(value["params"].clone(), data),
+        (Some(_), Some(_)) => VMServiceCoordinator::handle_request(coordinator, value),
+        (Some(method), None) => println!("Ignoring unexpected notification {}.", method),
+        (None, Some(_)) => match serde_json::from_value::<Response>(value) {
+            Ok(response) => coordinator.lock().unwrap().complete_job(response),
+            Err(err) => println!("Ignoring malformed response from the vm service: {}", err),
+        },
+        (None, None) => println!("Ignoring unrecognized message from the vm service."),
+    }
+}
+
+/// Split a binary stream event into its JSON metadata and its data.
+///
+/// The frame starts with the offset of the data as a little endian u32,
+/// followed by the metadata.
+fn split_binary(frame: &[u8]) -> Option<(&str, &[u8])> {
+    if frame.len() < 4 {
+        return None;
+    }
+    let offset = u32::from_le_bytes([frame[0], frame[1], frame[2], frame[3]]) as usize;
+    if offset < 4 || offset > frame.len() {
+        return None;
+    }
+    let metadata = std::str::from_utf8(&frame[4..offset]).ok()?;
+    Some((metadata, &frame[offset..]))
+}
+
+/// TLS and authentication settings for connecting to a vm service, used by
+/// both the websocket and the devfs uploader.
+#[derive(Debug, Clone, Default)]
+pub struct ConnectOptions {
+    /// PEM encoded certificates to trust in addition to the system roots.
+    pub ca_certificates: Vec<Vec<u8>>,
+
+    /// Accept any certificate, such as the self-signed one of a local proxy.
+    pub accept_invalid_certs: bool,
+
+    /// Headers sent with the websocket handshake and every upload, such as
+    /// `Authorization`.
+    pub headers: Vec<(String, String)>,
+}
+
+impl ConnectOptions {
+    fn websocket_headers(&self) -> websocket::header::Headers {
+        let mut headers = websocket::header::Headers::new();
+        for (name, value) in self.headers.iter() {
+            headers.set_raw(name.clone(), vec![value.clone().into_bytes()]);
+        }
+        headers
+    }
+
+    fn tls_connector(&self) -> Result<native_tls::TlsConnector, ()> {
+        let mut builder = native_tls::TlsConnector::builder();
+        for pem in self.ca_certificates.iter() {
+            let certificate = native_tls::Certificate::from_pem(pem)
+                .map_err(|err| println!("Invalid CA certificate: {}", err))?;
+            builder.add_root_certificate(certificate);
+        }
+        builder.danger_accept_invalid_certs(self.accept_invalid_certs);
+        builder
+            .build()
+            .map_err(|err| println!("Failed to configure TLS: {}", err))
+    }
+}
+
+/// The sending half of a vm service connection.
+enum Outgoing {
+    Plain(websocket::sender::Writer<std::net::TcpStream>),
+
+    /// Messages for the thread driving a TLS connection.
+    Tls(futures::sync::mpsc::UnboundedSender<websocket::OwnedMessage>),
+}
+
+impl Outgoing {
+    fn send_message(&mut self, message: &websocket::OwnedMessage) -> Result<(), ()> {
+        match self {
+            Outgoing::Plain(writer) => writer.send_message(message).map_err(|_| ()),
+            Outgoing::Tls(sender) => sender.unbounded_send(message.clone()).map_err(|_| ()),
+        }
+    }
+}
+
+/// Error code returned by streamListen when the stream is already subscribed.
+pub const STREAM_ALREADY_SUBSCRIBED: i64 = 103;
+
+/// Error code for requests to methods this client does not handle.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Error code for responses this client could not understand.
+const INTERNAL_ERROR: i64 = -32603;
+
+/// Error code for calls made after the connection closed.
+pub const CONNECTION_CLOSED: i64 = -32000;
+
+fn connection_closed() -> serde_json::Value {
+    serde_json::json!({
+        "code": CONNECTION_CLOSED,
+        "message": "The vm service connection is closed.",
+    })
 }
 
 struct VMServiceCoordinator {
     pending: HashMap<i32, VMServiceJob>,
-    sender: websocket::sender::Writer<std::net::TcpStream>,
+    listeners: HashMap<String, Vec<Listener>>,
+    handlers: HashMap<String, RequestHandler>,
+    isolates: HashMap<String, IsolateState>,
+    sender: Outgoing,
+
+    /// Isolates whose IsolateExit event has arrived, which must not be
+    /// registered again by a late getIsolate response.
+    exited: HashSet<String>,
+
+    /// Isolates with a debugger attached, which handles their pauses.
+    debugged: HashSet<String>,
+
+    /// Set once the connection is gone, after which calls fail immediately.
+    closed: bool,
 }
 
 impl VMServiceCoordinator {
-    fn new(sender: websocket::sender::Writer<std::net::TcpStream>) -> Self {
+    fn new(sender: Outgoing) -> Self {
         VMServiceCoordinator {
             pending: HashMap::new(),
+            listeners: HashMap::new(),
+            handlers: HashMap::new(),
+            isolates: HashMap::new(),
             sender: sender,
+            exited: HashSet::new(),
+            debugged: HashSet::new(),
+            closed: false,
         }
     }
 
+    /// Answer a request from the vm service.
+    ///
+    /// Handlers run on their own thread since they may block for a while (for
+    /// example compiling an expression) and the vm service may send further
+    /// messages in the meantime.
+    fn handle_request(coordinator: &sync::Arc<sync::Mutex<Self>>, request: serde_json::Value) {
+        let method = request["method"].as_str().unwrap_or_default().to_string();
+        let handler = coordinator.lock().unwrap().handlers.get(&method).cloned();
+        let coordinator = sync::Arc::clone(coordinator);
+        std::thread::spawn(move || {
+            let result = match handler {
+                Some(handler) => handler(&request["params"]),
+                None => Err(serde_json::json!({
+                    "code": METHOD_NOT_FOUND,
+                    "message": format!("Method not found: {}", method),
+                })),
+            };
+            let response = match result {
+                Ok(result) => serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "result": result,
+                }),
+                Err(error) => serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "error": error,
+                }),
+            };
+            let _ = coordinator
+                .lock()
+                .unwrap()
+                .sender
+                .send_message(&websocket::OwnedMessage::Text(response.to_string()));
+        });
+    }
+
+    /// Returns true if `stream_id` had not been listened to before.
+    ///
+    /// Without a `listener` the stream is only marked as subscribed.
+    fn add_listener(&mut self, stream_id: &str, listener: Option<Listener>) -> bool {
+        let first = !self.listeners.contains_key(stream_id);
+        let listeners = self
+            .listeners
+            .entry(stream_id.to_string())
+            .or_insert_with(Vec::new);
+        listeners.extend(listener);
+        first
+    }
+
+    /// Deliver a stream event to every listener, dropping any which have gone away.
+    ///
+    /// Raw listeners get every event, typed listeners only those which parse.
+    fn notify(&mut self, params: serde_json::Value, data: Option<Vec<u8>>) {
+        let stream_id = match params["streamId"].as_str() {
+            Some(stream_id) => stream_id.to_string(),
+            None => {
+                println!("Ignoring event without a stream from the vm service.");
+                return;
+            }
+        };
+        let notification = serde_json::from_value::<Notification>(params.clone()).map(|mut x| {
+            x.data = data.clone();
+            x
+        });
+        match notification {
+            Ok(ref notification) if stream_id == "Isolate" => {
+                self.update_isolates(&notification.event)
+            }
+            Ok(_) => {}
+            Err(ref err) => {
+                let typed = self
+                    .listeners
+                    .get(&stream_id)
+                    .map_or(false, |x| x.iter().any(|x| x.is_typed()));
+                if typed || stream_id == "Isolate" {
+                    println!("Ignoring malformed event from the vm service: {}", err);
+                }
+            }
+        }
+        let raw = RawNotification {
+            stream_id: stream_id.clone(),
+            params: params,
+            data: data,
+        };
+        if let Some(listeners) = self.listeners.get_mut(&stream_id) {
+            listeners.retain(|listener| match (listener, notification.as_ref()) {
+                (Listener::Typed(sender), Ok(notification)) => {
+                    sender.send(notification.clone()).is_ok()
+                }
+                (Listener::Typed(_), Err(_)) => true,
+                (Listener::Raw(sender), _) => sender.send(raw.clone()).is_ok(),
+            });
+        }
+    }
+
+    /// Keep the isolate registry in sync with an Isolate stream event.
+    fn update_isolates(&mut self, event: &Event) {
+        let isolate = match event.isolate.as_ref() {
+            Some(isolate) => isolate,
+            None => return,
+        };
+        if event.kind == EventKind::IsolateExit {
+            self.isolates.remove(&isolate.id);
+            self.exited.insert(isolate.id.clone());
+            return;
+        }
+        if self.exited.contains(&isolate.id) {
+            return;
+        }
+        let state = self
+            .isolates
+            .entry(isolate.id.clone())
+            .or_insert_with(|| IsolateState {
+                isolate: isolate.clone(),
+                runnable: false,
+                extension_rpcs: Vec::new(),
+            });
+        state.isolate = isolate.clone();
+        match event.kind {
+            EventKind::IsolateRunnable | EventKind::IsolateReload => {
+                state.runnable = true;
+            }
+            EventKind::ServiceExtensionAdded => {
+                if let Some(rpc) = event.extension_rpc.as_ref() {
+                    if !state.extension_rpcs.contains(rpc) {
+                        state.extension_rpcs.push(rpc.clone());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Register an isolate found by getVM, unless it has already exited.
+    fn seed_isolate(&mut self, isolate_ref: &IsolateRef, isolate: &Isolate) {
+        if self.exited.contains(&isolate_ref.id) {
+            return;
+        }
+        let state = self
+            .isolates
+            .entry(isolate_ref.id.clone())
+            .or_insert_with(|| IsolateState {
+                isolate: isolate_ref.clone(),
+                runnable: false,
+                extension_rpcs: Vec::new(),
+            });
+        state.runnable |= isolate.runnable;
+        for rpc in isolate.extension_rpcs.iter().flatten() {
+            if !state.extension_rpcs.contains(rpc) {
+                state.extension_rpcs.push(rpc.clone());
+            }
+        }
+    }
+
+    /// Send a call and return the channel its response arrives on. If the
+    /// connection is closed the channel is already disconnected.
     fn post_job(&mut self, method: &VMServiceMethod) -> mpsc::Receiver<Response> {
         let (sender, receiver) = mpsc::channel();
+        if self.closed {
+            return receiver;
+        }
         let id = method.id();
         let job = VMServiceJob {
             id: id,
             sender: sender,
         };
         self.pending.insert(id, job);
-        self.sender
-            .send_message(&websocket::OwnedMessage::Text(
-                serde_json::to_string(method).unwrap(),
-            ))
-            .unwrap();
+        let sent = self
+            .sender
+            .send_message(&websocket::OwnedMessage::Text(method.to_json()));
+        if sent.is_err() {
+            self.pending.remove(&id);
+        }
         receiver
     }
 
     fn forget_job(&mut self, method: &VMServiceMethod) {
-        self.sender
-            .send_message(&websocket::OwnedMessage::Text(
-                serde_json::to_string(method).unwrap(),
-            ))
-            .unwrap();
+        let _ = self
+            .sender
+            .send_message(&websocket::OwnedMessage::Text(method.to_json()));
     }
 
     fn complete_job(&mut self, response: Response) {
         match self.pending.remove(&response.id) {
-            Some(job) => job.sender.send(response).unwrap(),
-            None => {}
+            // The caller may have given up waiting.
+            Some(job) => {
+                let _ = job.sender.send(response);
+            }
+            None => println!("Ignoring response to unknown request {}.", response.id),
         };
     }
+
+    /// Fail every pending call and end every stream once the connection is
+    /// gone.
+    fn close(&mut self) {
+        self.closed = true;
+        self.pending.clear();
+        self.listeners.clear();
+    }
+}
+
+/// Where a stream's events are sent.
+enum Listener {
+    Typed(mpsc::Sender<Notification>),
+    Raw(mpsc::Sender<RawNotification>),
+}
+
+impl Listener {
+    fn is_typed(&self) -> bool {
+        match self {
+            Listener::Typed(_) => true,
+            Listener::Raw(_) => false,
+        }
+    }
 }
 
 struct VMServiceJob {
@@ -409,41 +1539,48 @@ struct VMServiceJob {
     sender: mpsc::Sender<Response>,
 }
 
-/// VM Service events
-
-#[derive(Debug, Copy, Clone)]
-enum VMServiceEvents {
-    VMUpdate,
-    IsolateStart,
-    IsolateRunnable,
-    IsolateExit,
-    IsolateUpdate,
-    IsolateReload,
-    IsolateSpawn,
-    ServiceExtensionAdded,
-    PauseStart,
-    PauseExit,
-    PauseBreakpoint,
-    PauseInterrupted,
-    PauseException,
-    PausePostRequest,
-    None,
-    Resume,
-    BreakpointAdded,
-    BreakpointResolved,
-    BreakpointRemoved,
-    Graph,
-    GC,
-    Inspect,
-    DebuggerSettingsUpdate,
-    ConnectionClosed,
-    Logging,
-    Extension,
-}
-
-/// VM Service objects
-///
-/// See https://github.com/dart-lang/sdk/blob/master/runtime/vm/service/service.md
+/// What is known about a live isolate.
+#[derive(Debug, Clone)]
+pub struct IsolateState {
+    pub isolate: IsolateRef,
+
+    /// Whether the isolate has loaded its libraries and can run code.
+    pub runnable: bool,
+
+    /// The service extensions the isolate has registered.
+    pub extension_rpcs: Vec<String>,
+}
+
+/// A `streamNotify` message from the vm service.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Notification {
+    #[serde(rename = "streamId")]
+    pub stream_id: String,
+
+    pub event: Event,
+
+    /// The data following the metadata of a binary event, such as a chunk of
+    /// a heap snapshot.
+    #[serde(skip)]
+    pub data: Option<Vec<u8>>,
+}
+
+/// A `streamNotify` message as it was sent, for passing the event on
+/// unchanged.
+#[derive(Debug, Clone)]
+pub struct RawNotification {
+    pub stream_id: String,
+
+    /// The params of the message, including event fields [Event] leaves out.
+    pub params: serde_json::Value,
+
+    /// The data following the metadata of a binary event.
+    pub data: Option<Vec<u8>>,
+}
+
+/// VM Service objects which the protocol specification leaves out, such as
+/// the results of private and flutter methods. The public ones are generated
+/// into protocol.rs.
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Response {
@@ -457,69 +1594,40 @@ pub struct RegisterResult {
     pub success: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct Version {
-    /// The major version number is incremented when the protocol is changed
-    /// in a potentially incompatible way.
-    pub major: i64,
-
-    /// The minor version number is incremented when the protocol is changed
-    /// in a backwards compatible way.
-    pub minor: i64,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct ReloadReport {
-    /// Whether the hot reload was successful.
-    pub success: bool,
-}
-
 #[derive(Deserialize, Debug)]
 pub struct DevFSListResult {
     #[serde(rename = "fsNames")]
     pub fs_names: Vec<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct VM {
-    /// Word length on target architecture (e.g. 32, 64).
-    #[serde(rename = "architectureBits")]
-    pub architecture_bits: i64,
-
-    /// The CPU we are generating code for.
-    #[serde(rename = "targetCPU")]
-    pub target_cpu: String,
-
-    /// The CPU we are actually running on.
-    #[serde(rename = "hostCPU")]
-    pub host_cpu: String,
-
-    /// The Dart VM version string.
-    pub version: String,
+#[derive(Deserialize, Debug)]
+pub struct DevFSFileList {
+    pub files: Vec<DevFSFile>,
+}
 
-    /// The process id for the VM.
-    pub pid: i64,
+#[derive(Deserialize, Debug)]
+pub struct DevFSFile {
+    /// The path of the file from the root of the devfs.
+    pub name: String,
 
-    /// The time that the VM started in milliseconds since the epoch.
-    ///
-    /// Suitable to pass to DateTime.fromMillisecondsSinceEpoch.
-    #[serde(rename = "startTime")]
-    pub start_time: i64,
+    pub size: i64,
 
-    /// A list of isolates running in the VM.
-    pub isolates: Vec<IsolateRef>,
+    /// Milliseconds since the epoch.
+    pub modified: i64,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct IsolateRef {
-    /// The id which is passed to the getIsolate RPC to load this isolate.
-    pub id: String,
-
-    /// A numeric id for this isolate, represented as a string. Unique.
-    pub number: String,
+#[derive(Deserialize, Debug)]
+pub struct DevFSFileContents {
+    /// The base64 encoded contents of the file.
+    #[serde(rename = "fileContents")]
+    pub file_contents: String,
+}
 
-    /// A name identifying this isolate. Not guaranteed to be unique.
-    pub name: String,
+impl DevFSFileContents {
+    pub fn decode(&self) -> Result<Vec<u8>, ()> {
+        base64::decode(&self.file_contents)
+            .map_err(|err| println!("Invalid file contents from the vm service: {}", err))
+    }
 }
 
 /// FlutterView has a slightly different isolate type.
@@ -536,144 +1644,294 @@ pub struct IsolateRef2 {
 }
 
 #[derive(Serialize, Deserialize, Debug)]
-pub struct Isolate {
-    /// The id which is passed to the getIsolate RPC to reload this
-    /// isolate.
-    pub id: String,
-
-    /// A numeric id for this isolate, represented as a string. Unique.
-    pub number: i32,
-
-    /// A name identifying this isolate. Not guaranteed to be unique.
-    pub name: String,
-
-    /// The time that the VM started in milliseconds since the epoch.
-    ///
-    /// Suitable to pass to DateTime.fromMillisecondsSinceEpoch.
-    #[serde(rename = "startTime")]
-    pub start_time: i64,
-
-    /// Is the isolate in a runnable state?
-    pub runnable: bool,
-
-    /// The number of live ports for this isolate.
-    #[serde(rename = "livePorts")]
-    pub live_ports: i64,
-
-    /// Will this isolate pause when exiting?
-    #[serde(rename = "pauseOnExit")]
-    pub pause_on_exit: bool,
-
-    /// The last pause event delivered to the isolate. If the isolate is
-    /// running, this will be a resume event.
-    #[serde(rename = "pauseEvent")]
-    pub pause_event: Event,
-
-    /// The root library for this isolate.
-    ///
-    /// Guaranteed to be initialized when the IsolateRunnable event fires.
-    #[serde(rename = "rootLib")]
-    pub root_lib: Option<LibraryRef>,
-
-    /// A list of all libraries for this isolate.
-    ///
-    /// Guaranteed to be initialized when the IsolateRunnable event fires.
-    pub libraries: Vec<LibraryRef>,
-
-    /// A list of all breakpoints for this isolate.
-    pub breakpoints: Vec<Breakpoint>,
-
-    /// The error that is causing this isolate to exit, if applicable.
-    pub error: Option<IsolateError>,
-
-    /// The current pause on exception mode for this isolate.
-    #[serde(rename = "exceptionPauseMode")]
-    pub exception_pause_mode: ExceptionPauseMode,
-
-    /// The list of service extension RPCs that are registered for this isolate,
-    /// if any.
-    #[serde(rename = "extensionRPCs")]
-    pub extension_rpcs: Option<Vec<String>>,
+pub struct FlutterViewList {
+    pub views: Vec<FlutterView>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct LibraryRef {
-    /// The name of this library.
-    pub name: String,
+#[derive(Deserialize, Debug)]
+pub struct Screenshot {
+    /// The base64 encoded PNG.
+    pub screenshot: String,
+}
 
-    /// The uri of this library.
+#[derive(Deserialize, Debug)]
+pub struct DevFSCreatedResponse {
+    /// The root uri of the created devfs.
     pub uri: String,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
-pub struct Library {
-    /// The name of this library.
-    pub name: String,
-
-    /// The uri of this library.
-    pub uri: String,
-
-    /// Is this library debuggable? Default true.
-    pub debuggable: bool,
-
-    /// A list of the imports for this library.
-    pub dependencies: Vec<LibraryDependency>,
+pub struct FlutterView {
+    pub id: String,
 
-    /// A list of the scripts which constitute this library.
-    pub scripts: Vec<ScriptRef>,
+    /// The main UI isolate.
+    pub isolate: IsolateRef2,
+}
 
-    /// A list of the top-level variables in this library.
-    pub variables: Vec<FieldRef>,
+impl Script {
+    /// Find the line containing `token_pos`.
+    pub fn line_of(&self, token_pos: i64) -> Option<i64> {
+        for row in self.token_pos_table.iter().flatten() {
+            let (line, pairs) = match row.split_first() {
+                Some(split) => split,
+                None => continue,
+            };
+            if pairs.chunks(2).any(|pair| pair[0] == token_pos) {
+                return Some(*line);
+            }
+        }
+        None
+    }
+}
 
-    /// A list of the top-level functions in this library.
-    pub functions: Vec<FunctionRef>,
+impl ProfileFunction {
+    /// The function name, qualified by its class if it has one.
+    pub fn display_name(&self) -> String {
+        let name = self.function["name"].as_str().unwrap_or("<unknown>");
+        match (
+            self.function["owner"]["type"].as_str(),
+            self.function["owner"]["name"].as_str(),
+        ) {
+            (Some("@Class"), Some(owner)) => format!("{}.{}", owner, name),
+            _ => name.to_string(),
+        }
+    }
+}
 
-    /// A list of all classes in this library.
-    pub classes: Vec<ClassRef>,
+impl Breakpoint {
+    /// Where the breakpoint is, read into the shape resolved and unresolved
+    /// locations share.
+    pub fn source_location(&self) -> Option<UnresolvedSourceLocation> {
+        serde_json::from_value(self.location.clone()).ok()
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct FlutterViewList {
-    pub views: Vec<FlutterView>,
+impl From<SourceLocation> for UnresolvedSourceLocation {
+    fn from(location: SourceLocation) -> Self {
+        UnresolvedSourceLocation {
+            type_: location.type_,
+            script: Some(location.script),
+            script_uri: None,
+            token_pos: Some(location.token_pos),
+            line: location.line,
+            column: location.column,
+        }
+    }
 }
 
-#[derive(Deserialize, Debug)]
-pub struct DevFSCreatedResponse {
-    /// The root uri of the created devfs.
-    pub uri: String,
+impl BoundField {
+    /// The field name, with positional record fields written as `$1`.
+    pub fn name(&self) -> String {
+        match (self.decl.as_ref(), self.name.as_ref()) {
+            (Some(decl), _) => decl.name.clone(),
+            (None, Some(serde_json::Value::String(name))) => name.clone(),
+            (None, Some(position)) => format!("${}", position),
+            (None, None) => String::from("<field>"),
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct FlutterView {
-    pub id: String,
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    /// The main UI isolate.
-    pub isolate: IsolateRef2,
-}
+    fn json(method: &VMServiceMethod) -> serde_json::Value {
+        serde_json::from_str(&method.to_json()).unwrap()
+    }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct LibraryDependency {}
+    fn version(major: i64, minor: i64) -> Version {
+        Version {
+            type_: String::from("Version"),
+            major: major,
+            minor: minor,
+        }
+    }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct ScriptRef {}
+    #[test]
+    fn generated_requests_share_ids() {
+        let mut methods = VMServiceMethods::new();
+        let mut clone = methods.clone();
+        assert_eq!(json(&methods.get_vm())["id"], 0);
+        assert_eq!(json(&clone.list_views())["id"], 1);
+        assert_eq!(json(&methods.get_version())["id"], 2);
+    }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct FieldRef {}
+    #[test]
+    fn optional_params_are_left_out() {
+        let mut methods = VMServiceMethods::new();
+        assert_eq!(
+            json(&methods.resume("isolates/1", None, None)),
+            serde_json::json!({"id": 0, "method": "resume", "params": {"isolateId": "isolates/1"}})
+        );
+        assert_eq!(
+            json(&methods.resume("isolates/1", Some(StepOption::Rewind), Some(2)))["params"],
+            serde_json::json!({"isolateId": "isolates/1", "step": "Rewind", "frameIndex": 2})
+        );
+    }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct FunctionRef {}
+    #[test]
+    fn line_of_skips_empty_rows() {
+        let script: Script = serde_json::from_value(serde_json::json!({
+            "type": "Script",
+            "id": "scripts/1",
+            "uri": "package:app/main.dart",
+            "library": {"type": "@Library", "id": "libraries/1", "name": "", "uri": "package:app/main.dart"},
+            "tokenPosTable": [[], [3, 10, 1, 14, 5], [4], [5, 20, 3]],
+        }))
+        .unwrap();
+        assert_eq!(script.line_of(14), Some(3));
+        assert_eq!(script.line_of(20), Some(5));
+        assert_eq!(script.line_of(1), None);
+        assert_eq!(script.line_of(99), None);
+    }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct ClassRef {}
+    #[test]
+    fn private_methods_until_version_is_known() {
+        let mut methods = VMServiceMethods::new();
+        assert!(!methods.supports(3, 0));
+        let reload = json(&methods.reload_sources("isolates/1", false, "lib.dill", ".packages"));
+        assert_eq!(reload["method"], "_reloadSources");
+        assert_eq!(
+            json(&methods.register_service("s", "a"))["method"],
+            "_registerService"
+        );
+        assert!(methods.get_isolate_group("isolateGroups/1").is_none());
+
+        methods.set_version(version(3, 38));
+        assert!(methods.supports(3, 22));
+        assert!(!methods.supports(3, 39));
+        let reload = json(&methods.reload_sources("isolates/1", false, "lib.dill", ".packages"));
+        assert_eq!(reload["method"], "reloadSources");
+        assert_eq!(
+            reload["params"],
+            serde_json::json!({
+                "isolateId": "isolates/1",
+                "pause": false,
+                "rootLibUri": "lib.dill",
+                "packagesUri": ".packages",
+            })
+        );
+        assert_eq!(
+            json(&methods.register_service("s", "a"))["method"],
+            "registerService"
+        );
+        assert!(methods.get_isolate_group("isolateGroups/1").is_some());
+    }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct ExceptionPauseMode {}
+    #[test]
+    fn pause_mode_follows_version() {
+        let mut methods = VMServiceMethods::new();
+        methods.set_version(version(3, 52));
+        let old = json(&methods.set_exception_pause_mode("isolates/1", ExceptionPauseMode::All));
+        assert_eq!(old["method"], "setExceptionPauseMode");
+        assert_eq!(old["params"]["mode"], "All");
+
+        methods.set_version(version(3, 53));
+        let new = json(&methods.set_exception_pause_mode("isolates/1", ExceptionPauseMode::All));
+        assert_eq!(new["method"], "setIsolatePauseMode");
+        assert_eq!(new["params"]["exceptionPauseMode"], "All");
+    }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct Breakpoint {}
+    #[test]
+    fn splits_binary_events() {
+        let metadata = r#"{"jsonrpc":"2.0","method":"streamNotify"}"#;
+        let mut frame = ((metadata.len() + 4) as u32).to_le_bytes().to_vec();
+        frame.extend_from_slice(metadata.as_bytes());
+        frame.extend_from_slice(&[1, 2, 3]);
+        assert_eq!(split_binary(&frame), Some((metadata, &[1u8, 2, 3][..])));
+
+        // Data may be empty.
+        let end = frame.len() - 3;
+        frame.truncate(end);
+        assert_eq!(split_binary(&frame), Some((metadata, &[][..])));
+    }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct Event {}
+    #[test]
+    fn rejects_malformed_binary_events() {
+        assert_eq!(split_binary(&[]), None);
+        assert_eq!(split_binary(&[4, 0, 0]), None);
+        // The offset points inside the header or past the end.
+        assert_eq!(split_binary(&[3, 0, 0, 0, b'{']), None);
+        assert_eq!(split_binary(&[9, 0, 0, 0, b'{']), None);
+        // The metadata is not utf8.
+        assert_eq!(split_binary(&[5, 0, 0, 0, 0xff]), None);
+    }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct IsolateError {}
+    #[test]
+    fn raw_listeners_get_every_event() {
+        let (outgoing, _) = futures::sync::mpsc::unbounded();
+        let mut coordinator = VMServiceCoordinator::new(Outgoing::Tls(outgoing));
+        let (typed, typed_events) = mpsc::channel();
+        let (raw, raw_events) = mpsc::channel();
+        assert!(coordinator.add_listener("Extension", Some(Listener::Typed(typed))));
+        assert!(!coordinator.add_listener("Extension", Some(Listener::Raw(raw))));
+
+        let params = serde_json::json!({
+            "streamId": "Extension",
+            "event": {"type": "Event", "kind": "Extension", "timestamp": 1, "unmodelled": true},
+        });
+        coordinator.notify(params.clone(), Some(vec![1, 2]));
+        let event = typed_events.try_recv().unwrap();
+        assert_eq!(event.event.kind, EventKind::Extension);
+        assert_eq!(event.data, Some(vec![1, 2]));
+        let event = raw_events.try_recv().unwrap();
+        assert_eq!(event.params, params);
+        assert_eq!(event.data, Some(vec![1, 2]));
+
+        // Only raw listeners get events which don't parse.
+        let params = serde_json::json!({"streamId": "Extension", "event": {"kind": 5}});
+        coordinator.notify(params.clone(), None);
+        assert!(typed_events.try_recv().is_err());
+        assert_eq!(raw_events.try_recv().unwrap().params, params);
+    }
+
+    #[test]
+    fn exited_isolates_are_not_registered_again() {
+        let (outgoing, _) = futures::sync::mpsc::unbounded();
+        let mut coordinator = VMServiceCoordinator::new(Outgoing::Tls(outgoing));
+        let isolate_ref = serde_json::json!({
+            "type": "@Isolate",
+            "id": "isolates/1",
+            "number": "1",
+            "name": "main",
+            "isSystemIsolate": false,
+            "isolateGroupId": "isolateGroups/1",
+        });
+        let isolate: Isolate = serde_json::from_value(serde_json::json!({
+            "type": "Isolate",
+            "id": "isolates/1",
+            "number": "1",
+            "name": "main",
+            "isSystemIsolate": false,
+            "isolateGroupId": "isolateGroups/1",
+            "isolateFlags": [],
+            "startTime": 0,
+            "runnable": true,
+            "livePorts": 0,
+            "pauseOnExit": false,
+            "pauseEvent": {"type": "Event", "kind": "Resume", "timestamp": 0},
+            "libraries": [],
+            "breakpoints": [],
+            "exceptionPauseMode": "None",
+        }))
+        .unwrap();
+        let event = |kind: &str| {
+            serde_json::json!({
+                "streamId": "Isolate",
+                "event": {"type": "Event", "kind": kind, "timestamp": 0, "isolate": isolate_ref},
+            })
+        };
+        let isolate_ref: IsolateRef = serde_json::from_value(isolate_ref.clone()).unwrap();
+
+        coordinator.notify(event("IsolateRunnable"), None);
+        assert!(coordinator.isolates["isolates/1"].runnable);
+        coordinator.notify(event("IsolateExit"), None);
+        assert!(coordinator.isolates.is_empty());
+
+        // A getVM sent before the exit still lists the isolate, and late
+        // events for it may follow.
+        coordinator.seed_isolate(&isolate_ref, &isolate);
+        coordinator.notify(event("ServiceExtensionAdded"), None);
+        assert!(coordinator.isolates.is_empty());
+    }
+}
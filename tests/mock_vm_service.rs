@@ -0,0 +1,48 @@
+//! Runs the tool against the canned mock vm service.
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+
+#[test]
+fn screenshots_the_mock_vm_service() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|x| x.local_addr())
+        .unwrap()
+        .port();
+    let mut mock = Command::new(env!("CARGO_BIN_EXE_mock_vm_service"))
+        .arg(port.to_string())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // The mock prints a line once it is listening.
+    let mut line = String::new();
+    BufReader::new(mock.stdout.take().unwrap())
+        .read_line(&mut line)
+        .unwrap();
+
+    let dir =
+        std::env::temp_dir().join(format!("flutter_tools_{}_screenshots", std::process::id()));
+    let output = Command::new(env!("CARGO_BIN_EXE_flutter_tools"))
+        .args(&["screenshot", "--debug-port", &port.to_string(), "--output"])
+        .arg(&dir)
+        .output();
+    let _ = mock.kill();
+    let output = output.unwrap();
+
+    let screenshots: Vec<std::path::PathBuf> = std::fs::read_dir(&dir)
+        .map(|x| x.filter_map(Result::ok).map(|x| x.path()).collect())
+        .unwrap_or_default();
+    let _ = std::fs::remove_dir_all(&dir);
+    assert_eq!(
+        screenshots.len(),
+        1,
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    let name = screenshots[0]
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .into_owned();
+    assert!(name.starts_with("screenshot-flutterView-0x1-"), "{}", name);
+    assert!(name.ends_with(".png"), "{}", name);
+}